@@ -0,0 +1,25 @@
+use std::{env, error::Error, path::PathBuf};
+
+/// The root of the protobuf definitions served by the nodes.
+const PROTO_ROOT: &str = "../nilvm/libs/node-api/proto";
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let value_proto = PathBuf::from(PROTO_ROOT).join("nillion/values/v1/value.proto");
+    println!("cargo:rerun-if-changed={}", value_proto.display());
+
+    // Use a vendored protoc so building doesn't depend on one being installed.
+    env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    prost_build::Config::new()
+        // Box every recursive field explicitly so the generated types don't depend on prost's heuristics.
+        .boxed(".nillion.values.v1.value.Value.value.array")
+        .boxed(".nillion.values.v1.value.Value.value.tuple")
+        .boxed(".nillion.values.v1.value.Tuple.left")
+        .boxed(".nillion.values.v1.value.Tuple.right")
+        .boxed(".nillion.values.v1.value.ValueType.value_type.array")
+        .boxed(".nillion.values.v1.value.ValueType.value_type.tuple")
+        .boxed(".nillion.values.v1.value.ArrayType.inner_type")
+        .boxed(".nillion.values.v1.value.TupleType.left")
+        .boxed(".nillion.values.v1.value.TupleType.right")
+        .compile_protos(&[value_proto], &[PathBuf::from(PROTO_ROOT), protoc_bin_vendored::include_path()?])?;
+    Ok(())
+}
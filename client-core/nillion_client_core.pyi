@@ -1,4 +1,18 @@
-from typing import Dict, List, Mapping, Union, Tuple
+import os
+from decimal import Decimal
+from fractions import Fraction
+from typing import (
+    Any,
+    BinaryIO,
+    Callable,
+    Dict,
+    Iterator,
+    List,
+    Mapping,
+    Optional,
+    Union,
+    overload,
+)
 
 NadaValue = Union[
     SecretUnsignedInteger,
@@ -18,49 +32,251 @@ NadaValue = Union[
     EddsaPublicKey,
     EddsaSignature,
     EddsaMessage,
+    Tuple,
+    NTuple,
+    Object,
+    SecretRational,
+    Rational,
 ]
 
 class SecretUnsignedInteger:
     """Encodes a secret as an unsigned integer."""
 
-    value: int
-
-    def __init__(self, value: int) -> None: ...
+    @property
+    def value(self) -> int: ...
+    @property
+    def prime_bits(self) -> Optional[int]:
+        """The size in bits of the safe prime chosen for this value, if any."""
+    def __init__(self, value: int, prime_bits: Optional[int] = None) -> None: ...
     def __eq__(self, other: object) -> bool: ...
     def __repr__(self) -> str: ...
+    def reveal(self) -> str:
+        """Returns the representation of this value including its secrets. The default representation redacts them."""
+    def __add__(self, other: Union[SecretUnsignedInteger, UnsignedInteger, int]) -> SecretUnsignedInteger: ...
+    def __radd__(self, other: Union[SecretUnsignedInteger, UnsignedInteger, int]) -> SecretUnsignedInteger: ...
+    def __sub__(self, other: Union[SecretUnsignedInteger, UnsignedInteger, int]) -> SecretUnsignedInteger: ...
+    def __rsub__(self, other: Union[SecretUnsignedInteger, UnsignedInteger, int]) -> SecretUnsignedInteger: ...
+    def __mul__(self, other: Union[SecretUnsignedInteger, UnsignedInteger, int]) -> SecretUnsignedInteger: ...
+    def __rmul__(self, other: Union[SecretUnsignedInteger, UnsignedInteger, int]) -> SecretUnsignedInteger: ...
+    def __floordiv__(self, other: Union[SecretUnsignedInteger, UnsignedInteger, int]) -> SecretUnsignedInteger: ...
+    def __rfloordiv__(self, other: Union[SecretUnsignedInteger, UnsignedInteger, int]) -> SecretUnsignedInteger: ...
+    def __mod__(self, other: Union[SecretUnsignedInteger, UnsignedInteger, int]) -> SecretUnsignedInteger: ...
+    def __rmod__(self, other: Union[SecretUnsignedInteger, UnsignedInteger, int]) -> SecretUnsignedInteger: ...
+    def __lt__(self, other: SecretUnsignedInteger) -> bool: ...
+    def __le__(self, other: SecretUnsignedInteger) -> bool: ...
+    def __gt__(self, other: SecretUnsignedInteger) -> bool: ...
+    def __ge__(self, other: SecretUnsignedInteger) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __int__(self) -> int: ...
+    def __index__(self) -> int: ...
 
 class SecretInteger:
     """Encodes a secret as an integer."""
 
-    value: int
-
-    def __init__(self, value: int) -> None: ...
+    @property
+    def value(self) -> int: ...
+    @property
+    def prime_bits(self) -> Optional[int]:
+        """The size in bits of the safe prime chosen for this value, if any."""
+    def __init__(self, value: int, prime_bits: Optional[int] = None) -> None: ...
     def __eq__(self, other: object) -> bool: ...
     def __repr__(self) -> str: ...
+    def reveal(self) -> str:
+        """Returns the representation of this value including its secrets. The default representation redacts them."""
+    def __add__(self, other: Union[SecretInteger, Integer, int]) -> SecretInteger: ...
+    def __radd__(self, other: Union[SecretInteger, Integer, int]) -> SecretInteger: ...
+    def __sub__(self, other: Union[SecretInteger, Integer, int]) -> SecretInteger: ...
+    def __rsub__(self, other: Union[SecretInteger, Integer, int]) -> SecretInteger: ...
+    def __mul__(self, other: Union[SecretInteger, Integer, int]) -> SecretInteger: ...
+    def __rmul__(self, other: Union[SecretInteger, Integer, int]) -> SecretInteger: ...
+    def __floordiv__(self, other: Union[SecretInteger, Integer, int]) -> SecretInteger: ...
+    def __rfloordiv__(self, other: Union[SecretInteger, Integer, int]) -> SecretInteger: ...
+    def __mod__(self, other: Union[SecretInteger, Integer, int]) -> SecretInteger: ...
+    def __rmod__(self, other: Union[SecretInteger, Integer, int]) -> SecretInteger: ...
+    def __lt__(self, other: SecretInteger) -> bool: ...
+    def __le__(self, other: SecretInteger) -> bool: ...
+    def __gt__(self, other: SecretInteger) -> bool: ...
+    def __ge__(self, other: SecretInteger) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __int__(self) -> int: ...
+    def __index__(self) -> int: ...
 
 class SecretBoolean:
     """Encodes a secret as a boolean."""
 
-    value: bool
-
+    @property
+    def value(self) -> bool: ...
     def __init__(self, value: bool) -> None: ...
     def __eq__(self, other: object) -> bool: ...
     def __repr__(self) -> str: ...
+    def reveal(self) -> str:
+        """Returns the representation of this value including its secrets. The default representation redacts them."""
+    def __and__(self, other: Union[SecretBoolean, Boolean, bool]) -> SecretBoolean: ...
+    def __rand__(self, other: Union[SecretBoolean, Boolean, bool]) -> SecretBoolean: ...
+    def __or__(self, other: Union[SecretBoolean, Boolean, bool]) -> SecretBoolean: ...
+    def __ror__(self, other: Union[SecretBoolean, Boolean, bool]) -> SecretBoolean: ...
+    def __xor__(self, other: Union[SecretBoolean, Boolean, bool]) -> SecretBoolean: ...
+    def __rxor__(self, other: Union[SecretBoolean, Boolean, bool]) -> SecretBoolean: ...
+    def __lt__(self, other: SecretBoolean) -> bool: ...
+    def __le__(self, other: SecretBoolean) -> bool: ...
+    def __gt__(self, other: SecretBoolean) -> bool: ...
+    def __ge__(self, other: SecretBoolean) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __int__(self) -> int: ...
+    def __index__(self) -> int: ...
+    def __bool__(self) -> bool: ...
+
+class SecretRational:
+    """Encodes a secret as a fixed-point rational, scaled by `2 ** precision`. The precision is at most 254. Compared and hashed by exact value, whatever the precision."""
+
+    value: float
+    scaled_value: int
+    precision: int
+
+    def __init__(
+        self, value: Union[float, Decimal, Fraction, int], precision: int = 16
+    ) -> None: ...
+    @staticmethod
+    def from_scaled(scaled_value: int, precision: int = 16) -> "SecretRational":
+        """Build a secret rational from an integer already scaled by `2 ** precision`. Raises `ValueError` if the precision is larger than 254 and `OverflowError` if the scaled value doesn't fit the 256 bit safe prime."""
 
-class EncryptedNadaType:
-    """An encrypted nada type."""
+    def __eq__(self, other: object) -> bool: ...
+    def __lt__(self, other: SecretRational) -> bool: ...
+    def __le__(self, other: SecretRational) -> bool: ...
+    def __gt__(self, other: SecretRational) -> bool: ...
+    def __ge__(self, other: SecretRational) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    def __float__(self) -> float: ...
+    def reveal(self) -> str:
+        """Returns the representation of this value including its secrets. The default representation redacts them."""
 
-    pass
+class Rational:
+    """Encodes a public variable value as a fixed-point rational, scaled by `2 ** precision`. The precision is at most 254. Compared and hashed by exact value, whatever the precision."""
+
+    value: float
+    scaled_value: int
+    precision: int
+
+    def __init__(
+        self, value: Union[float, Decimal, Fraction, int], precision: int = 16
+    ) -> None: ...
+    @staticmethod
+    def from_scaled(scaled_value: int, precision: int = 16) -> "Rational":
+        """Build a rational from an integer already scaled by `2 ** precision`. Raises `ValueError` if the precision is larger than 254 and `OverflowError` if the scaled value doesn't fit the 256 bit safe prime."""
+
+    def __eq__(self, other: object) -> bool: ...
+    def __lt__(self, other: Rational) -> bool: ...
+    def __le__(self, other: Rational) -> bool: ...
+    def __gt__(self, other: Rational) -> bool: ...
+    def __ge__(self, other: Rational) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    def __float__(self) -> float: ...
+
+class EncryptedNadaType:
+    """An encrypted nada type. `NTuple` and `Object` types have no protobuf representation, they're only supported locally."""
+
+    class Integer(EncryptedNadaType): ...
+    class UnsignedInteger(EncryptedNadaType): ...
+    class Boolean(EncryptedNadaType): ...
+    class ShamirShareInteger(EncryptedNadaType): ...
+    class ShamirShareUnsignedInteger(EncryptedNadaType): ...
+    class ShamirShareBoolean(EncryptedNadaType): ...
+    class ShamirShareSecretBlob(EncryptedNadaType): ...
+
+    class Array(EncryptedNadaType):
+        inner_type: EncryptedNadaType
+        size: int
+
+    class Tuple(EncryptedNadaType):
+        left: EncryptedNadaType
+        right: EncryptedNadaType
+
+    class NTuple(EncryptedNadaType):
+        types: List[EncryptedNadaType]
+
+    class Object(EncryptedNadaType):
+        types: Dict[str, EncryptedNadaType]
+
+    class EcdsaMessageDigest(EncryptedNadaType): ...
+    class EcdsaSignature(EncryptedNadaType): ...
+    class EcdsaPrivateKey(EncryptedNadaType): ...
+    class EcdsaPublicKey(EncryptedNadaType): ...
+    class StoreId(EncryptedNadaType): ...
+    class EddsaPrivateKey(EncryptedNadaType): ...
+    class EddsaPublicKey(EncryptedNadaType): ...
+    class EddsaSignature(EncryptedNadaType): ...
+    class EddsaMessage(EncryptedNadaType): ...
 
 class Array:
     """Encodes multiple values as an array."""
 
+    value: List[NadaValue]
+    inner_type: NadaType
+
+    def __init__(
+        self, value: List[NadaValue], inner_type: NadaType | None = None
+    ) -> None: ...
+    def __len__(self) -> int: ...
+    @overload
+    def __getitem__(self, index: int) -> NadaValue: ...
+    @overload
+    def __getitem__(self, index: slice) -> "Array": ...
+    def __setitem__(self, index: int, value: NadaValue) -> None: ...
+    def __iter__(self) -> Iterator[NadaValue]: ...
+    def append(self, value: NadaValue) -> None:
+        """Appends an element at the end of the array."""
+
+    @staticmethod
+    def from_numpy(data: Any, secret: bool = True, signed: bool = True) -> "Array":
+        """Builds an array from a one dimensional numpy array, or any object exposing the buffer protocol, of int64, uint64 or bool elements. This is a copying fast path: it avoids a Python object per element, but the buffer's contents are copied twice, and both copies are wiped once converted."""
+
+    def to_numpy(self) -> Any:
+        """Converts this array of integers, unsigned integers or booleans into a numpy array."""
+
+    def __eq__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+    def reveal(self) -> str:
+        """Returns the representation of this value including its secrets. The default representation redacts them."""
+
+class Tuple:
+    """Encodes a pair of values as a tuple."""
+
+    left: NadaValue
+    right: NadaValue
+    value: tuple[NadaValue, NadaValue]
+
+    def __init__(self, left: NadaValue, right: NadaValue) -> None: ...
+    def __len__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+    def reveal(self) -> str:
+        """Returns the representation of this value including its secrets. The default representation redacts them."""
+
+class NTuple:
+    """Encodes a fixed-size sequence of values of possibly different types."""
+
     value: List[NadaValue]
 
     def __init__(self, value: List[NadaValue]) -> None: ...
     def __len__(self) -> int: ...
     def __eq__(self, other: object) -> bool: ...
     def __repr__(self) -> str: ...
+    def reveal(self) -> str:
+        """Returns the representation of this value including its secrets. The default representation redacts them."""
+
+class Object:
+    """Encodes a set of named values."""
+
+    value: Dict[str, NadaValue]
+
+    def __init__(self, value: Mapping[str, NadaValue]) -> None: ...
+    def __len__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+    def reveal(self) -> str:
+        """Returns the representation of this value including its secrets. The default representation redacts them."""
 
 class SecretBlob:
     """Encodes a secret as a blob."""
@@ -68,35 +284,98 @@ class SecretBlob:
     value: bytearray
 
     def __init__(self, value: bytearray) -> None: ...
+    @staticmethod
+    def with_digest(value: bytearray, compress: bool = False) -> SecretBlob:
+        """Returns a blob that wraps `value` in an envelope recording its SHA-256 digest, optionally zstd compressing it. Unmasking opens the envelope transparently and raises `BlobIntegrityError` on a digest mismatch."""
+
     def __eq__(self, other: object) -> bool: ...
     def __repr__(self) -> str: ...
+    def reveal(self) -> str:
+        """Returns the representation of this value including its secrets. The default representation redacts them."""
+
+class BlobIntegrityError(ValueError):
+    """An enveloped secret blob doesn't match the digest it was created with."""
 
 class UnsignedInteger:
     """Encodes a public variable value as an unsigned integer."""
 
-    value: int
-
-    def __init__(self, value: int) -> None: ...
+    @property
+    def value(self) -> int: ...
+    @property
+    def prime_bits(self) -> Optional[int]:
+        """The size in bits of the safe prime chosen for this value, if any."""
+    def __init__(self, value: int, prime_bits: Optional[int] = None) -> None: ...
     def __eq__(self, other: object) -> bool: ...
     def __repr__(self) -> str: ...
+    def __add__(self, other: Union[UnsignedInteger, int]) -> UnsignedInteger: ...
+    def __radd__(self, other: Union[UnsignedInteger, int]) -> UnsignedInteger: ...
+    def __sub__(self, other: Union[UnsignedInteger, int]) -> UnsignedInteger: ...
+    def __rsub__(self, other: Union[UnsignedInteger, int]) -> UnsignedInteger: ...
+    def __mul__(self, other: Union[UnsignedInteger, int]) -> UnsignedInteger: ...
+    def __rmul__(self, other: Union[UnsignedInteger, int]) -> UnsignedInteger: ...
+    def __floordiv__(self, other: Union[UnsignedInteger, int]) -> UnsignedInteger: ...
+    def __rfloordiv__(self, other: Union[UnsignedInteger, int]) -> UnsignedInteger: ...
+    def __mod__(self, other: Union[UnsignedInteger, int]) -> UnsignedInteger: ...
+    def __rmod__(self, other: Union[UnsignedInteger, int]) -> UnsignedInteger: ...
+    def __lt__(self, other: UnsignedInteger) -> bool: ...
+    def __le__(self, other: UnsignedInteger) -> bool: ...
+    def __gt__(self, other: UnsignedInteger) -> bool: ...
+    def __ge__(self, other: UnsignedInteger) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __int__(self) -> int: ...
+    def __index__(self) -> int: ...
 
 class Integer:
     """Encodes a public variable value as an integer."""
 
-    value: int
-
-    def __init__(self, value: int) -> None: ...
+    @property
+    def value(self) -> int: ...
+    @property
+    def prime_bits(self) -> Optional[int]:
+        """The size in bits of the safe prime chosen for this value, if any."""
+    def __init__(self, value: int, prime_bits: Optional[int] = None) -> None: ...
     def __eq__(self, other: object) -> bool: ...
     def __repr__(self) -> str: ...
+    def __add__(self, other: Union[Integer, int]) -> Integer: ...
+    def __radd__(self, other: Union[Integer, int]) -> Integer: ...
+    def __sub__(self, other: Union[Integer, int]) -> Integer: ...
+    def __rsub__(self, other: Union[Integer, int]) -> Integer: ...
+    def __mul__(self, other: Union[Integer, int]) -> Integer: ...
+    def __rmul__(self, other: Union[Integer, int]) -> Integer: ...
+    def __floordiv__(self, other: Union[Integer, int]) -> Integer: ...
+    def __rfloordiv__(self, other: Union[Integer, int]) -> Integer: ...
+    def __mod__(self, other: Union[Integer, int]) -> Integer: ...
+    def __rmod__(self, other: Union[Integer, int]) -> Integer: ...
+    def __lt__(self, other: Integer) -> bool: ...
+    def __le__(self, other: Integer) -> bool: ...
+    def __gt__(self, other: Integer) -> bool: ...
+    def __ge__(self, other: Integer) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __int__(self) -> int: ...
+    def __index__(self) -> int: ...
 
 class Boolean:
     """Encodes a public variable value as a boolean."""
 
-    value: bool
-
+    @property
+    def value(self) -> bool: ...
     def __init__(self, value: bool) -> None: ...
     def __eq__(self, other: object) -> bool: ...
     def __repr__(self) -> str: ...
+    def __and__(self, other: Union[Boolean, bool]) -> Boolean: ...
+    def __rand__(self, other: Union[Boolean, bool]) -> Boolean: ...
+    def __or__(self, other: Union[Boolean, bool]) -> Boolean: ...
+    def __ror__(self, other: Union[Boolean, bool]) -> Boolean: ...
+    def __xor__(self, other: Union[Boolean, bool]) -> Boolean: ...
+    def __rxor__(self, other: Union[Boolean, bool]) -> Boolean: ...
+    def __lt__(self, other: Boolean) -> bool: ...
+    def __le__(self, other: Boolean) -> bool: ...
+    def __gt__(self, other: Boolean) -> bool: ...
+    def __ge__(self, other: Boolean) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __int__(self) -> int: ...
+    def __index__(self) -> int: ...
+    def __bool__(self) -> bool: ...
 
 class EcdsaPrivateKey:
     """Encodes a secret as an ecdsa private key."""
@@ -106,6 +385,8 @@ class EcdsaPrivateKey:
     def __init__(self, value: bytearray) -> None: ...
     def __eq__(self, other: object) -> bool: ...
     def __repr__(self) -> str: ...
+    def reveal(self) -> str:
+        """Returns the representation of this value including its secrets. The default representation redacts them."""
 
 class EcdsaDigestMessage:
     """Encodes an ecdsa digest message."""
@@ -128,9 +409,9 @@ class EcdsaPublicKey:
 class EcdsaSignature:
     """Encodes an ecdsa signature."""
 
-    value: Tuple[bytearray, bytearray]
+    value: tuple[bytearray, bytearray]
 
-    def __init__(self, value: Tuple[bytearray, bytearray]) -> None: ...
+    def __init__(self, value: tuple[bytearray, bytearray]) -> None: ...
     def __eq__(self, other: object) -> bool: ...
     def __repr__(self) -> str: ...
 
@@ -151,6 +432,8 @@ class EddsaPrivateKey:
     def __init__(self, value: bytearray) -> None: ...
     def __eq__(self, other: object) -> bool: ...
     def __repr__(self) -> str: ...
+    def reveal(self) -> str:
+        """Returns the representation of this value including its secrets. The default representation redacts them."""
 
 class EddsaPublicKey:
     """Encodes an eddsa public key."""
@@ -164,9 +447,9 @@ class EddsaPublicKey:
 class EddsaSignature:
     """Encodes an eddsa signature."""
 
-    value: Tuple[bytearray, bytearray]
+    value: tuple[bytearray, bytearray]
 
-    def __init__(self, value: Tuple[bytearray, bytearray]) -> None: ...
+    def __init__(self, value: tuple[bytearray, bytearray]) -> None: ...
     def __eq__(self, other: object) -> bool: ...
     def __repr__(self) -> str: ...
 
@@ -179,6 +462,93 @@ class EddsaMessage:
     def __eq__(self, other: object) -> bool: ...
     def __repr__(self) -> str: ...
 
+class NadaType:
+    """Describes the type of a cleartext value."""
+
+    @staticmethod
+    def integer() -> "NadaType": ...
+    @staticmethod
+    def unsigned_integer() -> "NadaType": ...
+    @staticmethod
+    def boolean() -> "NadaType": ...
+    @staticmethod
+    def secret_integer() -> "NadaType": ...
+    @staticmethod
+    def secret_unsigned_integer() -> "NadaType": ...
+    @staticmethod
+    def secret_boolean() -> "NadaType": ...
+    @staticmethod
+    def rational(precision: int = 16) -> "NadaType":
+        """A public rational, encoded as an integer scaled by `2 ** precision`. The precision is at most 254."""
+
+    @staticmethod
+    def secret_rational(precision: int = 16) -> "NadaType":
+        """A secret rational, encoded as a secret integer scaled by `2 ** precision`. The precision is at most 254."""
+
+    @staticmethod
+    def secret_blob() -> "NadaType": ...
+    @staticmethod
+    def ecdsa_private_key() -> "NadaType": ...
+    @staticmethod
+    def ecdsa_digest_message() -> "NadaType": ...
+    @staticmethod
+    def ecdsa_signature() -> "NadaType": ...
+    @staticmethod
+    def ecdsa_public_key() -> "NadaType": ...
+    @staticmethod
+    def store_id() -> "NadaType": ...
+    @staticmethod
+    def eddsa_private_key() -> "NadaType": ...
+    @staticmethod
+    def eddsa_public_key() -> "NadaType": ...
+    @staticmethod
+    def eddsa_signature() -> "NadaType": ...
+    @staticmethod
+    def eddsa_message() -> "NadaType": ...
+    @staticmethod
+    def array(inner_type: "NadaType", size: int) -> "NadaType": ...
+    @staticmethod
+    def tuple(left: "NadaType", right: "NadaType") -> "NadaType": ...
+    @staticmethod
+    def ntuple(types: List["NadaType"]) -> "NadaType": ...
+    @staticmethod
+    def object(types: Mapping[str, "NadaType"]) -> "NadaType": ...
+    @property
+    def rational_precision(self) -> int | None:
+        """The precision of the rationals described by this type, or `None` if this is not a rational type."""
+
+    def __eq__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+class NadaValuesSchema:
+    """Maps value names to their types, allowing plain Python values to be coerced into Nada values."""
+
+    types: Dict[str, NadaType]
+
+    def __init__(self, types: Mapping[str, NadaType]) -> None: ...
+    def validate(self, values: Mapping[str, object]) -> Dict[str, str]:
+        """Returns an error message for every value that doesn't fit its declared type."""
+
+    def coerce(self, values: Mapping[str, object]) -> Dict[str, NadaValue]:
+        """Coerces the given values into value classes according to this schema."""
+
+def nada_value_to_bytes(value: NadaValue) -> bytes:
+    """Encode a value into its compact, versioned binary representation. This is the representation used when pickling values."""
+
+def nada_value_from_bytes(bytes: bytes) -> NadaValue:
+    """Decode a value from the binary representation produced by `nada_value_to_bytes`."""
+
+def nada_type_from_bytes(
+    bytes: bytes, rational_precision: int | None = None
+) -> NadaType:
+    """Decode a type from the binary representation used when pickling a `NadaType`."""
+
+def values_to_json(values: Mapping[str, NadaValue], indent: bool = False) -> str:
+    """Export values as a typed JSON document. Every value is tagged with its type, integers are encoded as decimal strings tagged with their width in bits and byte strings as hex."""
+
+def values_from_json(json: str) -> Dict[str, NadaValue]:
+    """Import values from a typed JSON document produced by `values_to_json`."""
+
 class ProgramRequirements:
     """A program preprocessing requirements"""
 
@@ -205,9 +575,92 @@ def extract_program_metadata(program: bytes) -> ProgramMetadata:
     ...
 
 class EncryptedNadaValue:
-    """An encrypted Nada value."""
+    """An encrypted Nada value. `NTuple` and `Object` values have no protobuf representation, they can be kept in an `EncryptedBundle` and unmasked but not sent to the nodes."""
 
-    ...
+    class ShamirShareInteger(EncryptedNadaValue):
+        value: List[int]
+
+    class ShamirShareUnsignedInteger(EncryptedNadaValue):
+        value: List[int]
+
+    class ShamirShareBoolean(EncryptedNadaValue):
+        value: List[int]
+
+    class ShamirSharesBlob(EncryptedNadaValue):
+        values: List[List[int]]
+        original_size: int
+
+    class PublicInteger(EncryptedNadaValue):
+        value: List[int]
+
+    class PublicUnsignedInteger(EncryptedNadaValue):
+        value: List[int]
+
+    class PublicBoolean(EncryptedNadaValue):
+        value: List[int]
+
+    class Array(EncryptedNadaValue):
+        inner_type: EncryptedNadaType
+        values: List[EncryptedNadaValue]
+
+    class Tuple(EncryptedNadaValue):
+        left: EncryptedNadaValue
+        right: EncryptedNadaValue
+
+    class NTuple(EncryptedNadaValue):
+        values: List[EncryptedNadaValue]
+
+    class Object(EncryptedNadaValue):
+        values: Dict[str, EncryptedNadaValue]
+
+    class EcdsaMessageDigest(EncryptedNadaValue):
+        value: List[int]
+
+    class EcdsaSignature(EncryptedNadaValue):
+        r: List[int]
+        sigma: List[int]
+
+    class EcdsaPrivateKey(EncryptedNadaValue):
+        i: int
+        x: List[int]
+        shared_public_key: List[int]
+        public_shares: List[List[int]]
+
+    class EcdsaPublicKey(EncryptedNadaValue):
+        value: List[int]
+
+    class StoreId(EncryptedNadaValue):
+        value: List[int]
+
+    class EddsaPrivateKey(EncryptedNadaValue):
+        i: int
+        x: List[int]
+        shared_public_key: List[int]
+        public_shares: List[List[int]]
+
+    class EddsaPublicKey(EncryptedNadaValue):
+        value: List[int]
+
+    class EddsaSignature(EncryptedNadaValue):
+        value: List[int]
+
+    class EddsaMessage(EncryptedNadaValue):
+        value: List[int]
+
+    def to_protobuf_bytes(self) -> bytes:
+        """Encode this value as a `nillion.values.v1.value.Value` protobuf message. Raises `ValueError` for values containing an `NTuple` or `Object`."""
+
+    def encoded_len(self) -> int:
+        """The exact length of this value once encoded as protobuf, computed without serializing it."""
+
+    @staticmethod
+    def from_protobuf_bytes(bytes: bytes) -> "EncryptedNadaValue":
+        """Decode a value from a `nillion.values.v1.value.Value` protobuf message."""
+
+def encode_named_values(
+    values: Mapping[str, EncryptedNadaValue], field_number: int = 1
+) -> bytes:
+    """Encode a set of named encrypted values as a repeated `NamedValue` protobuf field with the given field number. The output can be appended to an encoded request to set its values."""
 
 class PartyId:
     """Represents a party identifier."""
@@ -218,15 +671,103 @@ class PartyId:
     def from_bytes(cls, bytes: bytes) -> "PartyId": ...
     """Creates a party identifier from an array of bytes."""
 
+class EncryptedBundle:
+    """The encrypted values a party received, along with the metadata needed to unmask them later on."""
+
+    party: PartyId
+    """The party the values belong to."""
+
+    prime_bits: int
+    """The size in bits of the safe prime the values were masked with."""
+
+    polynomial_degree: int
+    """The degree of the polynomial the values were masked with."""
+
+    created_at: int
+    """The creation time in seconds since the unix epoch."""
+
+    values: Dict[str, EncryptedNadaValue]
+    """The party's encrypted values."""
+
+    def __init__(
+        self,
+        party: PartyId,
+        values: Mapping[str, EncryptedNadaValue],
+        prime_bits: int,
+        polynomial_degree: int,
+        created_at: Optional[int] = None,
+    ) -> None: ...
+    def to_bytes(self) -> bytes:
+        """Encode this bundle in the versioned bundle format."""
+
+    @staticmethod
+    def from_bytes(bytes: bytes) -> "EncryptedBundle":
+        """Decode a bundle produced by `to_bytes`."""
+
+    def seal(
+        self, passphrase: Optional[str] = None, key: Optional[bytes] = None
+    ) -> bytes:
+        """Seal this bundle with a passphrase or a raw 32 byte key using XChaCha20-Poly1305, deriving the key from the passphrase with Argon2id. The sealed bundle can only be unsealed as this bundle's party and tampering is detected."""
+
+    @staticmethod
+    def unseal(
+        sealed: bytes,
+        party: PartyId,
+        passphrase: Optional[str] = None,
+        key: Optional[bytes] = None,
+    ) -> "EncryptedBundle":
+        """Unseal a bundle produced by `seal` for the given party."""
+
+    def save(
+        self,
+        path: Union[str, os.PathLike],
+        passphrase: Optional[str] = None,
+        key: Optional[bytes] = None,
+    ) -> None:
+        """Write this bundle to a file, sealing it if a passphrase or a key is provided."""
+
+    @staticmethod
+    def load(
+        path: Union[str, os.PathLike],
+        party: Optional[PartyId] = None,
+        passphrase: Optional[str] = None,
+        key: Optional[bytes] = None,
+    ) -> "EncryptedBundle":
+        """Read a bundle written by `save`. Sealed bundles require the party they belong to and the passphrase or key they were sealed with."""
+
 class PartyJar:
-    """A jar where every party puts an element."""
+    """A jar where every party puts an element. Values can be unmasked once `polynomial_degree + 1` of the masker's parties have put their element in it."""
 
     @classmethod
     def new(cls, count: int) -> PartyJar: ...
     def add_element(
-        self, party: PartyId, element: Mapping[str, EncryptedNadaValue]
+        self,
+        party: PartyId,
+        element: Union[Mapping[str, EncryptedNadaValue], EncryptedBundle],
+        checked: bool = True,
     ): ...
-    """Adds an element to the jar."""
+    """Adds an element to the jar, either as a mapping of values or as a bundle belonging to `party` that uses the jar's modulo and polynomial degree. The party must be one of the masker's parties and can only add a single element. Unless `checked` is false, shares are checked to be correctly encoded for the jar's modulo and errors name the party and the path of the offending value."""
+
+    def is_sufficient(self) -> bool:
+        """Whether enough parties, `polynomial_degree + 1`, have put their element in this jar to unmask values."""
+
+    def missing_parties(self) -> List[PartyId]:
+        """The masker's parties that haven't put their element in this jar yet."""
+
+    def verify_consistency(self) -> List[PublicValueMismatch]:
+        """Check that every party holds the same copy of public values and of the public metadata of key shares (`shared_public_key`, `public_shares`). Returns a mismatch for every value that some parties hold a different copy of, or don't hold at all; an empty list means the parties agree."""
+
+class PublicValueMismatch:
+    """A public value or piece of key metadata that not every party holds the same copy of."""
+
+    key: str
+    """The path of the value, e.g. `my_key.shared_public_key`."""
+
+    majority: Optional[bytes]
+    """The copy held by more than half of the parties, or `None` if no copy is."""
+
+    differing: Dict[PartyId, Optional[bytes]]
+    """The parties whose copy differs from the majority's, with their copy or `None` if they don't hold the value. Every party is listed if no copy is held by a majority."""
 
 class EncryptedPartyShares:
     """Each party's shares."""
@@ -248,39 +789,160 @@ class NadaValuesClassification:
     ecdsa_signature_shares: int
     """The number of ecdsa signature shares."""
 
+class RangeViolation:
+    """A value that is outside of the range supported by a masker's modulo."""
+
+    key: str
+    """The path of the offending value, e.g. `my_array[2]`."""
+
+    value: int
+    """The offending value."""
+
+    min: int
+    """The smallest allowed value."""
+
+    max: int
+    """The largest allowed value."""
+
+class RobustUnmaskResult:
+    """The result of unmasking values while correcting faulty shares."""
+
+    values: Dict[str, NadaValue | float | List[float]]
+    """The unmasked values."""
+
+    faulty_parties: List[PartyId]
+    """The parties that provided at least one faulty share, in the masker's order."""
+
+    faulty_keys: Dict[str, List[PartyId]]
+    """The parties whose shares of a value were faulty or missing, for every value that had any."""
+
+class ThreadPool:
+    """A pool of threads that maskers mask and unmask values with. A pool is handed to maskers when they're built, and can be shared by any number of them."""
+
+    threads: int
+    """The number of threads in this pool."""
+
+    def __init__(self, threads: int) -> None: ...
+
 class SecretMasker:
     """A secret masker. This allows masking and unmasking secrets."""
 
     @classmethod
     def new_64_bit_safe_prime(
-        cls, polynomial_degree: int, parties: List[PartyId]
+        cls,
+        polynomial_degree: int,
+        parties: List[PartyId],
+        seed: Optional[bytes] = None,
+        pool: Optional[ThreadPool] = None,
     ) -> "SecretMasker":
-        """Construct a new masker that uses a 64 bit safe prime under the hood."""
+        """Construct a new masker that uses a 64 bit safe prime under the hood. For tests only: a `seed` makes masking draw its randomness from a ChaCha20 generator seeded with it, so that a new masker with the same seed produces byte-identical shares for the same inputs. Never use a seed with real secrets. A `pool` masks and unmasks values and array elements in parallel with its threads, with the same results."""
 
     @classmethod
     def new_128_bit_safe_prime(
-        cls, polynomial_degree: int, parties: List[PartyId]
+        cls,
+        polynomial_degree: int,
+        parties: List[PartyId],
+        seed: Optional[bytes] = None,
+        pool: Optional[ThreadPool] = None,
     ) -> "SecretMasker":
-        """Construct a new masker that uses a 128 bit safe prime under the hood."""
+        """Construct a new masker that uses a 128 bit safe prime under the hood. For tests only: a `seed` makes masking draw its randomness from a ChaCha20 generator seeded with it, so that a new masker with the same seed produces byte-identical shares for the same inputs. Never use a seed with real secrets."""
 
     @classmethod
     def new_256_bit_safe_prime(
-        cls, polynomial_degree: int, parties: List[PartyId]
+        cls,
+        polynomial_degree: int,
+        parties: List[PartyId],
+        seed: Optional[bytes] = None,
+        pool: Optional[ThreadPool] = None,
+    ) -> "SecretMasker":
+        """Construct a new masker that uses a 256 bit safe prime under the hood. For tests only: a `seed` makes masking draw its randomness from a ChaCha20 generator seeded with it, so that a new masker with the same seed produces byte-identical shares for the same inputs. Never use a seed with real secrets."""
+
+    @classmethod
+    def from_prime(
+        cls,
+        prime: str,
+        polynomial_degree: int,
+        parties: List[PartyId],
+        seed: Optional[bytes] = None,
+        pool: Optional[ThreadPool] = None,
     ) -> "SecretMasker":
-        """Construct a new masker that uses a 256 bit safe prime under the hood."""
+        """Construct a new masker from the name of a cluster's prime, as in the membership `Cluster` message: one of `SAFE_64_BITS`, `SAFE_128_BITS` or `SAFE_256_BITS`. For tests only: a `seed` makes masking deterministic, see `new_64_bit_safe_prime`."""
+
+    parties: List[PartyId]
+    """The parties values are masked for."""
+
+    party_count: int
+    """The number of parties values are masked for."""
+
+    polynomial_degree: int
+    """The degree of the polynomials values are masked with."""
+
+    prime_bits: int
+    """The size in bits of the safe prime values are masked with."""
 
     def mask(
-        self, values: Mapping[str, NadaValue]
+        self,
+        values: Mapping[str, NadaValue | object],
+        schema: NadaValuesSchema | None = None,
     ) -> Mapping[PartyId, Mapping[str, EncryptedNadaValue]]:
-        """Mask a set of values."""
+        """Mask a set of values. If a schema is provided, plain Python values are coerced into the types it declares. Raises an error naming the first integer that can't be represented with this masker's prime, see `validate_ranges` to get all of them."""
+
+    pool: Optional[ThreadPool]
+    """The pool of threads values are masked and unmasked with, or `None` if they use the calling thread. Results are the same either way and the GIL is always released while masking and unmasking."""
+
+    threads: Optional[int]
+    """The number of threads values are masked and unmasked with, or `None` if they use the calling thread."""
+
+    def unmask(
+        self, jar: PartyJar, schema: NadaValuesSchema | None = None
+    ) -> Dict[str, NadaValue | float | List[float]]:
+        """Unmask a set of values from any `polynomial_degree + 1` of the masker's parties, raising an error naming the missing parties if fewer contributed. If a schema is provided, the values it declares as rationals are decoded into floats. Blobs created with `SecretBlob.with_digest` are decompressed and verified, raising `BlobIntegrityError` on mismatch."""
 
-    def unmask(self, jar: PartyJar) -> Dict[str, NadaValue]:
-        """Unmask a set of values."""
+    def robust_unmask(
+        self, jar: PartyJar, schema: NadaValuesSchema | None = None
+    ) -> RobustUnmaskResult:
+        """Unmask a set of values from more than `polynomial_degree + 1` parties, detecting and correcting faulty shares. Every value is recovered from the only polynomial that all but at most `(n - polynomial_degree - 1) / 2` of its `n` shares lie on, and the parties whose shares disagree or are missing are reported. Faults are decided per value, so a party that corrupted any element of an array is faulty for the whole array. Raises an error if there are too many faulty shares to recover a value, or if none of the first 4096 subsets of `polynomial_degree + 1` shares is consistent. Much slower than `unmask`: every subset tried costs up to `n` unmasks."""
+
+    def reshare(
+        self, jar: PartyJar, target: "SecretMasker"
+    ) -> Mapping[PartyId, Mapping[str, EncryptedNadaValue]]:
+        """Re-share the values in a jar for another masker, which can use other parties, polynomial degree or prime, without handing their cleartext to Python. ECDSA and EdDSA private keys are split into fresh key shares for the target's parties. Returns the target parties' shares, the same way `mask` does. Raises if an integer can't be represented with the target's prime."""
 
     def classify_values(
-        self, values: Mapping[str, NadaValue]
+        self,
+        values: Mapping[str, NadaValue | object],
+        schema: NadaValuesSchema | None = None,
     ) -> NadaValuesClassification:
         """Classify the given cleartext values. This allows getting the totals per value type which is a required parameter when storing values."""
 
+    def validate_ranges(
+        self,
+        values: Mapping[str, NadaValue | object],
+        schema: NadaValuesSchema | None = None,
+    ) -> List[RangeViolation]:
+        """Validate that every integer in the given values is within the range supported by this masker's modulo. Returns a violation for every offending value."""
+
+    def encoded_payload_size(
+        self, masked: Mapping[PartyId, Mapping[str, EncryptedNadaValue]]
+    ) -> int:
+        """Compute the size of the payload sent to each party when storing the given masked values. Raises if parties' payloads differ in size."""
+
+    def mask_blob_stream(
+        self,
+        source: Union[str, os.PathLike, BinaryIO],
+        sinks: Mapping[
+            PartyId, Union[str, os.PathLike, BinaryIO, Callable[[bytes], Any]]
+        ],
+        chunk_size: int = 1048576,
+    ) -> int:
+        """Mask a blob read from a path or a binary file-like object in chunks of `chunk_size` bytes, streaming each party's shares to its sink. There must be a sink for every one of the masker's parties and no other, which is checked before any sink is written to. Returns the size of the blob."""
+
+    def unmask_blob_stream(
+        self,
+        sources: Mapping[PartyId, Union[str, os.PathLike, BinaryIO]],
+        sink: Union[str, os.PathLike, BinaryIO, Callable[[bytes], Any]],
+    ) -> int:
+        """Unmask a blob from every party's share stream, as written by `mask_blob_stream`, into a sink one chunk at a time. Returns the size of the blob."""
+
     def build_jar(self) -> PartyJar:
         """Build a party jar for this masker."""
@@ -0,0 +1,76 @@
+//! Cross-party consistency of the values every party holds a copy of.
+//!
+//! Public values and the public metadata of threshold key shares aren't secret shared, every party receives the same
+//! bytes. Unmasking uses any party's copy, so a party holding a different copy must be caught before that.
+
+use crate::PartyId;
+use indexmap::IndexMap;
+use pyo3::{pyclass, pymethods};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+};
+
+/// A public value or piece of key metadata that not every party holds the same copy of.
+#[pyclass]
+pub struct PublicValueMismatch {
+    key: String,
+    majority: Option<Vec<u8>>,
+    differing: Vec<(PartyId, Option<Vec<u8>>)>,
+}
+
+#[pymethods]
+impl PublicValueMismatch {
+    /// The path of the value, e.g. `my_key.shared_public_key`.
+    #[getter]
+    fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The copy held by more than half of the parties, or `None` if no copy is.
+    #[getter]
+    fn majority(&self) -> Option<Cow<'_, [u8]>> {
+        self.majority.as_deref().map(Cow::Borrowed)
+    }
+
+    /// The parties whose copy differs from the majority's, with their copy or `None` if they don't hold the value.
+    ///
+    /// Every party is listed if no copy is held by a majority.
+    #[getter]
+    fn differing(&self) -> IndexMap<PartyId, Option<Cow<'_, [u8]>>> {
+        self.differing.iter().map(|(party, copy)| (party.clone(), copy.as_deref().map(Cow::Borrowed))).collect()
+    }
+
+    fn __repr__(&self) -> String {
+        let parties = self.differing.iter().map(|(party, _)| party.inner.to_string()).collect::<Vec<_>>();
+        format!("PublicValueMismatch(key='{}', differing=[{}])", self.key, parties.join(", "))
+    }
+}
+
+/// Find every public value that the given parties don't all hold the same copy of, sorted by path.
+pub(crate) fn find_mismatches(public: &[(PartyId, &BTreeMap<String, Vec<u8>>)]) -> Vec<PublicValueMismatch> {
+    let keys = public.iter().flat_map(|(_, values)| values.keys()).collect::<BTreeSet<_>>();
+    let mut mismatches = Vec::new();
+    for key in keys {
+        let copies = public.iter().map(|(party, values)| (party, values.get(key))).collect::<Vec<_>>();
+        let mut counts: Vec<(Option<&Vec<u8>>, usize)> = Vec::new();
+        for (_, copy) in &copies {
+            match counts.iter_mut().find(|(counted, _)| counted == copy) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((*copy, 1)),
+            }
+        }
+        if counts.len() == 1 {
+            continue;
+        }
+        // `Some(None)` means most parties don't hold the value at all.
+        let majority = counts.iter().find(|(_, count)| count * 2 > copies.len()).map(|(copy, _)| *copy);
+        let differing = copies
+            .iter()
+            .filter(|(_, copy)| majority != Some(*copy))
+            .map(|(party, copy)| ((*party).clone(), copy.cloned()))
+            .collect();
+        mismatches.push(PublicValueMismatch { key: key.clone(), majority: majority.flatten().cloned(), differing });
+    }
+    mismatches
+}
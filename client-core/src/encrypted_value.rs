@@ -3,41 +3,106 @@ use nillion_client_core::{
     key_share::{DirtyCoreKeyShare, DirtyKeyInfo, Validate},
     privatekey::ThresholdPrivateKeyShare,
     signature::{EcdsaSignatureShare, EddsaSignature},
-    values::{BlobPrimitiveType, Encoded, EncodedModularNumber, EncodedModulo, Encrypted, NadaValue},
+    values::{BigUint, BlobPrimitiveType, Encoded, EncodedModularNumber, EncodedModulo, Encrypted, NadaValue},
 };
 
+use crate::{
+    protobuf,
+    ranges::{modulo_from_prime_bits, prime, prime_bits},
+    sealing::{self, Secret},
+    values::{
+        encoding::{check_depth, write_bytes, write_varint, Reader},
+        secret::wipe_unsigned,
+    },
+    PartyId,
+};
+use indexmap::IndexMap;
 use pyo3::{
     exceptions::PyValueError,
     pyclass, pymethods,
-    types::{PyModule, PyModuleMethods},
-    Bound, IntoPy, Py, PyAny, PyResult, Python,
+    types::{PyAnyMethods, PyBytes, PyList, PyModule, PyModuleMethods},
+    Bound, FromPyObject, IntoPy, Py, PyAny, PyObject, PyResult, Python,
+};
+use std::{
+    collections::BTreeMap,
+    fs,
+    ops::Deref,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
 };
+use zeroize::Zeroizing;
 
 pub fn add_module(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<EncryptedNadaValue>()?;
     m.add_class::<EncryptedNadaType>()?;
+    m.add_class::<EncryptedBundle>()?;
     Ok(())
 }
 
+/// The bytes of a secret share, wiped when dropped.
+///
+/// Shares are exposed to python as a list of integers, the same way plain byte vectors are. Shares handed to the
+/// upstream masker are moved out rather than copied, upstream doesn't wipe them.
+#[derive(Clone)]
+pub struct ShareBytes(Zeroizing<Vec<u8>>);
+
+impl ShareBytes {
+    /// Move the bytes out, leaving nothing behind to wipe.
+    fn into_vec(mut self) -> Vec<u8> {
+        std::mem::take(&mut *self.0)
+    }
+}
+
+impl From<Vec<u8>> for ShareBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(Zeroizing::new(bytes))
+    }
+}
+
+impl Deref for ShareBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<'py> FromPyObject<'py> for ShareBytes {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        Ok(ob.extract::<Vec<u8>>()?.into())
+    }
+}
+
+impl IntoPy<PyObject> for ShareBytes {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        PyList::new_bound(py, self.0.iter()).into_py(py)
+    }
+}
+
 /// A nada value that has been encrypted/secret shared.
+///
+/// The `nillion.values.v1` protobuf messages have no `NTuple` or `Object` variants, so these values are only
+/// supported locally: they can be kept in bundles and unmasked, but not encoded as protobuf and sent to the nodes.
 #[pyclass]
 #[derive(Clone)]
 pub enum EncryptedNadaValue {
-    ShamirShareInteger { value: Vec<u8> },
-    ShamirShareUnsignedInteger { value: Vec<u8> },
-    ShamirShareBoolean { value: Vec<u8> },
-    ShamirSharesBlob { values: Vec<Vec<u8>>, original_size: u64 },
+    ShamirShareInteger { value: ShareBytes },
+    ShamirShareUnsignedInteger { value: ShareBytes },
+    ShamirShareBoolean { value: ShareBytes },
+    ShamirSharesBlob { values: Vec<ShareBytes>, original_size: u64 },
     PublicInteger { value: Vec<u8> },
     PublicUnsignedInteger { value: Vec<u8> },
     PublicBoolean { value: Vec<u8> },
     Array { inner_type: EncryptedNadaType, values: Vec<EncryptedNadaValue> },
     Tuple { left: Py<EncryptedNadaValue>, right: Py<EncryptedNadaValue> },
+    NTuple { values: Vec<EncryptedNadaValue> },
+    Object { values: IndexMap<String, EncryptedNadaValue> },
     EcdsaMessageDigest { value: Vec<u8> },
     EcdsaSignature { r: Vec<u8>, sigma: Vec<u8> },
-    EcdsaPrivateKey { i: u16, x: Vec<u8>, shared_public_key: Vec<u8>, public_shares: Vec<Vec<u8>> },
+    EcdsaPrivateKey { i: u16, x: ShareBytes, shared_public_key: Vec<u8>, public_shares: Vec<Vec<u8>> },
     EcdsaPublicKey { value: Vec<u8> },
     StoreId { value: Vec<u8> },
-    EddsaPrivateKey { i: u16, x: Vec<u8>, shared_public_key: Vec<u8>, public_shares: Vec<Vec<u8>> },
+    EddsaPrivateKey { i: u16, x: ShareBytes, shared_public_key: Vec<u8>, public_shares: Vec<Vec<u8>> },
     EddsaPublicKey { value: Vec<u8> },
     EddsaSignature { value: Vec<u8> },
     EddsaMessage { value: Vec<u8> },
@@ -46,16 +111,20 @@ pub enum EncryptedNadaValue {
 impl EncryptedNadaValue {
     pub(crate) fn new(py: Python<'_>, value: NadaValue<Encrypted<Encoded>>) -> PyResult<Self> {
         let value = match value {
-            NadaValue::ShamirShareInteger(value) => Self::ShamirShareInteger { value: value.as_bytes().to_vec() },
+            NadaValue::ShamirShareInteger(value) => {
+                Self::ShamirShareInteger { value: value.as_bytes().to_vec().into() }
+            }
             NadaValue::ShamirShareUnsignedInteger(value) => {
-                Self::ShamirShareUnsignedInteger { value: value.as_bytes().to_vec() }
+                Self::ShamirShareUnsignedInteger { value: value.as_bytes().to_vec().into() }
+            }
+            NadaValue::ShamirShareBoolean(value) => {
+                Self::ShamirShareBoolean { value: value.as_bytes().to_vec().into() }
             }
-            NadaValue::ShamirShareBoolean(value) => Self::ShamirShareBoolean { value: value.as_bytes().to_vec() },
             NadaValue::Integer(value) => Self::PublicInteger { value: value.as_bytes().to_vec() },
             NadaValue::UnsignedInteger(value) => Self::PublicUnsignedInteger { value: value.as_bytes().to_vec() },
             NadaValue::Boolean(value) => Self::PublicBoolean { value: value.as_bytes().to_vec() },
             NadaValue::SecretBlob(value) => Self::ShamirSharesBlob {
-                values: value.value.into_iter().map(|m| m.as_bytes().to_vec()).collect(),
+                values: value.value.into_iter().map(|m| m.as_bytes().to_vec().into()).collect(),
                 original_size: value.unencoded_size,
             },
             NadaValue::Array { inner_type, values } => {
@@ -65,6 +134,12 @@ impl EncryptedNadaValue {
             NadaValue::Tuple { left, right } => {
                 Self::Tuple { left: Py::new(py, Self::new(py, *left)?)?, right: Py::new(py, Self::new(py, *right)?)? }
             }
+            NadaValue::NTuple { values } => {
+                Self::NTuple { values: values.into_iter().map(|v| Self::new(py, v)).collect::<Result<_, _>>()? }
+            }
+            NadaValue::Object { values } => Self::Object {
+                values: values.into_iter().map(|(k, v)| Ok((k, Self::new(py, v)?))).collect::<PyResult<_>>()?,
+            },
             NadaValue::EcdsaDigestMessage(value) => Self::EcdsaMessageDigest { value: value.to_vec() },
             NadaValue::EcdsaPublicKey(value) => Self::EcdsaPublicKey { value: value.0.to_vec() },
             NadaValue::EcdsaSignature(signature) => Self::EcdsaSignature {
@@ -75,7 +150,7 @@ impl EncryptedNadaValue {
                 let key = key.into_inner();
                 Self::EcdsaPrivateKey {
                     i: key.i,
-                    x: key.x.clone().into_inner().as_ref().to_le_bytes().to_vec(),
+                    x: key.x.clone().into_inner().as_ref().to_le_bytes().to_vec().into(),
                     shared_public_key: key.key_info.shared_public_key.to_bytes(true).to_vec(),
                     public_shares: key.key_info.public_shares.iter().map(|s| s.to_bytes(true).to_vec()).collect(),
                 }
@@ -85,7 +160,7 @@ impl EncryptedNadaValue {
                 let key = key.into_inner();
                 Self::EddsaPrivateKey {
                     i: key.i,
-                    x: key.x.clone().into_inner().as_ref().to_le_bytes().to_vec(),
+                    x: key.x.clone().into_inner().as_ref().to_le_bytes().to_vec().into(),
                     shared_public_key: key.key_info.shared_public_key.to_bytes(true).to_vec(),
                     public_shares: key.key_info.public_shares.iter().map(|s| s.to_bytes(true).to_vec()).collect(),
                 }
@@ -102,6 +177,155 @@ impl EncryptedNadaValue {
         Ok(value)
     }
 
+    /// Check that this value can be decoded under a modulo.
+    ///
+    /// Every encoded number must be exactly as long as the modulo's encoding and lower than its prime, fixed size
+    /// byte strings must have the right length and array elements must match the array's inner type. Errors name
+    /// the path of the offending value, e.g. `my_array[2]`.
+    pub(crate) fn validate(&self, py: Python<'_>, bounds: &EncodingBounds, path: &str) -> Result<(), String> {
+        use EncryptedNadaValue as E;
+        let check = |bytes: &[u8]| bounds.check(bytes).map_err(|e| format!("value '{path}': {e}"));
+        let check_len = |bytes: &[u8], len: usize| {
+            if bytes.len() != len {
+                return Err(format!("value '{path}': expected {len} bytes, got {}", bytes.len()));
+            }
+            Ok(())
+        };
+        match self {
+            E::ShamirShareInteger { value }
+            | E::ShamirShareUnsignedInteger { value }
+            | E::ShamirShareBoolean { value } => check(value),
+            E::PublicInteger { value } | E::PublicUnsignedInteger { value } | E::PublicBoolean { value } => {
+                check(value)
+            }
+            E::ShamirSharesBlob { values, .. } => {
+                for (index, share) in values.iter().enumerate() {
+                    bounds.check(share).map_err(|e| format!("value '{path}[{index}]': {e}"))?;
+                }
+                Ok(())
+            }
+            E::Array { inner_type, values } => {
+                let inner_type = inner_type.clone().into_nada_type(py).map_err(|e| format!("value '{path}': {e}"))?;
+                for (index, value) in values.iter().enumerate() {
+                    let path = format!("{path}[{index}]");
+                    value.validate(py, bounds, &path)?;
+                    let value_type = value.nada_type(py).map_err(|e| format!("value '{path}': {e}"))?;
+                    if value_type != inner_type {
+                        return Err(format!(
+                            "value '{path}': element has type {value_type} but array elements are {inner_type}"
+                        ));
+                    }
+                }
+                Ok(())
+            }
+            E::Tuple { left, right } => {
+                left.get().validate(py, bounds, &format!("{path}[0]"))?;
+                right.get().validate(py, bounds, &format!("{path}[1]"))
+            }
+            E::NTuple { values } => values
+                .iter()
+                .enumerate()
+                .try_for_each(|(index, value)| value.validate(py, bounds, &format!("{path}[{index}]"))),
+            E::Object { values } => {
+                values.iter().try_for_each(|(name, value)| value.validate(py, bounds, &format!("{path}.{name}")))
+            }
+            E::EcdsaMessageDigest { value } => check_len(value, 32),
+            E::EcdsaPublicKey { value } => check_len(value, 33),
+            E::StoreId { value } => check_len(value, 16),
+            E::EddsaPublicKey { value } => check_len(value, 32),
+            E::EcdsaSignature { .. }
+            | E::EcdsaPrivateKey { .. }
+            | E::EddsaPrivateKey { .. }
+            | E::EddsaSignature { .. }
+            | E::EddsaMessage { .. } => Ok(()),
+        }
+    }
+
+    /// The type of this value, using the lengths of arrays as their sizes.
+    fn nada_type(&self, py: Python<'_>) -> PyResult<nillion_client_core::values::NadaType> {
+        use nillion_client_core::values::NadaType as T;
+        use EncryptedNadaValue as E;
+        let nada_type = match self {
+            E::ShamirShareInteger { .. } => T::ShamirShareInteger,
+            E::ShamirShareUnsignedInteger { .. } => T::ShamirShareUnsignedInteger,
+            E::ShamirShareBoolean { .. } => T::ShamirShareBoolean,
+            E::ShamirSharesBlob { .. } => T::SecretBlob,
+            E::PublicInteger { .. } => T::Integer,
+            E::PublicUnsignedInteger { .. } => T::UnsignedInteger,
+            E::PublicBoolean { .. } => T::Boolean,
+            E::Array { inner_type, values } => {
+                T::Array { inner_type: Box::new(inner_type.clone().into_nada_type(py)?), size: values.len() }
+            }
+            E::Tuple { left, right } => T::Tuple {
+                left_type: Box::new(left.get().nada_type(py)?),
+                right_type: Box::new(right.get().nada_type(py)?),
+            },
+            E::NTuple { values } => {
+                T::NTuple { types: values.iter().map(|v| v.nada_type(py)).collect::<PyResult<_>>()? }
+            }
+            E::Object { values } => T::Object {
+                types: values.iter().map(|(k, v)| Ok((k.clone(), v.nada_type(py)?))).collect::<PyResult<_>>()?,
+            },
+            E::EcdsaMessageDigest { .. } => T::EcdsaDigestMessage,
+            E::EcdsaSignature { .. } => T::EcdsaSignature,
+            E::EcdsaPrivateKey { .. } => T::EcdsaPrivateKey,
+            E::EcdsaPublicKey { .. } => T::EcdsaPublicKey,
+            E::StoreId { .. } => T::StoreId,
+            E::EddsaPrivateKey { .. } => T::EddsaPrivateKey,
+            E::EddsaPublicKey { .. } => T::EddsaPublicKey,
+            E::EddsaSignature { .. } => T::EddsaSignature,
+            E::EddsaMessage { .. } => T::EddsaMessage,
+        };
+        Ok(nada_type)
+    }
+
+    /// Collect the parts of this value that every party holds a copy of, keyed by their path.
+    ///
+    /// These are public values and the public metadata of threshold key shares, which must be identical across
+    /// parties.
+    pub(crate) fn collect_public(&self, path: &str, public: &mut BTreeMap<String, Vec<u8>>) {
+        use EncryptedNadaValue as E;
+        match self {
+            E::PublicInteger { value }
+            | E::PublicUnsignedInteger { value }
+            | E::PublicBoolean { value }
+            | E::EcdsaMessageDigest { value }
+            | E::EcdsaPublicKey { value }
+            | E::StoreId { value }
+            | E::EddsaPublicKey { value }
+            | E::EddsaSignature { value }
+            | E::EddsaMessage { value } => {
+                public.insert(path.to_string(), value.clone());
+            }
+            E::EcdsaPrivateKey { shared_public_key, public_shares, .. }
+            | E::EddsaPrivateKey { shared_public_key, public_shares, .. } => {
+                public.insert(format!("{path}.shared_public_key"), shared_public_key.clone());
+                for (index, share) in public_shares.iter().enumerate() {
+                    public.insert(format!("{path}.public_shares[{index}]"), share.clone());
+                }
+            }
+            E::Array { values, .. } | E::NTuple { values } => {
+                for (index, value) in values.iter().enumerate() {
+                    value.collect_public(&format!("{path}[{index}]"), public);
+                }
+            }
+            E::Tuple { left, right } => {
+                left.get().collect_public(&format!("{path}[0]"), public);
+                right.get().collect_public(&format!("{path}[1]"), public);
+            }
+            E::Object { values } => {
+                for (name, value) in values {
+                    value.collect_public(&format!("{path}.{name}"), public);
+                }
+            }
+            E::ShamirShareInteger { .. }
+            | E::ShamirShareUnsignedInteger { .. }
+            | E::ShamirShareBoolean { .. }
+            | E::ShamirSharesBlob { .. }
+            | E::EcdsaSignature { .. } => (),
+        }
+    }
+
     pub(crate) fn into_nada_value(
         self,
         py: Python<'_>,
@@ -110,16 +334,16 @@ impl EncryptedNadaValue {
         use EncryptedNadaValue as E;
         let value = match self {
             E::ShamirShareInteger { value } => {
-                NadaValue::new_shamir_share_integer(EncodedModularNumber::new_unchecked(value, modulo))
-            }
-            E::ShamirShareUnsignedInteger { value } => {
-                NadaValue::new_shamir_share_unsigned_integer(EncodedModularNumber::new_unchecked(value, modulo))
+                NadaValue::new_shamir_share_integer(EncodedModularNumber::new_unchecked(value.into_vec(), modulo))
             }
+            E::ShamirShareUnsignedInteger { value } => NadaValue::new_shamir_share_unsigned_integer(
+                EncodedModularNumber::new_unchecked(value.into_vec(), modulo),
+            ),
             E::ShamirShareBoolean { value } => {
-                NadaValue::new_shamir_share_boolean(EncodedModularNumber::new_unchecked(value, modulo))
+                NadaValue::new_shamir_share_boolean(EncodedModularNumber::new_unchecked(value.into_vec(), modulo))
             }
             E::ShamirSharesBlob { values, original_size } => NadaValue::new_secret_blob(BlobPrimitiveType {
-                value: values.into_iter().map(|s| EncodedModularNumber::new_unchecked(s, modulo)).collect(),
+                value: values.into_iter().map(|s| EncodedModularNumber::new_unchecked(s.into_vec(), modulo)).collect(),
                 unencoded_size: original_size,
             }),
             E::PublicInteger { value } => NadaValue::new_integer(EncodedModularNumber::new_unchecked(value, modulo)),
@@ -137,6 +361,19 @@ impl EncryptedNadaValue {
                 right.get().clone().into_nada_value(py, modulo)?,
             )
             .map_err(|e| PyValueError::new_err(format!("failed to create tuple: {e}")))?,
+            E::NTuple { values } => {
+                let values = values.into_iter().map(|v| v.into_nada_value(py, modulo)).collect::<Result<_, _>>()?;
+                NadaValue::new_n_tuple(values)
+                    .map_err(|e| PyValueError::new_err(format!("failed to create ntuple: {e}")))?
+            }
+            E::Object { values } => {
+                let values = values
+                    .into_iter()
+                    .map(|(k, v)| Ok((k, v.into_nada_value(py, modulo)?)))
+                    .collect::<PyResult<IndexMap<_, _>>>()?;
+                NadaValue::new_object(values)
+                    .map_err(|e| PyValueError::new_err(format!("failed to create object: {e}")))?
+            }
             E::EcdsaMessageDigest { value } => {
                 let value: [u8; 32] =
                     value.try_into().map_err(|_| PyValueError::new_err("invalid digest message length"))?;
@@ -213,6 +450,24 @@ impl EncryptedNadaValue {
 
 #[pymethods]
 impl EncryptedNadaValue {
+    /// Encode this value as a `nillion.values.v1.value.Value` protobuf message.
+    fn to_protobuf_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        protobuf::encode_value(py, self)
+    }
+
+    /// The exact length of this value once encoded as a `nillion.values.v1.value.Value` protobuf message.
+    ///
+    /// This is the same as `len(value.to_protobuf_bytes())` but doesn't serialize the message.
+    fn encoded_len(&self, py: Python<'_>) -> PyResult<usize> {
+        protobuf::encoded_len(py, self)
+    }
+
+    /// Decode a value from a `nillion.values.v1.value.Value` protobuf message.
+    #[staticmethod]
+    fn from_protobuf_bytes(py: Python<'_>, bytes: &[u8]) -> PyResult<Self> {
+        protobuf::decode_value(py, bytes)
+    }
+
     fn __str__(&self) -> String {
         match self {
             Self::ShamirShareInteger { .. } => "ShamirShareInteger {..}".into(),
@@ -226,6 +481,8 @@ impl EncryptedNadaValue {
             Self::PublicBoolean { .. } => "Boolean".into(),
             Self::Array { .. } => "Array {..}".into(),
             Self::Tuple { .. } => "Tuple {..}".into(),
+            Self::NTuple { .. } => "NTuple {..}".into(),
+            Self::Object { .. } => "Object {..}".into(),
             Self::EcdsaMessageDigest { .. } => "EcdsaMessageDigest {..}".into(),
             Self::EcdsaSignature { .. } => "EcdsaSignature".into(),
             Self::EcdsaPrivateKey { .. } => "EcdsaPrivateKey {..}".into(),
@@ -239,6 +496,40 @@ impl EncryptedNadaValue {
     }
 }
 
+/// The bounds that encoded numbers must satisfy under a modulo.
+pub(crate) struct EncodingBounds {
+    prime: &'static BigUint,
+    byte_len: usize,
+}
+
+impl EncodingBounds {
+    pub(crate) fn new(modulo: EncodedModulo) -> Self {
+        let prime = prime(modulo);
+        let byte_len = (prime.bits() as usize).div_ceil(8);
+        Self { prime, byte_len }
+    }
+
+    /// Check that some bytes are a little endian number encoded under this modulo.
+    pub(crate) fn check(&self, bytes: &[u8]) -> Result<(), String> {
+        if bytes.len() != self.byte_len {
+            return Err(format!(
+                "expected {} bytes for a {} bit prime, got {}",
+                self.byte_len,
+                self.byte_len * 8,
+                bytes.len()
+            ));
+        }
+        let mut value = BigUint::from_bytes_le(bytes);
+        let in_range = &value < self.prime;
+        // The number may be a share, don't leave a copy of it behind.
+        wipe_unsigned(&mut value);
+        if !in_range {
+            return Err("encoded number is not lower than the prime".to_string());
+        }
+        Ok(())
+    }
+}
+
 fn non_zero_point_from_bytes<C: Curve>(bytes: &[u8]) -> PyResult<NonZero<Point<C>>> {
     let point = Point::from_bytes(bytes).map_err(|_| PyValueError::new_err("invalid bytes"))?;
     NonZero::from_point(point).ok_or_else(|| PyValueError::new_err("point is zero"))
@@ -261,6 +552,8 @@ pub enum EncryptedNadaType {
     ShamirShareSecretBlob(),
     Array { inner_type: Py<PyAny>, size: u64 },
     Tuple { left: Py<PyAny>, right: Py<PyAny> },
+    NTuple { types: Vec<Py<PyAny>> },
+    Object { types: IndexMap<String, Py<PyAny>> },
     EcdsaMessageDigest(),
     EcdsaSignature(),
     EcdsaPrivateKey(),
@@ -290,6 +583,15 @@ impl EncryptedNadaType {
                 left: Self::new(py, *left_type)?.into_py(py),
                 right: Self::new(py, *right_type)?.into_py(py),
             },
+            T::NTuple { types } => Self::NTuple {
+                types: types.into_iter().map(|t| Ok(Self::new(py, t)?.into_py(py))).collect::<PyResult<_>>()?,
+            },
+            T::Object { types } => Self::Object {
+                types: types
+                    .into_iter()
+                    .map(|(k, t)| Ok((k, Self::new(py, t)?.into_py(py))))
+                    .collect::<PyResult<_>>()?,
+            },
             T::EcdsaPrivateKey => Self::EcdsaPrivateKey(),
             T::EcdsaDigestMessage => Self::EcdsaMessageDigest(),
             T::EcdsaSignature => Self::EcdsaSignature(),
@@ -299,7 +601,7 @@ impl EncryptedNadaType {
             T::EddsaPublicKey => Self::EddsaPublicKey(),
             T::EddsaSignature => Self::EddsaSignature(),
             T::EddsaMessage => Self::EddsaMessage(),
-            T::SecretInteger | T::SecretUnsignedInteger | T::SecretBoolean | T::NTuple { .. } | T::Object { .. } => {
+            T::SecretInteger | T::SecretUnsignedInteger | T::SecretBoolean => {
                 return Err(PyValueError::new_err(format!("unsupported type: {t}",)));
             }
         };
@@ -328,6 +630,20 @@ impl EncryptedNadaType {
                     right_type: Box::new(right.into_nada_type(py)?),
                 }
             }
+            EncryptedNadaType::NTuple { types } => {
+                let types = types
+                    .into_iter()
+                    .map(|t| t.extract::<EncryptedNadaType>(py)?.into_nada_type(py))
+                    .collect::<PyResult<_>>()?;
+                T::NTuple { types }
+            }
+            EncryptedNadaType::Object { types } => {
+                let types = types
+                    .into_iter()
+                    .map(|(k, t)| Ok((k, t.extract::<EncryptedNadaType>(py)?.into_nada_type(py)?)))
+                    .collect::<PyResult<_>>()?;
+                T::Object { types }
+            }
             EncryptedNadaType::EcdsaMessageDigest() => T::EcdsaDigestMessage,
             EncryptedNadaType::EcdsaSignature() => T::EcdsaSignature,
             EncryptedNadaType::EcdsaPrivateKey() => T::EcdsaPrivateKey,
@@ -355,6 +671,8 @@ impl EncryptedNadaType {
             Self::Boolean { .. } => "Boolean".into(),
             Self::Array { .. } => "Array {..}".into(),
             Self::Tuple { .. } => "Tuple {..}".into(),
+            Self::NTuple { .. } => "NTuple {..}".into(),
+            Self::Object { .. } => "Object {..}".into(),
             Self::EcdsaMessageDigest { .. } => "EcdsaMessageDigest {..}".into(),
             Self::EcdsaSignature { .. } => "EcdsaSignature".into(),
             Self::EcdsaPrivateKey { .. } => "EcdsaPrivateKey {..}".into(),
@@ -367,3 +685,581 @@ impl EncryptedNadaType {
         }
     }
 }
+
+/// The magic bytes that start every encoded bundle.
+const BUNDLE_MAGIC: &[u8; 4] = b"NILB";
+
+/// The current version of the bundle format.
+///
+/// A bundle is encoded as:
+///
+/// * The magic bytes `NILB` followed by a version byte.
+/// * The party id, length prefixed.
+/// * The size in bits of the safe prime, the polynomial degree and the creation time in seconds since the unix
+///   epoch.
+/// * The value count, followed by each value's name (length prefixed utf-8) and the value itself.
+///
+/// Every value starts with a one byte tag identifying its variant followed by its payload. Shares and byte strings
+/// are length prefixed, blobs are their share count, shares and original size, arrays are their element type,
+/// element count and elements, tuples are their two elements, n-tuples and objects are their element count and
+/// elements, with object fields preceded by their name. Private key shares are their index, share, shared public
+/// key and public shares. Types use the same tags as values, with arrays carrying their inner type and size.
+///
+/// Lengths, counts and numbers are unsigned LEB128 varints.
+const BUNDLE_VERSION: u8 = 1;
+
+const TAG_SHAMIR_SHARE_INTEGER: u8 = 0;
+const TAG_SHAMIR_SHARE_UNSIGNED_INTEGER: u8 = 1;
+const TAG_SHAMIR_SHARE_BOOLEAN: u8 = 2;
+const TAG_SHAMIR_SHARES_BLOB: u8 = 3;
+const TAG_PUBLIC_INTEGER: u8 = 4;
+const TAG_PUBLIC_UNSIGNED_INTEGER: u8 = 5;
+const TAG_PUBLIC_BOOLEAN: u8 = 6;
+const TAG_ARRAY: u8 = 7;
+const TAG_TUPLE: u8 = 8;
+const TAG_NTUPLE: u8 = 9;
+const TAG_OBJECT: u8 = 10;
+const TAG_ECDSA_MESSAGE_DIGEST: u8 = 11;
+const TAG_ECDSA_SIGNATURE: u8 = 12;
+const TAG_ECDSA_PRIVATE_KEY: u8 = 13;
+const TAG_ECDSA_PUBLIC_KEY: u8 = 14;
+const TAG_STORE_ID: u8 = 15;
+const TAG_EDDSA_PRIVATE_KEY: u8 = 16;
+const TAG_EDDSA_PUBLIC_KEY: u8 = 17;
+const TAG_EDDSA_SIGNATURE: u8 = 18;
+const TAG_EDDSA_MESSAGE: u8 = 19;
+
+/// The encrypted values a party received, along with the metadata needed to unmask them later on.
+///
+/// Bundles can be persisted with `save` and `to_bytes`, sealed with a passphrase or a key with `seal`, and passed
+/// as is to `PartyJar.add_element`.
+///
+/// Arguments
+/// ---------
+/// party : PartyId
+///     The party the values belong to.
+/// values : Dict[str, EncryptedNadaValue]
+///     The party's encrypted values.
+/// prime_bits : int
+///     The size of the safe prime the values were masked with: 64, 128 or 256.
+/// polynomial_degree : int
+///     The degree of the polynomial the values were masked with.
+/// created_at : int, optional
+///     The creation time in seconds since the unix epoch, defaults to now.
+///
+/// Example
+/// -------
+///
+/// .. code-block:: py3
+///
+///     bundle = EncryptedBundle(party, masked[party], prime_bits=64, polynomial_degree=1)
+///     bundle.save("party.bundle")
+///     jar.add_element(party, EncryptedBundle.load("party.bundle"))
+#[pyclass]
+#[derive(Clone)]
+pub struct EncryptedBundle {
+    /// The party the values belong to.
+    #[pyo3(get)]
+    party: PartyId,
+
+    modulo: EncodedModulo,
+
+    /// The degree of the polynomial the values were masked with.
+    #[pyo3(get)]
+    polynomial_degree: u64,
+
+    /// The creation time in seconds since the unix epoch.
+    #[pyo3(get)]
+    created_at: u64,
+
+    /// The party's encrypted values.
+    #[pyo3(get)]
+    values: IndexMap<String, EncryptedNadaValue>,
+}
+
+impl EncryptedBundle {
+    /// The party the values belong to.
+    pub(crate) fn party(&self) -> &PartyId {
+        &self.party
+    }
+
+    /// The modulo the values were masked with.
+    pub(crate) fn modulo(&self) -> EncodedModulo {
+        self.modulo
+    }
+
+    /// The degree of the polynomial the values were masked with.
+    pub(crate) fn polynomial_degree(&self) -> u64 {
+        self.polynomial_degree
+    }
+
+    /// Consume this bundle, returning its values.
+    pub(crate) fn into_values(self) -> IndexMap<String, EncryptedNadaValue> {
+        self.values
+    }
+
+    fn encode(&self, py: Python<'_>) -> PyResult<Zeroizing<Vec<u8>>> {
+        let mut output = Zeroizing::new(BUNDLE_MAGIC.to_vec());
+        output.push(BUNDLE_VERSION);
+        write_bytes(&mut output, self.party.inner.as_bytes());
+        write_varint(&mut output, prime_bits(self.modulo).into());
+        write_varint(&mut output, self.polynomial_degree);
+        write_varint(&mut output, self.created_at);
+        write_varint(&mut output, self.values.len() as u64);
+        for (name, value) in &self.values {
+            write_bytes(&mut output, name.as_bytes());
+            write_encrypted_value(py, &mut output, value)?;
+        }
+        Ok(output)
+    }
+
+    /// Decode a bundle, unsealing it first if a party and a secret are provided.
+    fn open(py: Python<'_>, bytes: &[u8], party: Option<PartyId>, secret: Option<Secret>) -> PyResult<Self> {
+        match (party, secret) {
+            (None, None) => Self::decode(py, bytes),
+            (Some(party), Some(secret)) => {
+                let bundle = Self::decode(py, &sealing::unseal(bytes, &party, secret)?)?;
+                if bundle.party != party {
+                    return Err(PyValueError::new_err(format!(
+                        "sealed bundle holds the values of party {}, not {}",
+                        bundle.party.inner, party.inner
+                    )));
+                }
+                Ok(bundle)
+            }
+            (None, Some(_)) => Err(PyValueError::new_err("unsealing a bundle requires the party it belongs to")),
+            (Some(_), None) => Err(PyValueError::new_err("unsealing a bundle requires a passphrase or a key")),
+        }
+    }
+
+    fn decode(py: Python<'_>, bytes: &[u8]) -> PyResult<Self> {
+        if sealing::is_sealed(bytes) {
+            return Err(PyValueError::new_err("bundle is sealed, a party and a passphrase or key are required"));
+        }
+        let (magic, bytes) = bytes.split_at(bytes.len().min(BUNDLE_MAGIC.len()));
+        if magic != BUNDLE_MAGIC {
+            return Err(PyValueError::new_err("not an encrypted bundle"));
+        }
+        let mut reader = Reader::new(bytes);
+        match reader.read_u8()? {
+            BUNDLE_VERSION => (),
+            version => return Err(PyValueError::new_err(format!("unsupported bundle version: {version}"))),
+        };
+        let party = PartyId { inner: ::nillion_client_core::values::PartyId::from(reader.read_bytes()?.to_vec()) };
+        let prime_bits =
+            u32::try_from(reader.read_varint()?).map_err(|_| PyValueError::new_err("prime size is too large"))?;
+        let modulo = modulo_from_prime_bits(prime_bits)?;
+        let polynomial_degree = reader.read_varint()?;
+        let created_at = reader.read_varint()?;
+        let count = reader.read_count()?;
+        let mut values = IndexMap::with_capacity(count);
+        for _ in 0..count {
+            let name = reader.read_string()?;
+            let value = read_encrypted_value(py, &mut reader, 0)?;
+            if values.insert(name.clone(), value).is_some() {
+                return Err(PyValueError::new_err(format!("duplicate value '{name}'")));
+            }
+        }
+        reader.finish()?;
+        Ok(Self { party, modulo, polynomial_degree, created_at, values })
+    }
+}
+
+#[pymethods]
+impl EncryptedBundle {
+    #[new]
+    #[pyo3(signature = (party, values, prime_bits, polynomial_degree, created_at=None))]
+    fn new(
+        party: PartyId,
+        values: IndexMap<String, EncryptedNadaValue>,
+        prime_bits: u32,
+        polynomial_degree: u64,
+        created_at: Option<u64>,
+    ) -> PyResult<Self> {
+        let modulo = modulo_from_prime_bits(prime_bits)?;
+        let created_at = match created_at {
+            Some(created_at) => created_at,
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_err(|_| PyValueError::new_err("system clock is before the unix epoch"))?
+                .as_secs(),
+        };
+        Ok(Self { party, modulo, polynomial_degree, created_at, values })
+    }
+
+    /// The size in bits of the safe prime the values were masked with.
+    #[getter]
+    fn prime_bits(&self) -> u32 {
+        prime_bits(self.modulo)
+    }
+
+    /// Encode this bundle.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new_bound(py, &self.encode(py)?))
+    }
+
+    /// Decode a bundle produced by `to_bytes`.
+    #[staticmethod]
+    fn from_bytes(py: Python<'_>, bytes: &[u8]) -> PyResult<Self> {
+        Self::decode(py, bytes)
+    }
+
+    /// Seal this bundle with a passphrase or a raw 32 byte key.
+    ///
+    /// The bundle is encrypted with XChaCha20-Poly1305, using a key derived from the passphrase with Argon2id, and
+    /// bound to this bundle's party: it can only be unsealed as that party's, and any tampering is detected.
+    #[pyo3(signature = (passphrase=None, key=None))]
+    fn seal<'py>(
+        &self,
+        py: Python<'py>,
+        passphrase: Option<&str>,
+        key: Option<&[u8]>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let secret = Secret::from_args(passphrase, key)?
+            .ok_or_else(|| PyValueError::new_err("a passphrase or a key is required"))?;
+        let sealed = sealing::seal(&self.encode(py)?, &self.party, secret)?;
+        Ok(PyBytes::new_bound(py, &sealed))
+    }
+
+    /// Unseal a bundle produced by `seal` for the given party.
+    #[staticmethod]
+    #[pyo3(signature = (sealed, party, passphrase=None, key=None))]
+    fn unseal(
+        py: Python<'_>,
+        sealed: &[u8],
+        party: PartyId,
+        passphrase: Option<&str>,
+        key: Option<&[u8]>,
+    ) -> PyResult<Self> {
+        Self::open(py, sealed, Some(party), Secret::from_args(passphrase, key)?)
+    }
+
+    /// Write this bundle to a file, sealing it if a passphrase or a key is provided.
+    #[pyo3(signature = (path, passphrase=None, key=None))]
+    fn save(&self, py: Python<'_>, path: PathBuf, passphrase: Option<&str>, key: Option<&[u8]>) -> PyResult<()> {
+        let encoded = self.encode(py)?;
+        match Secret::from_args(passphrase, key)? {
+            Some(secret) => fs::write(path, sealing::seal(&encoded, &self.party, secret)?)?,
+            None => fs::write(path, encoded)?,
+        };
+        Ok(())
+    }
+
+    /// Read a bundle written by `save`.
+    ///
+    /// Sealed bundles require the party they belong to and the passphrase or key they were sealed with.
+    #[staticmethod]
+    #[pyo3(signature = (path, party=None, passphrase=None, key=None))]
+    fn load(
+        py: Python<'_>,
+        path: PathBuf,
+        party: Option<PartyId>,
+        passphrase: Option<&str>,
+        key: Option<&[u8]>,
+    ) -> PyResult<Self> {
+        let bytes = Zeroizing::new(fs::read(path)?);
+        Self::open(py, &bytes, party, Secret::from_args(passphrase, key)?)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "EncryptedBundle(party={}, prime_bits={}, polynomial_degree={}, created_at={}, values={:?})",
+            self.party.__repr__(),
+            prime_bits(self.modulo),
+            self.polynomial_degree,
+            self.created_at,
+            self.values.keys().collect::<Vec<_>>()
+        )
+    }
+}
+
+fn write_encrypted_value(py: Python<'_>, output: &mut Vec<u8>, value: &EncryptedNadaValue) -> PyResult<()> {
+    use EncryptedNadaValue as E;
+    match value {
+        E::ShamirShareInteger { value } => {
+            output.push(TAG_SHAMIR_SHARE_INTEGER);
+            write_bytes(output, value);
+        }
+        E::ShamirShareUnsignedInteger { value } => {
+            output.push(TAG_SHAMIR_SHARE_UNSIGNED_INTEGER);
+            write_bytes(output, value);
+        }
+        E::ShamirShareBoolean { value } => {
+            output.push(TAG_SHAMIR_SHARE_BOOLEAN);
+            write_bytes(output, value);
+        }
+        E::ShamirSharesBlob { values, original_size } => {
+            output.push(TAG_SHAMIR_SHARES_BLOB);
+            write_varint(output, values.len() as u64);
+            for share in values {
+                write_bytes(output, share);
+            }
+            write_varint(output, *original_size);
+        }
+        E::PublicInteger { value } => {
+            output.push(TAG_PUBLIC_INTEGER);
+            write_bytes(output, value);
+        }
+        E::PublicUnsignedInteger { value } => {
+            output.push(TAG_PUBLIC_UNSIGNED_INTEGER);
+            write_bytes(output, value);
+        }
+        E::PublicBoolean { value } => {
+            output.push(TAG_PUBLIC_BOOLEAN);
+            write_bytes(output, value);
+        }
+        E::Array { inner_type, values } => {
+            output.push(TAG_ARRAY);
+            write_encrypted_type(py, output, inner_type)?;
+            write_varint(output, values.len() as u64);
+            for value in values {
+                write_encrypted_value(py, output, value)?;
+            }
+        }
+        E::Tuple { left, right } => {
+            output.push(TAG_TUPLE);
+            write_encrypted_value(py, output, left.get())?;
+            write_encrypted_value(py, output, right.get())?;
+        }
+        E::NTuple { values } => {
+            output.push(TAG_NTUPLE);
+            write_varint(output, values.len() as u64);
+            for value in values {
+                write_encrypted_value(py, output, value)?;
+            }
+        }
+        E::Object { values } => {
+            output.push(TAG_OBJECT);
+            write_varint(output, values.len() as u64);
+            for (name, value) in values {
+                write_bytes(output, name.as_bytes());
+                write_encrypted_value(py, output, value)?;
+            }
+        }
+        E::EcdsaMessageDigest { value } => {
+            output.push(TAG_ECDSA_MESSAGE_DIGEST);
+            write_bytes(output, value);
+        }
+        E::EcdsaSignature { r, sigma } => {
+            output.push(TAG_ECDSA_SIGNATURE);
+            write_bytes(output, r);
+            write_bytes(output, sigma);
+        }
+        E::EcdsaPrivateKey { i, x, shared_public_key, public_shares } => {
+            output.push(TAG_ECDSA_PRIVATE_KEY);
+            write_key_share(output, *i, x, shared_public_key, public_shares);
+        }
+        E::EcdsaPublicKey { value } => {
+            output.push(TAG_ECDSA_PUBLIC_KEY);
+            write_bytes(output, value);
+        }
+        E::StoreId { value } => {
+            output.push(TAG_STORE_ID);
+            write_bytes(output, value);
+        }
+        E::EddsaPrivateKey { i, x, shared_public_key, public_shares } => {
+            output.push(TAG_EDDSA_PRIVATE_KEY);
+            write_key_share(output, *i, x, shared_public_key, public_shares);
+        }
+        E::EddsaPublicKey { value } => {
+            output.push(TAG_EDDSA_PUBLIC_KEY);
+            write_bytes(output, value);
+        }
+        E::EddsaSignature { value } => {
+            output.push(TAG_EDDSA_SIGNATURE);
+            write_bytes(output, value);
+        }
+        E::EddsaMessage { value } => {
+            output.push(TAG_EDDSA_MESSAGE);
+            write_bytes(output, value);
+        }
+    }
+    Ok(())
+}
+
+fn write_key_share(output: &mut Vec<u8>, i: u16, x: &[u8], shared_public_key: &[u8], public_shares: &[Vec<u8>]) {
+    write_varint(output, i.into());
+    write_bytes(output, x);
+    write_bytes(output, shared_public_key);
+    write_varint(output, public_shares.len() as u64);
+    for share in public_shares {
+        write_bytes(output, share);
+    }
+}
+
+fn write_encrypted_type(py: Python<'_>, output: &mut Vec<u8>, nada_type: &EncryptedNadaType) -> PyResult<()> {
+    use EncryptedNadaType as T;
+    let tag = match nada_type {
+        T::ShamirShareInteger() => TAG_SHAMIR_SHARE_INTEGER,
+        T::ShamirShareUnsignedInteger() => TAG_SHAMIR_SHARE_UNSIGNED_INTEGER,
+        T::ShamirShareBoolean() => TAG_SHAMIR_SHARE_BOOLEAN,
+        T::ShamirShareSecretBlob() => TAG_SHAMIR_SHARES_BLOB,
+        T::Integer() => TAG_PUBLIC_INTEGER,
+        T::UnsignedInteger() => TAG_PUBLIC_UNSIGNED_INTEGER,
+        T::Boolean() => TAG_PUBLIC_BOOLEAN,
+        T::Array { inner_type, size } => {
+            output.push(TAG_ARRAY);
+            write_encrypted_type(py, output, &inner_type.extract(py)?)?;
+            write_varint(output, *size);
+            return Ok(());
+        }
+        T::Tuple { left, right } => {
+            output.push(TAG_TUPLE);
+            write_encrypted_type(py, output, &left.extract(py)?)?;
+            write_encrypted_type(py, output, &right.extract(py)?)?;
+            return Ok(());
+        }
+        T::NTuple { types } => {
+            output.push(TAG_NTUPLE);
+            write_varint(output, types.len() as u64);
+            for element_type in types {
+                write_encrypted_type(py, output, &element_type.extract(py)?)?;
+            }
+            return Ok(());
+        }
+        T::Object { types } => {
+            output.push(TAG_OBJECT);
+            write_varint(output, types.len() as u64);
+            for (name, field_type) in types {
+                write_bytes(output, name.as_bytes());
+                write_encrypted_type(py, output, &field_type.extract(py)?)?;
+            }
+            return Ok(());
+        }
+        T::EcdsaMessageDigest() => TAG_ECDSA_MESSAGE_DIGEST,
+        T::EcdsaSignature() => TAG_ECDSA_SIGNATURE,
+        T::EcdsaPrivateKey() => TAG_ECDSA_PRIVATE_KEY,
+        T::EcdsaPublicKey() => TAG_ECDSA_PUBLIC_KEY,
+        T::StoreId() => TAG_STORE_ID,
+        T::EddsaPrivateKey() => TAG_EDDSA_PRIVATE_KEY,
+        T::EddsaPublicKey() => TAG_EDDSA_PUBLIC_KEY,
+        T::EddsaSignature() => TAG_EDDSA_SIGNATURE,
+        T::EddsaMessage() => TAG_EDDSA_MESSAGE,
+    };
+    output.push(tag);
+    Ok(())
+}
+
+fn read_encrypted_value(py: Python<'_>, reader: &mut Reader<'_>, depth: usize) -> PyResult<EncryptedNadaValue> {
+    use EncryptedNadaValue as E;
+    check_depth(depth)?;
+    let share = |reader: &mut Reader<'_>| -> PyResult<ShareBytes> { Ok(reader.read_bytes()?.to_vec().into()) };
+    let value = match reader.read_u8()? {
+        TAG_SHAMIR_SHARE_INTEGER => E::ShamirShareInteger { value: share(reader)? },
+        TAG_SHAMIR_SHARE_UNSIGNED_INTEGER => E::ShamirShareUnsignedInteger { value: share(reader)? },
+        TAG_SHAMIR_SHARE_BOOLEAN => E::ShamirShareBoolean { value: share(reader)? },
+        TAG_SHAMIR_SHARES_BLOB => {
+            let count = reader.read_count()?;
+            let values = (0..count).map(|_| share(reader)).collect::<PyResult<_>>()?;
+            E::ShamirSharesBlob { values, original_size: reader.read_varint()? }
+        }
+        TAG_PUBLIC_INTEGER => E::PublicInteger { value: reader.read_bytes()?.to_vec() },
+        TAG_PUBLIC_UNSIGNED_INTEGER => E::PublicUnsignedInteger { value: reader.read_bytes()?.to_vec() },
+        TAG_PUBLIC_BOOLEAN => E::PublicBoolean { value: reader.read_bytes()?.to_vec() },
+        TAG_ARRAY => {
+            let inner_type = read_encrypted_type(py, reader, depth + 1)?;
+            let count = reader.read_count()?;
+            let values = (0..count).map(|_| read_encrypted_value(py, reader, depth + 1)).collect::<PyResult<_>>()?;
+            E::Array { inner_type, values }
+        }
+        TAG_TUPLE => {
+            let left = Py::new(py, read_encrypted_value(py, reader, depth + 1)?)?;
+            let right = Py::new(py, read_encrypted_value(py, reader, depth + 1)?)?;
+            E::Tuple { left, right }
+        }
+        TAG_NTUPLE => {
+            let count = reader.read_count()?;
+            E::NTuple {
+                values: (0..count).map(|_| read_encrypted_value(py, reader, depth + 1)).collect::<PyResult<_>>()?,
+            }
+        }
+        TAG_OBJECT => {
+            let count = reader.read_count()?;
+            let mut values = IndexMap::with_capacity(count);
+            for _ in 0..count {
+                let name = reader.read_string()?;
+                values.insert(name, read_encrypted_value(py, reader, depth + 1)?);
+            }
+            E::Object { values }
+        }
+        TAG_ECDSA_MESSAGE_DIGEST => E::EcdsaMessageDigest { value: reader.read_bytes()?.to_vec() },
+        TAG_ECDSA_SIGNATURE => {
+            let r = reader.read_bytes()?.to_vec();
+            E::EcdsaSignature { r, sigma: reader.read_bytes()?.to_vec() }
+        }
+        TAG_ECDSA_PRIVATE_KEY => {
+            let (i, x, shared_public_key, public_shares) = read_key_share(reader)?;
+            E::EcdsaPrivateKey { i, x, shared_public_key, public_shares }
+        }
+        TAG_ECDSA_PUBLIC_KEY => E::EcdsaPublicKey { value: reader.read_bytes()?.to_vec() },
+        TAG_STORE_ID => E::StoreId { value: reader.read_bytes()?.to_vec() },
+        TAG_EDDSA_PRIVATE_KEY => {
+            let (i, x, shared_public_key, public_shares) = read_key_share(reader)?;
+            E::EddsaPrivateKey { i, x, shared_public_key, public_shares }
+        }
+        TAG_EDDSA_PUBLIC_KEY => E::EddsaPublicKey { value: reader.read_bytes()?.to_vec() },
+        TAG_EDDSA_SIGNATURE => E::EddsaSignature { value: reader.read_bytes()?.to_vec() },
+        TAG_EDDSA_MESSAGE => E::EddsaMessage { value: reader.read_bytes()?.to_vec() },
+        tag => return Err(PyValueError::new_err(format!("unknown value tag: {tag}"))),
+    };
+    Ok(value)
+}
+
+#[allow(clippy::type_complexity)]
+fn read_key_share(reader: &mut Reader<'_>) -> PyResult<(u16, ShareBytes, Vec<u8>, Vec<Vec<u8>>)> {
+    let i = u16::try_from(reader.read_varint()?).map_err(|_| PyValueError::new_err("key share index is too large"))?;
+    let x = reader.read_bytes()?.to_vec().into();
+    let shared_public_key = reader.read_bytes()?.to_vec();
+    let count = reader.read_count()?;
+    let public_shares = (0..count).map(|_| Ok(reader.read_bytes()?.to_vec())).collect::<PyResult<_>>()?;
+    Ok((i, x, shared_public_key, public_shares))
+}
+
+fn read_encrypted_type(py: Python<'_>, reader: &mut Reader<'_>, depth: usize) -> PyResult<EncryptedNadaType> {
+    use EncryptedNadaType as T;
+    check_depth(depth)?;
+    let nada_type = match reader.read_u8()? {
+        TAG_SHAMIR_SHARE_INTEGER => T::ShamirShareInteger(),
+        TAG_SHAMIR_SHARE_UNSIGNED_INTEGER => T::ShamirShareUnsignedInteger(),
+        TAG_SHAMIR_SHARE_BOOLEAN => T::ShamirShareBoolean(),
+        TAG_SHAMIR_SHARES_BLOB => T::ShamirShareSecretBlob(),
+        TAG_PUBLIC_INTEGER => T::Integer(),
+        TAG_PUBLIC_UNSIGNED_INTEGER => T::UnsignedInteger(),
+        TAG_PUBLIC_BOOLEAN => T::Boolean(),
+        TAG_ARRAY => {
+            let inner_type = read_encrypted_type(py, reader, depth + 1)?.into_py(py);
+            T::Array { inner_type, size: reader.read_varint()? }
+        }
+        TAG_TUPLE => {
+            let left = read_encrypted_type(py, reader, depth + 1)?.into_py(py);
+            T::Tuple { left, right: read_encrypted_type(py, reader, depth + 1)?.into_py(py) }
+        }
+        TAG_NTUPLE => {
+            let count = reader.read_count()?;
+            let types = (0..count)
+                .map(|_| Ok(read_encrypted_type(py, reader, depth + 1)?.into_py(py)))
+                .collect::<PyResult<_>>()?;
+            T::NTuple { types }
+        }
+        TAG_OBJECT => {
+            let count = reader.read_count()?;
+            let mut types = IndexMap::with_capacity(count);
+            for _ in 0..count {
+                let name = reader.read_string()?;
+                types.insert(name, read_encrypted_type(py, reader, depth + 1)?.into_py(py));
+            }
+            T::Object { types }
+        }
+        TAG_ECDSA_MESSAGE_DIGEST => T::EcdsaMessageDigest(),
+        TAG_ECDSA_SIGNATURE => T::EcdsaSignature(),
+        TAG_ECDSA_PRIVATE_KEY => T::EcdsaPrivateKey(),
+        TAG_ECDSA_PUBLIC_KEY => T::EcdsaPublicKey(),
+        TAG_STORE_ID => T::StoreId(),
+        TAG_EDDSA_PRIVATE_KEY => T::EddsaPrivateKey(),
+        TAG_EDDSA_PUBLIC_KEY => T::EddsaPublicKey(),
+        TAG_EDDSA_SIGNATURE => T::EddsaSignature(),
+        TAG_EDDSA_MESSAGE => T::EddsaMessage(),
+        tag => return Err(PyValueError::new_err(format!("unknown type tag: {tag}"))),
+    };
+    Ok(nada_type)
+}
@@ -1,15 +1,38 @@
-use crate::values::{nada_values_clear_to_pydict, pydict_to_nada_values_clear};
-use ::nillion_client_core::values::{EncodedModulo, ShamirError};
-use encrypted_value::EncryptedNadaValue;
+use crate::values::{
+    envelope, nada_values_clear_to_pydict, pydict_to_nada_values_clear, schema::NadaValuesSchema, secret::wipe,
+};
+use ::nillion_client_core::values::{Clear, Encoded, EncodedModulo, Encrypted, NadaValue, ShamirError};
+use consistency::{find_mismatches, PublicValueMismatch};
+use encrypted_value::{EncodingBounds, EncryptedBundle, EncryptedNadaValue};
 use pyo3::{
     exceptions::PyValueError,
     prelude::*,
     types::{PyBytes, PyDict},
 };
-use std::collections::HashMap;
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+use ranges::{check_ranges, find_range_violations, modulo_from_prime_name, prime_bits, RangeViolation};
+use rayon::{
+    iter::{IntoParallelIterator, ParallelIterator},
+    ThreadPool, ThreadPoolBuilder,
+};
+use robust::RobustUnmaskResult;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
+pub(crate) mod consistency;
 pub(crate) mod encrypted_value;
 pub(crate) mod programs;
+pub(crate) mod protobuf;
+pub(crate) mod ranges;
+pub(crate) mod robust;
+pub(crate) mod sealing;
+pub(crate) mod streaming;
 pub(crate) mod values;
 
 #[cfg(test)]
@@ -31,10 +54,15 @@ fn nillion_client_core(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     values::add_module(py, m)?;
     encrypted_value::add_module(py, m)?;
     programs::add_module(py, m)?;
+    protobuf::add_module(py, m)?;
     m.add_class::<PartyId>()?;
     m.add_class::<PartyJar>()?;
+    m.add_class::<PublicValueMismatch>()?;
     m.add_class::<NadaValuesClassification>()?;
+    m.add_class::<RangeViolation>()?;
+    m.add_class::<RobustUnmaskResult>()?;
     m.add_class::<SecretMasker>()?;
+    m.add_class::<MaskingPool>()?;
 
     Ok(())
 }
@@ -65,36 +93,153 @@ impl From<PartyId> for ::nillion_client_core::values::PartyId {
 }
 
 /// A jar where every party puts an element.
+///
+/// Values can be unmasked once `polynomial_degree + 1` of the masker's parties have put their element in it.
 #[pyclass]
 #[derive(Clone)]
 struct PartyJar {
     inner: ::nillion_client_core::values::PartyJar<::nillion_client_core::values::EncryptedValues>,
     modulo: EncodedModulo,
+    parties: Vec<PartyId>,
+    polynomial_degree: u64,
+    contributed: Vec<PartyId>,
+    public_values: HashMap<PartyId, BTreeMap<String, Vec<u8>>>,
 }
 
 impl PartyJar {
-    fn new(party_count: usize, modulo: EncodedModulo) -> Self {
-        Self { inner: ::nillion_client_core::values::PartyJar::new(party_count), modulo }
+    fn new(parties: Vec<PartyId>, polynomial_degree: u64, modulo: EncodedModulo) -> Self {
+        Self {
+            inner: ::nillion_client_core::values::PartyJar::new(parties.len()),
+            modulo,
+            parties,
+            polynomial_degree,
+            contributed: Vec::new(),
+            public_values: HashMap::new(),
+        }
+    }
+
+    /// The number of parties whose shares are needed to unmask values.
+    fn threshold(&self) -> usize {
+        usize::try_from(self.polynomial_degree).unwrap_or(usize::MAX).saturating_add(1)
+    }
+
+    /// Check that enough parties have put their element in this jar to unmask values.
+    fn check_sufficient(&self) -> PyResult<()> {
+        if self.is_sufficient() {
+            return Ok(());
+        }
+        let missing = self.missing_parties().iter().map(|party| party.inner.to_string()).collect::<Vec<_>>();
+        Err(PyValueError::new_err(format!(
+            "unmasking requires shares from at least {} of {} parties, got {}; missing parties: {}",
+            self.threshold(),
+            self.parties.len(),
+            self.contributed.len(),
+            missing.join(", ")
+        )))
     }
 }
 
 #[pymethods]
 impl PartyJar {
-    pub fn add_element(
-        &mut self,
-        py: Python<'_>,
-        party: PartyId,
-        values: HashMap<String, EncryptedNadaValue>,
-    ) -> PyResult<()> {
+    /// Add the values received from a party, either as a dictionary or as an encrypted bundle.
+    ///
+    /// Unless `checked` is false, every share is checked to be correctly encoded for this jar's modulo and
+    /// arrays are checked against their element type, so that corrupt or truncated values are rejected
+    /// instead of unmasking into wrong secrets. Errors name the party and the path of the offending value.
+    ///
+    /// Bundles must belong to `party` and use this jar's modulo and polynomial degree.
+    #[pyo3(signature = (party, values, checked=true))]
+    pub fn add_element(&mut self, py: Python<'_>, party: PartyId, values: PartyElement, checked: bool) -> PyResult<()> {
+        if !self.parties.contains(&party) {
+            return Err(PyValueError::new_err(format!("party {} is not one of the masker's parties", party.inner)));
+        }
+        if self.contributed.contains(&party) {
+            return Err(PyValueError::new_err(format!("party {} already put an element in the jar", party.inner)));
+        }
+        let values = match values {
+            PartyElement::Values(values) => values.into_iter().collect::<Vec<_>>(),
+            PartyElement::Bundle(bundle) => {
+                if bundle.party() != &party {
+                    return Err(PyValueError::new_err(format!(
+                        "bundle belongs to party {}, not {}",
+                        bundle.party().inner,
+                        party.inner
+                    )));
+                }
+                if bundle.modulo() != self.modulo {
+                    return Err(PyValueError::new_err(format!(
+                        "bundle uses a {} bit prime but the jar uses a {} bit prime",
+                        prime_bits(bundle.modulo()),
+                        prime_bits(self.modulo)
+                    )));
+                }
+                if bundle.polynomial_degree() != self.polynomial_degree {
+                    return Err(PyValueError::new_err(format!(
+                        "bundle was masked with polynomial degree {} but the jar uses polynomial degree {}",
+                        bundle.polynomial_degree(),
+                        self.polynomial_degree
+                    )));
+                }
+                bundle.into_values().into_iter().collect()
+            }
+        };
+        let mut public_values = BTreeMap::new();
+        for (name, value) in &values {
+            value.collect_public(name, &mut public_values);
+        }
+        let bounds = checked.then(|| EncodingBounds::new(self.modulo));
         let values = values
             .into_iter()
-            .map(|(k, v)| v.into_nada_value(py, self.modulo).map(|v| (k, v)))
-            .collect::<Result<_, _>>()?;
+            .map(|(name, value)| {
+                if let Some(bounds) = &bounds {
+                    value
+                        .validate(py, bounds, &name)
+                        .map_err(|e| PyValueError::new_err(format!("party {}: {e}", party.inner)))?;
+                }
+                let value = value.into_nada_value(py, self.modulo).map_err(|e| {
+                    PyValueError::new_err(format!("party {}: value '{name}': {}", party.inner, e.value_bound(py)))
+                })?;
+                Ok((name, value))
+            })
+            .collect::<PyResult<_>>()?;
         self.inner
-            .add_element(party.into(), values)
+            .add_element(party.clone().into(), values)
             .map_err(|err| PyValueError::new_err(format!("adding element into party jar failed: {}", err)))?;
+        self.public_values.insert(party.clone(), public_values);
+        self.contributed.push(party);
         Ok(())
     }
+
+    /// Whether enough parties have put their element in this jar to unmask values.
+    pub fn is_sufficient(&self) -> bool {
+        self.contributed.len() >= self.threshold()
+    }
+
+    /// The masker's parties that haven't put their element in this jar yet.
+    pub fn missing_parties(&self) -> Vec<PartyId> {
+        self.parties.iter().filter(|party| !self.contributed.contains(party)).cloned().collect()
+    }
+
+    /// Check that every party holds the same copy of public values and of the public metadata of key shares.
+    ///
+    /// Returns a mismatch for every value that some parties hold a different copy of, or don't hold at all, listing
+    /// the parties that differ from the majority; an empty list means the parties agree. Unmasking would otherwise
+    /// use any party's copy of these values.
+    pub fn verify_consistency(&self) -> Vec<PublicValueMismatch> {
+        let public = self
+            .parties
+            .iter()
+            .filter_map(|party| self.public_values.get(party).map(|values| (party.clone(), values)))
+            .collect::<Vec<_>>();
+        find_mismatches(&public)
+    }
+}
+
+/// The values a party puts in a jar.
+#[derive(FromPyObject)]
+enum PartyElement {
+    Bundle(EncryptedBundle),
+    Values(HashMap<String, EncryptedNadaValue>),
 }
 
 impl TryFrom<PartyJar> for ::nillion_client_core::values::PartyJar<::nillion_client_core::values::EncryptedValues> {
@@ -144,108 +289,657 @@ impl From<::nillion_client_core::values::NadaValuesClassification> for NadaValue
     }
 }
 
+/// A pool of threads that maskers mask and unmask values with.
+///
+/// A pool is handed to maskers when they're built, and can be shared by any number of them.
+#[pyclass(frozen, name = "ThreadPool")]
+#[derive(Clone)]
+struct MaskingPool {
+    inner: Arc<ThreadPool>,
+}
+
+#[pymethods]
+impl MaskingPool {
+    /// Build a pool of `threads` threads.
+    #[new]
+    fn new(threads: usize) -> PyResult<Self> {
+        if threads == 0 {
+            return Err(PyValueError::new_err("thread count must be at least 1"));
+        }
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|index| format!("secret-masker-{index}"))
+            .build()
+            .map_err(|err| PyValueError::new_err(format!("creating thread pool failed: {}", err)))?;
+        Ok(Self { inner: Arc::new(pool) })
+    }
+
+    /// The number of threads in this pool.
+    #[getter]
+    fn threads(&self) -> usize {
+        self.inner.current_num_threads()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ThreadPool(threads={})", self.threads())
+    }
+}
+
 /// A secret masker.
 ///
 /// This allows masking and unmasking secrets.
 #[pyclass]
 struct SecretMasker {
     inner: ::nillion_client_core::values::SecretMasker,
-    party_count: usize,
+    parties: Vec<PartyId>,
+    polynomial_degree: u64,
     modulo: EncodedModulo,
+    pool: Option<MaskingPool>,
+    seed: Option<[u8; 32]>,
+    mask_calls: AtomicU64,
 }
 
 impl SecretMasker {
-    fn new<B>(polynomial_degree: u64, parties: Vec<Py<PartyId>>, modulo: EncodedModulo, builder: B) -> PyResult<Self>
+    fn new<B>(
+        polynomial_degree: u64,
+        parties: Vec<Py<PartyId>>,
+        modulo: EncodedModulo,
+        seed: Option<&[u8]>,
+        pool: Option<MaskingPool>,
+        builder: B,
+    ) -> PyResult<Self>
     where
         B: Fn(
             u64,
             Vec<::nillion_client_core::values::PartyId>,
         ) -> Result<::nillion_client_core::values::SecretMasker, ShamirError>,
     {
-        let party_count = parties.len();
-        let inner = builder(
-            polynomial_degree,
-            parties.into_iter().map(|party| Python::with_gil(|py| party.borrow(py).clone()).inner).collect(),
-        )
-        .map_err(|err| PyValueError::new_err(format!("creating new secret masker failed: {}", err)))?;
-        Ok(Self { inner, party_count, modulo })
+        let parties =
+            parties.into_iter().map(|party| Python::with_gil(|py| party.borrow(py).clone())).collect::<Vec<_>>();
+        let inner = builder(polynomial_degree, parties.iter().map(|party| party.inner.clone()).collect())
+            .map_err(|err| PyValueError::new_err(format!("creating new secret masker failed: {}", err)))?;
+        let seed = seed.map(|seed| Sha256::digest(seed).into());
+        Ok(Self { inner, parties, polynomial_degree, modulo, pool, seed, mask_calls: AtomicU64::new(0) })
+    }
+
+    /// Mask a set of values, masking every value, and every element of an array, on its own in the thread pool if
+    /// there's one.
+    ///
+    /// Seeded maskers always mask values and array elements on their own, each with a generator derived from the
+    /// seed, the number of previous calls, the value's name and the element's index, so that the shares don't depend
+    /// on the order they're masked in. Shares for every party are generated together by a single value's masking, so
+    /// work is never split across parties.
+    fn mask_values(&self, values: HashMap<String, NadaValue<Clear>>) -> PyResult<MaskedValues> {
+        let seed = self.seed.as_ref().map(|seed| (seed, self.mask_calls.fetch_add(1, Ordering::Relaxed)));
+        let mask = |values: HashMap<String, NadaValue<Clear>>, rng: Option<ChaCha20Rng>| -> PyResult<MaskedValues> {
+            let masked = match rng {
+                Some(mut rng) => self.inner.mask_with_rng(values, &mut rng),
+                None => self.inner.mask(values),
+            }
+            .map_err(|err| PyValueError::new_err(format!("masking failed: {}", err)))?;
+            Ok(masked.into_iter().map(|(party, values)| (party, values.into_iter().collect())).collect())
+        };
+        if values.is_empty() || (self.pool.is_none() && seed.is_none()) {
+            return mask(values, None);
+        }
+        let mask_piece = |piece: Piece<NadaValue<Clear>>| {
+            let rng = seed.map(|(seed, call)| value_rng(seed, call, &piece.name, piece.index));
+            let masked = mask(std::iter::once((piece.name.clone(), piece.value)).collect(), rng)?;
+            let shares = masked
+                .into_iter()
+                .map(|(party, mut values)| {
+                    let share = values.remove(&piece.name).ok_or_else(|| {
+                        PyValueError::new_err(format!("masking failed: no share of '{}' for a party", piece.name))
+                    })?;
+                    Ok((party, share))
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+            Ok(Piece { name: piece.name, index: piece.index, value: shares })
+        };
+        let pieces = values.into_iter().flat_map(|(name, value)| split_value(name, value)).collect::<Vec<_>>();
+        let masked = match &self.pool {
+            None => pieces.into_iter().map(mask_piece).collect::<PyResult<Vec<_>>>()?,
+            Some(pool) => {
+                pool.inner.install(|| pieces.into_par_iter().map(mask_piece).collect::<PyResult<Vec<_>>>())?
+            }
+        };
+        let mut party_values = MaskedValues::new();
+        let mut party_elements: HashMap<_, BTreeMap<String, Vec<_>>> = HashMap::new();
+        for piece in masked {
+            for (party, share) in piece.value {
+                if piece.index.is_some() {
+                    party_elements.entry(party).or_default().entry(piece.name.clone()).or_default().push(share);
+                } else {
+                    party_values.entry(party).or_default().insert(piece.name.clone(), share);
+                }
+            }
+        }
+        for (party, arrays) in party_elements {
+            for (name, shares) in arrays {
+                party_values.entry(party.clone()).or_default().insert(name, join_shares(shares)?);
+            }
+        }
+        Ok(party_values)
+    }
+
+    /// Unmask the values in a jar, unmasking every value, and every element of an array, on its own in the thread
+    /// pool if there's one.
+    fn unmask_values(&self, jar: UpstreamPartyJar) -> PyResult<HashMap<String, NadaValue<Clear>>> {
+        let unmask = |jar: UpstreamPartyJar| {
+            self.inner.unmask(jar).map_err(|err| PyValueError::new_err(format!("unmasking failed: {}", err)))
+        };
+        let Some(pool) = &self.pool else {
+            return unmask(jar);
+        };
+        // Split the jar into one jar per value or array element, holding every party's share of it.
+        let mut shares: BTreeMap<String, Vec<_>> = BTreeMap::new();
+        for (party, values) in jar.into_elements() {
+            for (name, value) in values {
+                shares.entry(name).or_default().push((party.clone(), value));
+            }
+        }
+        let pieces = shares.into_iter().flat_map(|(name, shares)| split_shares(name, shares)).collect::<Vec<_>>();
+        let unmask_piece = |piece: Piece<Vec<(::nillion_client_core::values::PartyId, _)>>| {
+            let elements = piece
+                .value
+                .into_iter()
+                .map(|(party, share)| (party, std::iter::once((piece.name.clone(), share)).collect()))
+                .collect();
+            let jar = UpstreamPartyJar::new_with_elements(elements)
+                .map_err(|err| PyValueError::new_err(format!("converting party jar failed: {}", err)))?;
+            let value = unmask(jar)?
+                .remove(&piece.name)
+                .ok_or_else(|| PyValueError::new_err(format!("unmasking failed: value '{}' is missing", piece.name)))?;
+            Ok(Piece { name: piece.name, index: piece.index, value })
+        };
+        let unmasked = pool.inner.install(|| pieces.into_par_iter().map(unmask_piece).collect::<PyResult<Vec<_>>>())?;
+        let mut values = HashMap::new();
+        let mut arrays: BTreeMap<String, Vec<_>> = BTreeMap::new();
+        for piece in unmasked {
+            if piece.index.is_some() {
+                arrays.entry(piece.name).or_default().push(piece.value);
+            } else {
+                values.insert(piece.name, piece.value);
+            }
+        }
+        for (name, elements) in arrays {
+            values.insert(name, join_values(elements)?);
+        }
+        Ok(values)
     }
 }
 
+/// A value, an array element or the shares of either, that's masked or unmasked on its own.
+struct Piece<T> {
+    name: String,
+    /// The index of the element, if this is an element of an array.
+    index: Option<usize>,
+    value: T,
+}
+
+/// Split a value into pieces that can be masked on their own: arrays with several elements are split into their
+/// elements, any other value is a single piece.
+fn split_value(name: String, value: NadaValue<Clear>) -> Vec<Piece<NadaValue<Clear>>> {
+    match value {
+        NadaValue::Array { values, .. } if values.len() > 1 => values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| Piece { name: name.clone(), index: Some(index), value })
+            .collect(),
+        value => vec![Piece { name, index: None, value }],
+    }
+}
+
+/// Split every party's share of a value into pieces that can be unmasked on their own, the same way `split_value`
+/// splits values. Shares are only split if they're all arrays of the same length.
+fn split_shares<P: Clone>(
+    name: String,
+    shares: Vec<(P, NadaValue<Encrypted<Encoded>>)>,
+) -> Vec<Piece<Vec<(P, NadaValue<Encrypted<Encoded>>)>>> {
+    let length = match shares.first() {
+        Some((_, NadaValue::Array { values, .. })) if values.len() > 1 => values.len(),
+        _ => return vec![Piece { name, index: None, value: shares }],
+    };
+    if !shares.iter().all(|(_, share)| matches!(share, NadaValue::Array { values, .. } if values.len() == length)) {
+        return vec![Piece { name, index: None, value: shares }];
+    }
+    let mut pieces = (0..length)
+        .map(|index| Piece { name: name.clone(), index: Some(index), value: Vec::new() })
+        .collect::<Vec<_>>();
+    for (party, share) in shares {
+        if let NadaValue::Array { values, .. } = share {
+            for (piece, value) in pieces.iter_mut().zip(values) {
+                piece.value.push((party.clone(), value));
+            }
+        }
+    }
+    pieces
+}
+
+/// Join the shares of the elements of an array that was split by `split_value`, in order, back into an array.
+fn join_shares(elements: Vec<NadaValue<Encrypted<Encoded>>>) -> PyResult<NadaValue<Encrypted<Encoded>>> {
+    let inner_type = elements.first().map(NadaValue::to_type).ok_or_else(|| PyValueError::new_err("empty array"))?;
+    NadaValue::new_array(inner_type, elements)
+        .map_err(|e| PyValueError::new_err(format!("failed to create array: {e}")))
+}
+
+/// Join the elements of an array that was split by `split_shares`, in order, back into an array.
+fn join_values(elements: Vec<NadaValue<Clear>>) -> PyResult<NadaValue<Clear>> {
+    let inner_type = elements.first().map(NadaValue::to_type).ok_or_else(|| PyValueError::new_err("empty array"))?;
+    NadaValue::new_array(inner_type, elements)
+        .map_err(|e| PyValueError::new_err(format!("failed to create array: {e}")))
+}
+
+/// The generator a seeded masker masks a value, or an element of an array, with.
+fn value_rng(seed: &[u8; 32], call: u64, name: &str, index: Option<usize>) -> ChaCha20Rng {
+    let mut hasher = Sha256::new();
+    hasher.update(seed);
+    hasher.update(call.to_le_bytes());
+    hasher.update(name.as_bytes());
+    if let Some(index) = index {
+        hasher.update((index as u64).to_le_bytes());
+    }
+    ChaCha20Rng::from_seed(hasher.finalize().into())
+}
+
+/// Convert every party's masked values into their Python representation.
+fn party_values(
+    py: Python<'_>,
+    masked: MaskedValues,
+) -> PyResult<HashMap<PartyId, HashMap<String, EncryptedNadaValue>>> {
+    let mut party_values = HashMap::new();
+    for (party, values) in masked {
+        let mut named_values = HashMap::new();
+        for (name, value) in values {
+            named_values.insert(name, EncryptedNadaValue::new(py, value)?);
+        }
+        party_values.insert(PartyId { inner: party }, named_values);
+    }
+    Ok(party_values)
+}
+
+/// Every party's masked values.
+type MaskedValues = HashMap<::nillion_client_core::values::PartyId, HashMap<String, NadaValue<Encrypted<Encoded>>>>;
+
+type UpstreamPartyJar = ::nillion_client_core::values::PartyJar<::nillion_client_core::values::EncryptedValues>;
+
 #[pymethods]
 impl SecretMasker {
     /// Construct a new masker that uses a 64 bit safe prime under the hood.
+    ///
+    /// **For tests only**: passing a `seed` draws the masking randomness from a ChaCha20 generator seeded with it
+    /// instead of the operating system, so that a new masker built with the same seed produces byte-identical shares
+    /// for the same inputs. Anyone who knows the seed can recompute the shares, never use it with real secrets.
+    ///
+    /// Passing a `pool` masks and unmasks values and array elements in parallel with its threads.
     #[staticmethod]
-    pub fn new_64_bit_safe_prime(polynomial_degree: u64, parties: Vec<Py<PartyId>>) -> PyResult<Self> {
+    #[pyo3(signature = (polynomial_degree, parties, seed=None, pool=None))]
+    pub fn new_64_bit_safe_prime(
+        polynomial_degree: u64,
+        parties: Vec<Py<PartyId>>,
+        seed: Option<&[u8]>,
+        pool: Option<MaskingPool>,
+    ) -> PyResult<Self> {
         Self::new(
             polynomial_degree,
             parties,
             EncodedModulo::U64SafePrime,
+            seed,
+            pool,
             ::nillion_client_core::values::SecretMasker::new_64_bit_safe_prime,
         )
     }
 
     /// Construct a new masker that uses a 128 bit safe prime under the hood.
+    ///
+    /// Passing a `seed` makes masking deterministic and a `pool` makes it parallel, see `new_64_bit_safe_prime`.
     #[staticmethod]
-    pub fn new_128_bit_safe_prime(polynomial_degree: u64, parties: Vec<Py<PartyId>>) -> PyResult<Self> {
+    #[pyo3(signature = (polynomial_degree, parties, seed=None, pool=None))]
+    pub fn new_128_bit_safe_prime(
+        polynomial_degree: u64,
+        parties: Vec<Py<PartyId>>,
+        seed: Option<&[u8]>,
+        pool: Option<MaskingPool>,
+    ) -> PyResult<Self> {
         Self::new(
             polynomial_degree,
             parties,
             EncodedModulo::U128SafePrime,
+            seed,
+            pool,
             ::nillion_client_core::values::SecretMasker::new_128_bit_safe_prime,
         )
     }
 
     /// Construct a new masker that uses a 256 bit safe prime under the hood.
+    ///
+    /// Passing a `seed` makes masking deterministic and a `pool` makes it parallel, see `new_64_bit_safe_prime`.
     #[staticmethod]
-    pub fn new_256_bit_safe_prime(polynomial_degree: u64, parties: Vec<Py<PartyId>>) -> PyResult<Self> {
+    #[pyo3(signature = (polynomial_degree, parties, seed=None, pool=None))]
+    pub fn new_256_bit_safe_prime(
+        polynomial_degree: u64,
+        parties: Vec<Py<PartyId>>,
+        seed: Option<&[u8]>,
+        pool: Option<MaskingPool>,
+    ) -> PyResult<Self> {
         Self::new(
             polynomial_degree,
             parties,
             EncodedModulo::U256SafePrime,
+            seed,
+            pool,
             ::nillion_client_core::values::SecretMasker::new_256_bit_safe_prime,
         )
     }
 
+    /// Construct a new masker from the name of a cluster's prime, as in the membership `Cluster` message.
+    ///
+    /// The prime is one of `SAFE_64_BITS`, `SAFE_128_BITS` or `SAFE_256_BITS`. Passing a `seed` makes masking
+    /// deterministic and a `pool` makes it parallel, see `new_64_bit_safe_prime`.
+    #[staticmethod]
+    #[pyo3(signature = (prime, polynomial_degree, parties, seed=None, pool=None))]
+    pub fn from_prime(
+        prime: &str,
+        polynomial_degree: u64,
+        parties: Vec<Py<PartyId>>,
+        seed: Option<&[u8]>,
+        pool: Option<MaskingPool>,
+    ) -> PyResult<Self> {
+        match modulo_from_prime_name(prime)? {
+            EncodedModulo::U64SafePrime => Self::new_64_bit_safe_prime(polynomial_degree, parties, seed, pool),
+            EncodedModulo::U128SafePrime => Self::new_128_bit_safe_prime(polynomial_degree, parties, seed, pool),
+            EncodedModulo::U256SafePrime => Self::new_256_bit_safe_prime(polynomial_degree, parties, seed, pool),
+        }
+    }
+
+    /// The parties values are masked for.
+    #[getter]
+    pub fn parties(&self) -> Vec<PartyId> {
+        self.parties.clone()
+    }
+
+    /// The number of parties values are masked for.
+    #[getter]
+    pub fn party_count(&self) -> usize {
+        self.parties.len()
+    }
+
+    /// The degree of the polynomials values are masked with.
+    #[getter]
+    pub fn polynomial_degree(&self) -> u64 {
+        self.polynomial_degree
+    }
+
+    /// The size in bits of the safe prime values are masked with.
+    #[getter]
+    pub fn prime_bits(&self) -> u32 {
+        prime_bits(self.modulo)
+    }
+
+    /// The pool of threads values are masked and unmasked with, or `None` if they use the calling thread.
+    ///
+    /// Values and array elements are masked and unmasked independently of each other, so the results are the same
+    /// either way. The GIL is released while masking and unmasking regardless of the pool.
+    #[getter]
+    pub fn pool(&self) -> Option<MaskingPool> {
+        self.pool.clone()
+    }
+
+    /// The number of threads values are masked and unmasked with, or `None` if they use the calling thread.
+    #[getter]
+    pub fn threads(&self) -> Option<usize> {
+        self.pool.as_ref().map(MaskingPool::threads)
+    }
+
     /// Mask a set of values.
+    ///
+    /// If a schema is provided, plain Python values are coerced into the types it declares. An error naming the first
+    /// integer that can't be represented with this masker's prime is raised, see `validate_ranges` to get all of them.
+    #[pyo3(signature = (values, schema=None))]
     pub fn mask<'a>(
         &self,
         py: Python<'a>,
         values: &Bound<'a, PyDict>,
+        schema: Option<NadaValuesSchema>,
     ) -> PyResult<HashMap<PartyId, HashMap<String, EncryptedNadaValue>>> {
-        let nada_values = pydict_to_nada_values_clear(values)?;
-        let encrypted_values =
-            self.inner.mask(nada_values).map_err(|err| PyValueError::new_err(format!("masking failed: {}", err)))?;
-        let mut party_values = HashMap::new();
-        for (party, values) in encrypted_values {
-            let mut named_values = HashMap::new();
-            for (name, value) in values {
-                named_values.insert(name, EncryptedNadaValue::new(py, value)?);
-            }
-            party_values.insert(PartyId { inner: party }, named_values);
+        let mut nada_values = pydict_to_nada_values_clear(values, schema.as_ref())?;
+        if let Err(e) = check_ranges(&nada_values, self.modulo, "masker") {
+            nada_values.values_mut().for_each(wipe);
+            return Err(e);
         }
-        Ok(party_values)
+        let encrypted_values = py.allow_threads(|| self.mask_values(nada_values))?;
+        party_values(py, encrypted_values)
     }
 
     /// Unmask a set of values.
-    pub fn unmask<'a>(&self, py: Python<'a>, jar: PartyJar) -> PyResult<Bound<'a, PyDict>> {
-        let result = self
-            .inner
-            .unmask(jar.try_into()?)
-            .map_err(|err| PyValueError::new_err(format!("unmasking failed: {}", err)))?;
-        nada_values_clear_to_pydict(py, result)
+    ///
+    /// Any `polynomial_degree + 1` of the masker's parties are enough to unmask values, an error naming the missing
+    /// parties is raised if fewer have put their element in the jar.
+    ///
+    /// If a schema is provided, the values it declares as rationals are decoded into floats. Blobs created with
+    /// `SecretBlob.with_digest` are decompressed and checked against their digest, raising `BlobIntegrityError`
+    /// if they don't match.
+    #[pyo3(signature = (jar, schema=None))]
+    pub fn unmask<'a>(
+        &self,
+        py: Python<'a>,
+        jar: PartyJar,
+        schema: Option<NadaValuesSchema>,
+    ) -> PyResult<Bound<'a, PyDict>> {
+        jar.check_sufficient()?;
+        let jar = jar.try_into()?;
+        let mut result = py.allow_threads(|| self.unmask_values(jar))?;
+        envelope::open_values(&mut result)?;
+        match schema {
+            Some(schema) => schema.decode_values(py, result),
+            None => nada_values_clear_to_pydict(py, result),
+        }
+    }
+
+    /// Unmask a set of values, detecting and correcting faulty shares.
+    ///
+    /// This requires shares from more than `polynomial_degree + 1` parties. Every value is recovered from the only
+    /// polynomial that all but at most `(n - polynomial_degree - 1) / 2` of its `n` shares lie on, and the parties
+    /// whose shares don't lie on it, or that didn't provide a share of it, are reported as faulty. An error is raised
+    /// if there are too many faulty shares to recover a value.
+    ///
+    /// Faults are decided per value, so a party that corrupted any element of an array is faulty for the whole array.
+    /// This unmasks every value from several subsets of the shares, so it's much slower than `unmask`: at most 4096
+    /// subsets of `polynomial_degree + 1` shares are tried per value, each costing up to `n` unmasks.
+    #[pyo3(signature = (jar, schema=None))]
+    pub fn robust_unmask(
+        &self,
+        py: Python<'_>,
+        jar: PartyJar,
+        schema: Option<NadaValuesSchema>,
+    ) -> PyResult<RobustUnmaskResult> {
+        let threshold = jar.threshold();
+        let jar = jar.try_into()?;
+        let mut recovered = py.allow_threads(|| robust::unmask(&self.inner, threshold, jar))?;
+        envelope::open_values(&mut recovered.values)?;
+        let values = match schema {
+            Some(schema) => schema.decode_values(py, recovered.values),
+            None => nada_values_clear_to_pydict(py, recovered.values),
+        }?;
+        let faulty_parties = self
+            .parties
+            .iter()
+            .filter(|party| recovered.faults.values().any(|faulty| faulty.contains(&party.inner)))
+            .cloned()
+            .collect();
+        let faulty_keys = recovered
+            .faults
+            .into_iter()
+            .map(|(name, faulty)| (name, faulty.into_iter().map(|inner| PartyId { inner }).collect()))
+            .collect();
+        Ok(RobustUnmaskResult { values: values.unbind(), faulty_parties, faulty_keys })
+    }
+
+    /// Re-share the values in a jar for another masker, which can use other parties, polynomial degree or prime.
+    ///
+    /// The values are unmasked and masked again for `target` without leaving Rust, so their cleartext is never
+    /// handed to Python. ECDSA and EdDSA private keys are split into fresh key shares for the target's parties, and
+    /// blobs are re-shared as they are, envelopes included. Every integer must be representable with the target's
+    /// prime.
+    ///
+    /// Returns the target parties' shares, the same way `mask` does.
+    pub fn reshare(
+        &self,
+        py: Python<'_>,
+        jar: PartyJar,
+        target: PyRef<'_, SecretMasker>,
+    ) -> PyResult<HashMap<PartyId, HashMap<String, EncryptedNadaValue>>> {
+        jar.check_sufficient()?;
+        let jar = jar.try_into()?;
+        let target = &*target;
+        let masked = py.allow_threads(|| {
+            let mut values = self.unmask_values(jar)?;
+            // The masker consumes the values it masks without wiping them, so it's given a copy and the cleartext
+            // is wiped here whether masking succeeds or not.
+            let masked =
+                check_ranges(&values, target.modulo, "target masker").and_then(|_| target.mask_values(values.clone()));
+            values.values_mut().for_each(wipe);
+            masked
+        })?;
+        party_values(py, masked)
     }
 
     /// Classify the given cleartext values.
     ///
     /// This allows getting the totals per value type which is a required parameter when storing values.
-    pub fn classify_values(&self, values: &Bound<'_, PyDict>) -> PyResult<NadaValuesClassification> {
-        let nada_values = pydict_to_nada_values_clear(values)?;
-        Ok(self.inner.classify_values(&nada_values).into())
+    #[pyo3(signature = (values, schema=None))]
+    pub fn classify_values(
+        &self,
+        values: &Bound<'_, PyDict>,
+        schema: Option<NadaValuesSchema>,
+    ) -> PyResult<NadaValuesClassification> {
+        let mut nada_values = pydict_to_nada_values_clear(values, schema.as_ref())?;
+        let classification = self.inner.classify_values(&nada_values).into();
+        nada_values.values_mut().for_each(wipe);
+        Ok(classification)
+    }
+
+    /// Validate that every integer in the given values is within the range supported by this masker's modulo.
+    ///
+    /// Returns a violation for every offending value; an empty list means all values can be masked.
+    #[pyo3(signature = (values, schema=None))]
+    pub fn validate_ranges(
+        &self,
+        values: &Bound<'_, PyDict>,
+        schema: Option<NadaValuesSchema>,
+    ) -> PyResult<Vec<RangeViolation>> {
+        let mut nada_values = pydict_to_nada_values_clear(values, schema.as_ref())?;
+        let violations = find_range_violations(&nada_values, self.modulo);
+        nada_values.values_mut().for_each(wipe);
+        Ok(violations)
+    }
+
+    /// Compute the size of the payload sent to each party when storing the given masked values.
+    ///
+    /// The size of a party's payload is the sum of the length of every value's name and its encoded
+    /// protobuf length, which is what nodes use to price operations. Every one of the masker's parties, and no
+    /// other, must receive a payload of the same size with the same value names, otherwise an error describing the
+    /// mismatch is raised.
+    pub fn encoded_payload_size(
+        &self,
+        py: Python<'_>,
+        masked: HashMap<PartyId, HashMap<String, EncryptedNadaValue>>,
+    ) -> PyResult<usize> {
+        if let Some(party) = masked.keys().find(|party| !self.parties.contains(party)) {
+            return Err(PyValueError::new_err(format!("party {} is not one of the masker's parties", party.inner)));
+        }
+        let missing = self.parties.iter().filter(|party| !masked.contains_key(party)).collect::<Vec<_>>();
+        if !missing.is_empty() {
+            let missing = missing.iter().map(|party| party.inner.to_string()).collect::<Vec<_>>();
+            return Err(PyValueError::new_err(format!("missing payloads for parties {}", missing.join(", "))));
+        }
+        let mut expected: Option<(PartyId, BTreeMap<String, usize>)> = None;
+        for (party, values) in masked {
+            let sizes = values
+                .iter()
+                .map(|(name, value)| {
+                    let size = encrypted_value_size(py, name, value).map_err(|e| {
+                        PyValueError::new_err(format!("party {}: value '{name}': {}", party.inner, e.value_bound(py)))
+                    })?;
+                    Ok((name.clone(), size))
+                })
+                .collect::<PyResult<BTreeMap<_, _>>>()?;
+            match &expected {
+                None => expected = Some((party, sizes)),
+                Some((expected_party, expected_sizes)) => {
+                    check_payload_sizes((expected_party, expected_sizes), (&party, &sizes))?;
+                }
+            }
+        }
+        Ok(expected.map(|(_, sizes)| sizes.values().sum()).unwrap_or_default())
+    }
+
+    /// Mask a blob read from a path or a binary file-like object, streaming each party's shares to its sink.
+    ///
+    /// The blob is masked in chunks of `chunk_size` bytes, so it never needs to fit in memory. Sinks are paths,
+    /// binary file-like objects or callables that receive every encoded piece of the share stream. Returns the
+    /// size of the blob.
+    #[pyo3(signature = (source, sinks, chunk_size=streaming::DEFAULT_CHUNK_SIZE))]
+    pub fn mask_blob_stream(
+        &self,
+        source: &Bound<'_, PyAny>,
+        sinks: HashMap<PartyId, Bound<'_, PyAny>>,
+        chunk_size: usize,
+    ) -> PyResult<u64> {
+        streaming::mask_blob(&self.inner, self.modulo, &self.parties, source, sinks, chunk_size)
+    }
+
+    /// Unmask a blob from every party's share stream, as written by `mask_blob_stream`, into a sink.
+    ///
+    /// Streams are paths or binary file-like objects and the blob is rebuilt one chunk at a time. Returns the
+    /// size of the blob.
+    pub fn unmask_blob_stream(
+        &self,
+        py: Python<'_>,
+        sources: HashMap<PartyId, Bound<'_, PyAny>>,
+        sink: &Bound<'_, PyAny>,
+    ) -> PyResult<u64> {
+        streaming::unmask_blob(py, &self.inner, self.modulo, self.parties.len(), sources, sink)
     }
 
     /// Build a party jar for this masker.
     pub fn build_jar(&self) -> PartyJar {
-        PartyJar::new(self.party_count, self.modulo)
+        PartyJar::new(self.parties.clone(), self.polynomial_degree, self.modulo)
     }
 }
+
+/// The number of bytes a named value accounts for in a payload.
+fn encrypted_value_size(py: Python<'_>, name: &str, value: &EncryptedNadaValue) -> PyResult<usize> {
+    Ok(name.len() + protobuf::encoded_len(py, value)?)
+}
+
+/// Check that two parties received payloads with the same value names and sizes.
+fn check_payload_sizes(
+    (expected_party, expected): (&PartyId, &BTreeMap<String, usize>),
+    (party, sizes): (&PartyId, &BTreeMap<String, usize>),
+) -> PyResult<()> {
+    for (name, expected_size) in expected {
+        match sizes.get(name) {
+            None => {
+                return Err(PyValueError::new_err(format!(
+                    "party {} is missing value '{name}' sent to party {}",
+                    party.inner, expected_party.inner
+                )));
+            }
+            Some(size) if size != expected_size => {
+                return Err(PyValueError::new_err(format!(
+                    "value '{name}' is {size} bytes long for party {} but {expected_size} bytes long for party {}",
+                    party.inner, expected_party.inner
+                )));
+            }
+            Some(_) => (),
+        }
+    }
+    if let Some(name) = sizes.keys().find(|name| !expected.contains_key(*name)) {
+        return Err(PyValueError::new_err(format!(
+            "party {} has value '{name}' that wasn't sent to party {}",
+            party.inner, expected_party.inner
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,250 @@
+//! Encoding of encrypted values into the `nillion.values.v1` protobuf messages sent to the nodes.
+//!
+//! The message types are generated at build time from the same `.proto` files the nodes are built from. They have no
+//! variants for ntuples and objects, which can't be sent to the nodes.
+
+use crate::encrypted_value::{EncryptedNadaType, EncryptedNadaValue};
+use prost::Message;
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyBytes, PyDict},
+};
+use zeroize::Zeroizing;
+
+#[allow(clippy::all, missing_docs)]
+mod proto {
+    include!(concat!(env!("OUT_DIR"), "/nillion.values.v1.value.rs"));
+}
+
+use proto::{value::Value as V, value_type::ValueType as VT};
+
+/// The largest field number allowed by protobuf.
+const MAX_FIELD_NUMBER: u32 = (1 << 29) - 1;
+
+pub fn add_module(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(encode_named_values, m)?)?;
+    Ok(())
+}
+
+/// Encode a set of named encrypted values as a repeated `NamedValue` protobuf field.
+///
+/// The output is the encoding of a message whose only field is `repeated NamedValue values = field_number`.
+/// As protobuf messages can be merged by concatenating their encodings, the output can be appended to an
+/// encoded request to set its values, e.g. using field number 5 for a `StoreValuesRequest`.
+#[pyfunction]
+#[pyo3(signature = (values, field_number=1))]
+pub(crate) fn encode_named_values<'py>(
+    py: Python<'py>,
+    values: &Bound<'py, PyDict>,
+    field_number: u32,
+) -> PyResult<Bound<'py, PyBytes>> {
+    if field_number == 0 || field_number > MAX_FIELD_NUMBER {
+        return Err(PyValueError::new_err(format!("invalid field number: {field_number}")));
+    }
+    let mut output = Zeroizing::new(Vec::new());
+    for (name, value) in values {
+        let name = name.extract::<String>()?;
+        let value = value_to_proto(py, &value.extract::<EncryptedNadaValue>()?)
+            .map_err(|e| PyValueError::new_err(format!("value '{name}': {e}")))?;
+        let named_value = proto::NamedValue { name, value: Some(value) };
+        prost::encoding::message::encode(field_number, &named_value, &mut *output);
+    }
+    Ok(PyBytes::new_bound(py, &output))
+}
+
+/// Encode a value as a `nillion.values.v1.value.Value` protobuf message.
+pub(crate) fn encode_value<'py>(py: Python<'py>, value: &EncryptedNadaValue) -> PyResult<Bound<'py, PyBytes>> {
+    let encoded = Zeroizing::new(value_to_proto(py, value)?.encode_to_vec());
+    Ok(PyBytes::new_bound(py, &encoded))
+}
+
+/// The exact length of a value once encoded as a `nillion.values.v1.value.Value` protobuf message.
+pub(crate) fn encoded_len(py: Python<'_>, value: &EncryptedNadaValue) -> PyResult<usize> {
+    Ok(value_to_proto(py, value)?.encoded_len())
+}
+
+/// Decode a value from a `nillion.values.v1.value.Value` protobuf message.
+pub(crate) fn decode_value(py: Python<'_>, bytes: &[u8]) -> PyResult<EncryptedNadaValue> {
+    let value =
+        proto::Value::decode(bytes).map_err(|e| PyValueError::new_err(format!("invalid protobuf value: {e}")))?;
+    value_from_proto(py, value)
+}
+
+fn value_to_proto(py: Python<'_>, value: &EncryptedNadaValue) -> PyResult<proto::Value> {
+    use EncryptedNadaValue as E;
+    let value = match value {
+        E::ShamirShareInteger { value } => V::ShamirShareInteger(proto::ShamirShare { value: value.to_vec() }),
+        E::ShamirShareUnsignedInteger { value } => {
+            V::ShamirShareUnsignedInteger(proto::ShamirShare { value: value.to_vec() })
+        }
+        E::ShamirShareBoolean { value } => V::ShamirShareBoolean(proto::ShamirShare { value: value.to_vec() }),
+        E::ShamirSharesBlob { values, original_size } => V::ShamirSharesBlob(proto::ShamirSharesBlob {
+            shares: values.iter().map(|v| proto::ShamirShare { value: v.to_vec() }).collect(),
+            original_size: *original_size,
+        }),
+        E::PublicInteger { value } => V::PublicInteger(proto::PublicInteger { value: value.clone() }),
+        E::PublicUnsignedInteger { value } => V::PublicUnsignedInteger(proto::PublicInteger { value: value.clone() }),
+        E::PublicBoolean { value } => V::PublicBoolean(proto::PublicInteger { value: value.clone() }),
+        E::Array { inner_type, values } => V::Array(Box::new(proto::Array {
+            values: values.iter().map(|v| value_to_proto(py, v)).collect::<PyResult<_>>()?,
+            inner_type: Some(type_to_proto(py, inner_type)?),
+        })),
+        E::Tuple { left, right } => V::Tuple(Box::new(proto::Tuple {
+            left: Some(Box::new(value_to_proto(py, left.get())?)),
+            right: Some(Box::new(value_to_proto(py, right.get())?)),
+        })),
+        E::NTuple { .. } | E::Object { .. } => {
+            return Err(PyValueError::new_err("ntuple and object values have no protobuf representation"));
+        }
+        E::EcdsaMessageDigest { value } => V::EcdsaMessageDigest(proto::EcdsaMessageDigest { digest: value.clone() }),
+        E::EcdsaSignature { r, sigma } => {
+            V::EcdsaSignatureShare(proto::EcdsaSignatureShare { r: r.clone(), sigma: sigma.clone() })
+        }
+        E::EcdsaPrivateKey { i, x, shared_public_key, public_shares } => {
+            V::EcdsaPrivateKeyShare(proto::EcdsaPrivateKeyShare {
+                i: u32::from(*i),
+                x: x.to_vec(),
+                shared_public_key: shared_public_key.clone(),
+                public_shares: public_shares.clone(),
+            })
+        }
+        E::EcdsaPublicKey { value } => V::EcdsaPublicKey(proto::EcdsaPublicKey { public_key: value.clone() }),
+        E::StoreId { value } => V::StoreId(proto::StoreId { store_id: value.clone() }),
+        E::EddsaPrivateKey { i, x, shared_public_key, public_shares } => {
+            V::EddsaPrivateKeyShare(proto::EddsaPrivateKeyShare {
+                i: u32::from(*i),
+                x: x.to_vec(),
+                shared_public_key: shared_public_key.clone(),
+                public_shares: public_shares.clone(),
+            })
+        }
+        E::EddsaPublicKey { value } => V::EddsaPublicKey(proto::EddsaPublicKey { public_key: value.clone() }),
+        E::EddsaSignature { value } => V::EddsaSignature(proto::EddsaSignature { signature: value.clone() }),
+        E::EddsaMessage { value } => V::EddsaMessage(proto::EddsaMessage { message: value.clone() }),
+    };
+    Ok(proto::Value { value: Some(value) })
+}
+
+fn value_from_proto(py: Python<'_>, value: proto::Value) -> PyResult<EncryptedNadaValue> {
+    use EncryptedNadaValue as E;
+    let value = match value.value.ok_or_else(|| PyValueError::new_err("value not set"))? {
+        V::ShamirShareInteger(share) => E::ShamirShareInteger { value: share.value.into() },
+        V::ShamirShareUnsignedInteger(share) => E::ShamirShareUnsignedInteger { value: share.value.into() },
+        V::ShamirShareBoolean(share) => E::ShamirShareBoolean { value: share.value.into() },
+        V::ShamirSharesBlob(blob) => E::ShamirSharesBlob {
+            values: blob.shares.into_iter().map(|s| s.value.into()).collect(),
+            original_size: blob.original_size,
+        },
+        V::PublicInteger(value) => E::PublicInteger { value: value.value },
+        V::PublicUnsignedInteger(value) => E::PublicUnsignedInteger { value: value.value },
+        V::PublicBoolean(value) => E::PublicBoolean { value: value.value },
+        V::Array(array) => {
+            let proto::Array { values, inner_type } = *array;
+            let inner_type = inner_type.ok_or_else(|| PyValueError::new_err("array inner type not set"))?;
+            E::Array {
+                inner_type: type_from_proto(py, inner_type)?,
+                values: values.into_iter().map(|v| value_from_proto(py, v)).collect::<PyResult<_>>()?,
+            }
+        }
+        V::Tuple(tuple) => {
+            let proto::Tuple { left, right } = *tuple;
+            let left = left.ok_or_else(|| PyValueError::new_err("tuple left value not set"))?;
+            let right = right.ok_or_else(|| PyValueError::new_err("tuple right value not set"))?;
+            E::Tuple {
+                left: Py::new(py, value_from_proto(py, *left)?)?,
+                right: Py::new(py, value_from_proto(py, *right)?)?,
+            }
+        }
+        V::EcdsaMessageDigest(digest) => E::EcdsaMessageDigest { value: digest.digest },
+        V::EcdsaSignatureShare(signature) => E::EcdsaSignature { r: signature.r, sigma: signature.sigma },
+        V::EcdsaPrivateKeyShare(key) => E::EcdsaPrivateKey {
+            i: key_index(key.i)?,
+            x: key.x.into(),
+            shared_public_key: key.shared_public_key,
+            public_shares: key.public_shares,
+        },
+        V::EcdsaPublicKey(key) => E::EcdsaPublicKey { value: key.public_key },
+        V::StoreId(store_id) => E::StoreId { value: store_id.store_id },
+        V::EddsaPrivateKeyShare(key) => E::EddsaPrivateKey {
+            i: key_index(key.i)?,
+            x: key.x.into(),
+            shared_public_key: key.shared_public_key,
+            public_shares: key.public_shares,
+        },
+        V::EddsaPublicKey(key) => E::EddsaPublicKey { value: key.public_key },
+        V::EddsaSignature(signature) => E::EddsaSignature { value: signature.signature },
+        V::EddsaMessage(message) => E::EddsaMessage { value: message.message },
+    };
+    Ok(value)
+}
+
+fn key_index(i: u32) -> PyResult<u16> {
+    u16::try_from(i).map_err(|_| PyValueError::new_err(format!("invalid key share index: {i}")))
+}
+
+fn type_to_proto(py: Python<'_>, nada_type: &EncryptedNadaType) -> PyResult<proto::ValueType> {
+    use EncryptedNadaType as E;
+    let nada_type = match nada_type {
+        E::Integer() => VT::PublicInteger(()),
+        E::UnsignedInteger() => VT::PublicUnsignedInteger(()),
+        E::Boolean() => VT::PublicBoolean(()),
+        E::ShamirShareInteger() => VT::ShamirShareInteger(()),
+        E::ShamirShareUnsignedInteger() => VT::ShamirShareUnsignedInteger(()),
+        E::ShamirShareBoolean() => VT::ShamirShareBoolean(()),
+        E::Array { inner_type, size } => VT::Array(Box::new(proto::ArrayType {
+            inner_type: Some(Box::new(type_to_proto(py, &inner_type.extract::<EncryptedNadaType>(py)?)?)),
+            size: *size,
+        })),
+        E::Tuple { left, right } => VT::Tuple(Box::new(proto::TupleType {
+            left: Some(Box::new(type_to_proto(py, &left.extract::<EncryptedNadaType>(py)?)?)),
+            right: Some(Box::new(type_to_proto(py, &right.extract::<EncryptedNadaType>(py)?)?)),
+        })),
+        E::ShamirShareSecretBlob() | E::NTuple { .. } | E::Object { .. } => {
+            return Err(PyValueError::new_err("blob, ntuple and object types have no protobuf representation"));
+        }
+        E::EcdsaMessageDigest() => VT::EcdsaMessageDigest(()),
+        E::EcdsaSignature() => VT::EcdsaSignatureShare(()),
+        E::EcdsaPrivateKey() => VT::EcdsaPrivateKeyShare(()),
+        E::EcdsaPublicKey() => VT::EcdsaPublicKey(()),
+        E::StoreId() => VT::StoreId(()),
+        E::EddsaPrivateKey() => VT::EddsaPrivateKeyShare(()),
+        E::EddsaPublicKey() => VT::EddsaPublicKey(()),
+        E::EddsaSignature() => VT::EddsaSignature(()),
+        E::EddsaMessage() => VT::EddsaMessage(()),
+    };
+    Ok(proto::ValueType { value_type: Some(nada_type) })
+}
+
+fn type_from_proto(py: Python<'_>, nada_type: proto::ValueType) -> PyResult<EncryptedNadaType> {
+    use EncryptedNadaType as E;
+    let nada_type = match nada_type.value_type.ok_or_else(|| PyValueError::new_err("value type not set"))? {
+        VT::PublicInteger(()) => E::Integer(),
+        VT::PublicUnsignedInteger(()) => E::UnsignedInteger(),
+        VT::PublicBoolean(()) => E::Boolean(),
+        VT::ShamirShareInteger(()) => E::ShamirShareInteger(),
+        VT::ShamirShareUnsignedInteger(()) => E::ShamirShareUnsignedInteger(),
+        VT::ShamirShareBoolean(()) => E::ShamirShareBoolean(),
+        VT::Array(array) => {
+            let proto::ArrayType { inner_type, size } = *array;
+            let inner_type = inner_type.ok_or_else(|| PyValueError::new_err("array inner type not set"))?;
+            E::Array { inner_type: type_from_proto(py, *inner_type)?.into_py(py), size }
+        }
+        VT::Tuple(tuple) => {
+            let proto::TupleType { left, right } = *tuple;
+            let left = left.ok_or_else(|| PyValueError::new_err("tuple left type not set"))?;
+            let right = right.ok_or_else(|| PyValueError::new_err("tuple right type not set"))?;
+            E::Tuple { left: type_from_proto(py, *left)?.into_py(py), right: type_from_proto(py, *right)?.into_py(py) }
+        }
+        VT::EcdsaMessageDigest(()) => E::EcdsaMessageDigest(),
+        VT::EcdsaSignatureShare(()) => E::EcdsaSignature(),
+        VT::EcdsaPrivateKeyShare(()) => E::EcdsaPrivateKey(),
+        VT::EcdsaPublicKey(()) => E::EcdsaPublicKey(),
+        VT::StoreId(()) => E::StoreId(),
+        VT::EddsaPrivateKeyShare(()) => E::EddsaPrivateKey(),
+        VT::EddsaPublicKey(()) => E::EddsaPublicKey(),
+        VT::EddsaSignature(()) => E::EddsaSignature(),
+        VT::EddsaMessage(()) => E::EddsaMessage(),
+    };
+    Ok(nada_type)
+}
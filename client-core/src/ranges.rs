@@ -0,0 +1,165 @@
+//! Range validation of cleartext values against a modulo.
+
+use math_lib::modular::{Modular, U128SafePrime, U256SafePrime, U64SafePrime};
+use nillion_client_core::values::{BigInt, BigUint, Clear, EncodedModulo, NadaValue};
+use pyo3::{exceptions::PyValueError, prelude::*};
+use std::{collections::HashMap, sync::OnceLock};
+
+/// The prime used by the given modulo, converted the first time it's needed.
+pub(crate) fn prime(modulo: EncodedModulo) -> &'static BigUint {
+    static PRIMES: [OnceLock<BigUint>; 3] = [OnceLock::new(), OnceLock::new(), OnceLock::new()];
+    match modulo {
+        EncodedModulo::U64SafePrime => PRIMES[0].get_or_init(modulo_prime::<U64SafePrime>),
+        EncodedModulo::U128SafePrime => PRIMES[1].get_or_init(modulo_prime::<U128SafePrime>),
+        EncodedModulo::U256SafePrime => PRIMES[2].get_or_init(modulo_prime::<U256SafePrime>),
+    }
+}
+
+/// The prime of an upstream modulo type, so the ranges can't drift from what the masker actually uses.
+fn modulo_prime<T: Modular>() -> BigUint {
+    // Words are stored least significant first.
+    T::MODULO
+        .as_words()
+        .iter()
+        .rev()
+        .fold(BigUint::from(0u32), |prime, word| (prime << (std::mem::size_of_val(word) * 8)) | BigUint::from(*word))
+}
+
+/// The modulo that uses a safe prime of the given size in bits.
+pub(crate) fn modulo_from_prime_bits(prime_bits: u32) -> PyResult<EncodedModulo> {
+    match prime_bits {
+        64 => Ok(EncodedModulo::U64SafePrime),
+        128 => Ok(EncodedModulo::U128SafePrime),
+        256 => Ok(EncodedModulo::U256SafePrime),
+        _ => Err(PyValueError::new_err(format!("unsupported prime size: {prime_bits}"))),
+    }
+}
+
+/// The modulo that uses the prime with the given name, as in the membership `Cluster` message's `Prime` enum.
+pub(crate) fn modulo_from_prime_name(name: &str) -> PyResult<EncodedModulo> {
+    match name {
+        "SAFE_64_BITS" => Ok(EncodedModulo::U64SafePrime),
+        "SAFE_128_BITS" => Ok(EncodedModulo::U128SafePrime),
+        "SAFE_256_BITS" => Ok(EncodedModulo::U256SafePrime),
+        _ => Err(PyValueError::new_err(format!(
+            "unknown prime '{name}', expected one of SAFE_64_BITS, SAFE_128_BITS or SAFE_256_BITS"
+        ))),
+    }
+}
+
+/// The size in bits of the safe prime used by the given modulo.
+pub(crate) fn prime_bits(modulo: EncodedModulo) -> u32 {
+    match modulo {
+        EncodedModulo::U64SafePrime => 64,
+        EncodedModulo::U128SafePrime => 128,
+        EncodedModulo::U256SafePrime => 256,
+    }
+}
+
+/// The inclusive range of signed integers that can be represented under the given modulo.
+pub(crate) fn signed_range(modulo: EncodedModulo) -> (BigInt, BigInt) {
+    let max = BigInt::from((prime(modulo) - 1u32) / 2u32);
+    (-max.clone(), max)
+}
+
+/// The inclusive range of unsigned integers that can be represented under the given modulo.
+pub(crate) fn unsigned_range(modulo: EncodedModulo) -> (BigInt, BigInt) {
+    (BigInt::from(0u32), BigInt::from(prime(modulo) - 1u32))
+}
+
+/// A value that is outside of the range supported by a modulo.
+#[pyclass(get_all)]
+#[derive(Clone)]
+pub struct RangeViolation {
+    /// The path of the offending value, e.g. `my_array[2]`.
+    key: String,
+
+    /// The offending value.
+    value: BigInt,
+
+    /// The smallest allowed value.
+    min: BigInt,
+
+    /// The largest allowed value.
+    max: BigInt,
+}
+
+#[pymethods]
+impl RangeViolation {
+    fn __repr__(&self) -> String {
+        format!("RangeViolation(key='{}', value={}, min={}, max={})", self.key, self.value, self.min, self.max)
+    }
+}
+
+/// Find every integer in the given values that can't be represented under the given modulo.
+pub(crate) fn find_range_violations(
+    values: &HashMap<String, NadaValue<Clear>>,
+    modulo: EncodedModulo,
+) -> Vec<RangeViolation> {
+    let signed = signed_range(modulo);
+    let unsigned = unsigned_range(modulo);
+    let mut violations = Vec::new();
+    for (key, value) in values {
+        collect_violations(key.clone(), value, &signed, &unsigned, &mut violations);
+    }
+    violations.sort_by(|a, b| a.key.cmp(&b.key));
+    violations
+}
+
+/// Check that every integer in the given values can be represented under a masker's modulo.
+///
+/// The error names the first offending value but not the value itself, which may be secret.
+pub(crate) fn check_ranges(
+    values: &HashMap<String, NadaValue<Clear>>,
+    modulo: EncodedModulo,
+    masker: &str,
+) -> PyResult<()> {
+    match find_range_violations(values, modulo).first() {
+        Some(violation) => Err(PyValueError::new_err(format!(
+            "value '{}' can't be represented with the {masker}'s {} bit prime",
+            violation.key,
+            prime_bits(modulo)
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn collect_violations(
+    key: String,
+    value: &NadaValue<Clear>,
+    signed: &(BigInt, BigInt),
+    unsigned: &(BigInt, BigInt),
+    violations: &mut Vec<RangeViolation>,
+) {
+    let (value, (min, max)) = match value {
+        NadaValue::Integer(value) | NadaValue::SecretInteger(value) => {
+            let value: BigInt = value.clone().into();
+            (value, signed)
+        }
+        NadaValue::UnsignedInteger(value) | NadaValue::SecretUnsignedInteger(value) => {
+            let value: BigUint = value.clone().into();
+            (BigInt::from(value), unsigned)
+        }
+        NadaValue::Array { values, .. } | NadaValue::NTuple { values } => {
+            for (index, value) in values.iter().enumerate() {
+                collect_violations(format!("{key}[{index}]"), value, signed, unsigned, violations);
+            }
+            return;
+        }
+        NadaValue::Tuple { left, right } => {
+            collect_violations(format!("{key}[0]"), left, signed, unsigned, violations);
+            collect_violations(format!("{key}[1]"), right, signed, unsigned, violations);
+            return;
+        }
+        NadaValue::Object { values } => {
+            for (name, value) in values {
+                collect_violations(format!("{key}.{name}"), value, signed, unsigned, violations);
+            }
+            return;
+        }
+        _ => return,
+    };
+    if &value < min || &value > max {
+        violations.push(RangeViolation { key, value, min: min.clone(), max: max.clone() });
+    }
+}
@@ -0,0 +1,173 @@
+//! Error-correcting unmasking of values from jars holding more shares than needed.
+//!
+//! Values are masked with polynomials of degree `d`, so any `d + 1` shares of a value determine it. When `n > d + 1`
+//! parties contributed a share, up to `(n - d - 1) / 2` faulty shares can be corrected, as a Berlekamp–Welch decoder
+//! would: the value is recovered from the only polynomial that at least `n - (n - d - 1) / 2` shares lie on, and the
+//! parties whose shares don't lie on it are reported as faulty.
+//!
+//! Share abscissas are internal to the masker, so polynomials are never evaluated directly. Instead, a share lies on
+//! the polynomial defined by a set of `d + 1` shares if and only if swapping it in for one of them unmasks into the
+//! same value: two distinct polynomials of degree `d` that agree on `d` non-zero points never agree at zero.
+//!
+//! Without the abscissas this can't be an algebraic decoder, so sets of `d + 1` shares are tried in turn until one
+//! that only holds honest shares is found. Checking a set costs `n - d` unmasks of the value, and there are
+//! `C(n, d + 1)` sets, so the search is capped at `MAX_CANDIDATES` sets: `O(MAX_CANDIDATES * n)` unmasks per value in
+//! the worst case. A set holding a faulty share is rejected after at most `(n - d - 1) / 2 + 1` of them, and when
+//! faulty shares are spread out the first set or one of the first few sets is usually honest.
+//!
+//! Faults are decided per value: a party whose share of any element of an array or any part of a blob is corrupted
+//! is faulty for the whole value, so a value can only be recovered if at most `(n - d - 1) / 2` parties corrupted any
+//! part of it, even if every element on its own had few enough faulty shares.
+
+use crate::{PartyId, UpstreamPartyJar};
+use ::nillion_client_core::values::{Clear, Encoded, Encrypted, NadaValue, SecretMasker};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+use std::collections::{BTreeMap, HashMap};
+
+type Share = (::nillion_client_core::values::PartyId, NadaValue<Encrypted<Encoded>>);
+
+/// The largest number of sets of shares that are tried when recovering a value.
+const MAX_CANDIDATES: usize = 4096;
+
+/// The result of unmasking values while correcting faulty shares.
+#[pyclass(get_all)]
+pub struct RobustUnmaskResult {
+    /// The unmasked values.
+    pub(crate) values: Py<PyDict>,
+
+    /// The parties that provided at least one faulty share, in the masker's order.
+    pub(crate) faulty_parties: Vec<PartyId>,
+
+    /// The parties whose shares of a value were faulty or missing, for every value that had any.
+    pub(crate) faulty_keys: BTreeMap<String, Vec<PartyId>>,
+}
+
+#[pymethods]
+impl RobustUnmaskResult {
+    fn __repr__(&self, py: Python<'_>) -> String {
+        let parties = self.faulty_parties.iter().map(|party| party.inner.to_string()).collect::<Vec<_>>();
+        format!(
+            "RobustUnmaskResult(values={}, faulty_parties=[{}], faulty_keys={:?})",
+            self.values.bind(py),
+            parties.join(", "),
+            self.faulty_keys.keys().collect::<Vec<_>>()
+        )
+    }
+}
+
+/// The values recovered from a jar, along with the parties whose shares of each value were faulty or missing.
+pub(crate) struct Recovered {
+    pub(crate) values: HashMap<String, NadaValue<Clear>>,
+    pub(crate) faults: BTreeMap<String, Vec<::nillion_client_core::values::PartyId>>,
+}
+
+/// Unmask every value in a jar, correcting faulty shares.
+///
+/// `threshold` is the number of shares needed to unmask a value, `polynomial_degree + 1`.
+pub(crate) fn unmask(masker: &SecretMasker, threshold: usize, jar: UpstreamPartyJar) -> PyResult<Recovered> {
+    let mut contributors = Vec::new();
+    let mut shares: BTreeMap<String, Vec<Share>> = BTreeMap::new();
+    for (party, values) in jar.into_elements() {
+        for (name, value) in values {
+            shares.entry(name).or_default().push((party.clone(), value));
+        }
+        contributors.push(party);
+    }
+    let mut recovered = Recovered { values: HashMap::new(), faults: BTreeMap::new() };
+    for (name, shares) in shares {
+        let (value, mut faulty) = recover_value(masker, &name, &shares, threshold)
+            .map_err(|e| PyValueError::new_err(format!("value '{name}': {e}")))?;
+        faulty.extend(contributors.iter().filter(|party| !shares.iter().any(|(owner, _)| owner == *party)).cloned());
+        if !faulty.is_empty() {
+            recovered.faults.insert(name.clone(), faulty);
+        }
+        recovered.values.insert(name, value);
+    }
+    Ok(recovered)
+}
+
+/// Recover a value from every party's share of it, returning it along with the parties whose shares are faulty.
+fn recover_value(
+    masker: &SecretMasker,
+    name: &str,
+    shares: &[Share],
+    threshold: usize,
+) -> Result<(NadaValue<Clear>, Vec<::nillion_client_core::values::PartyId>), String> {
+    let count = shares.len();
+    if count <= threshold {
+        return Err(format!(
+            "correcting faulty shares requires shares from more than {threshold} parties, got {count}"
+        ));
+    }
+    let max_faults = (count - threshold) / 2;
+    // Any set of honest shares yields the right polynomial, so this usually succeeds on the first or one of the
+    // first few sets.
+    for base in Combinations::new(count, threshold).take(MAX_CANDIDATES) {
+        let base_shares = base.iter().map(|index| &shares[*index]).collect::<Vec<_>>();
+        let Some(value) = unmask_shares(masker, name, &base_shares) else {
+            continue;
+        };
+        let mut faulty = Vec::new();
+        for other in (0..count).filter(|index| !base.contains(index)) {
+            let mut swapped = base_shares.clone();
+            swapped[0] = &shares[other];
+            if unmask_shares(masker, name, &swapped).as_ref() != Some(&value) {
+                faulty.push(shares[other].0.clone());
+                if faulty.len() > max_faults {
+                    break;
+                }
+            }
+        }
+        if faulty.len() <= max_faults {
+            return Ok((value, faulty));
+        }
+    }
+    if Combinations::new(count, threshold).nth(MAX_CANDIDATES).is_some() {
+        return Err(format!(
+            "no consistent set of shares found in the first {MAX_CANDIDATES} sets, at most {max_faults} of {count} \
+             shares can be corrected"
+        ));
+    }
+    Err(format!("too many inconsistent shares, at most {max_faults} of {count} can be corrected"))
+}
+
+/// Unmask a value from some parties' shares of it, returning `None` if they can't be unmasked.
+fn unmask_shares(masker: &SecretMasker, name: &str, shares: &[&Share]) -> Option<NadaValue<Clear>> {
+    let elements = shares
+        .iter()
+        .map(|(party, value)| (party.clone(), std::iter::once((name.to_string(), value.clone())).collect()))
+        .collect::<Vec<_>>();
+    let jar = UpstreamPartyJar::new_with_elements(elements).ok()?;
+    masker.unmask(jar).ok()?.remove(name)
+}
+
+/// Every `k` sized combination of the indices `0..n`, in lexicographic order.
+struct Combinations {
+    n: usize,
+    next: Option<Vec<usize>>,
+}
+
+impl Combinations {
+    fn new(n: usize, k: usize) -> Self {
+        Self { n, next: (k <= n).then(|| (0..k).collect()) }
+    }
+}
+
+impl Iterator for Combinations {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+        let k = current.len();
+        // Find the rightmost index that can still be incremented and reset every index after it.
+        if let Some(position) = (0..k).rev().find(|position| current[*position] < self.n - k + position) {
+            let mut next = current.clone();
+            next[position] += 1;
+            for following in position + 1..k {
+                next[following] = next[following - 1] + 1;
+            }
+            self.next = Some(next);
+        }
+        Some(current)
+    }
+}
@@ -0,0 +1,205 @@
+//! Authenticated encryption of encrypted bundles at rest.
+//!
+//! A sealed bundle is encoded as:
+//!
+//! * The magic bytes `NILS` followed by a version byte.
+//! * The key derivation: `0` for a raw 32 byte key, or `1` for Argon2id followed by its memory cost in KiB, its
+//!   iteration count, its parallelism and its salt.
+//! * The id of the party the bundle belongs to.
+//! * The XChaCha20-Poly1305 nonce and the encrypted bundle.
+//!
+//! Everything before the nonce is authenticated as associated data. The party id used for it is the one the caller
+//! expects when unsealing, so a bundle sealed for a party can't be opened as another party's, and any change to the
+//! header or the ciphertext is detected.
+//!
+//! Numbers are unsigned LEB128 varints and byte strings are length prefixed.
+
+use crate::{
+    values::encoding::{write_bytes, write_varint, Reader},
+    PartyId,
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use pyo3::{exceptions::PyValueError, PyResult};
+use zeroize::Zeroizing;
+
+/// The magic bytes that start every sealed bundle.
+const MAGIC: &[u8; 4] = b"NILS";
+
+/// The current version of the sealed bundle format.
+const VERSION: u8 = 1;
+
+const KDF_RAW_KEY: u8 = 0;
+const KDF_ARGON2ID: u8 = 1;
+
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/// The largest Argon2id memory cost accepted when unsealing, in KiB.
+///
+/// The parameters are read before the header can be authenticated, this keeps a corrupt header from making us
+/// allocate an arbitrary amount of memory.
+const MAX_MEMORY_COST: u32 = 1 << 20;
+
+/// The largest Argon2id iteration count accepted when unsealing.
+///
+/// Like the memory cost, this is read before the header can be authenticated, this keeps a corrupt header from
+/// making key derivation run for an arbitrary amount of time.
+const MAX_ITERATIONS: u32 = 16;
+
+/// The secret a bundle is sealed with.
+pub(crate) enum Secret<'a> {
+    /// A passphrase, stretched into a key with Argon2id.
+    Passphrase(&'a str),
+
+    /// A raw 32 byte key.
+    Key(&'a [u8]),
+}
+
+impl<'a> Secret<'a> {
+    /// Get the secret from a method's optional `passphrase` and `key` arguments.
+    pub(crate) fn from_args(passphrase: Option<&'a str>, key: Option<&'a [u8]>) -> PyResult<Option<Self>> {
+        match (passphrase, key) {
+            (None, None) => Ok(None),
+            (Some(passphrase), None) => Ok(Some(Self::Passphrase(passphrase))),
+            (None, Some(key)) => Ok(Some(Self::Key(key))),
+            (Some(_), Some(_)) => Err(PyValueError::new_err("either a passphrase or a key can be provided, not both")),
+        }
+    }
+}
+
+/// The way the sealing key is derived from the secret.
+enum Kdf {
+    RawKey,
+    Argon2id { params: Params, salt: Vec<u8> },
+}
+
+impl Kdf {
+    fn derive_key(&self, secret: &Secret) -> PyResult<Zeroizing<[u8; KEY_LEN]>> {
+        let mut key = Zeroizing::new([0; KEY_LEN]);
+        match (self, secret) {
+            (Kdf::RawKey, Secret::Key(bytes)) => {
+                if bytes.len() != KEY_LEN {
+                    return Err(PyValueError::new_err(format!("key must be exactly {KEY_LEN} bytes long")));
+                }
+                key.copy_from_slice(bytes);
+            }
+            (Kdf::Argon2id { params, salt }, Secret::Passphrase(passphrase)) => {
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params.clone())
+                    .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+                    .map_err(|e| PyValueError::new_err(format!("deriving key failed: {e}")))?;
+            }
+            (Kdf::RawKey, Secret::Passphrase(_)) => {
+                return Err(PyValueError::new_err("bundle was sealed with a key, not a passphrase"));
+            }
+            (Kdf::Argon2id { .. }, Secret::Key(_)) => {
+                return Err(PyValueError::new_err("bundle was sealed with a passphrase, not a key"));
+            }
+        }
+        Ok(key)
+    }
+}
+
+/// Whether some bytes look like a sealed bundle.
+pub(crate) fn is_sealed(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Seal an encoded bundle that belongs to a party.
+pub(crate) fn seal(bundle: &[u8], party: &PartyId, secret: Secret) -> PyResult<Vec<u8>> {
+    let kdf = match secret {
+        Secret::Key(_) => Kdf::RawKey,
+        Secret::Passphrase(_) => {
+            let mut salt = vec![0; SALT_LEN];
+            OsRng.fill_bytes(&mut salt);
+            Kdf::Argon2id { params: Params::DEFAULT, salt }
+        }
+    };
+    let key = kdf.derive_key(&secret)?;
+    let mut output = Vec::new();
+    write_header(&mut output, &kdf, party);
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher(&key)?
+        .encrypt(&nonce, Payload { msg: bundle, aad: &output })
+        .map_err(|_| PyValueError::new_err("sealing bundle failed"))?;
+    write_bytes(&mut output, &nonce);
+    write_bytes(&mut output, &ciphertext);
+    Ok(output)
+}
+
+/// Unseal a bundle produced by [`seal`], returning the encoded bundle.
+pub(crate) fn unseal(sealed: &[u8], party: &PartyId, secret: Secret) -> PyResult<Zeroizing<Vec<u8>>> {
+    let Some(bytes) = sealed.strip_prefix(MAGIC) else {
+        return Err(PyValueError::new_err("not a sealed bundle"));
+    };
+    let mut reader = Reader::new(bytes);
+    match reader.read_u8()? {
+        VERSION => (),
+        version => return Err(PyValueError::new_err(format!("unsupported sealed bundle version: {version}"))),
+    };
+    let kdf = match reader.read_u8()? {
+        KDF_RAW_KEY => Kdf::RawKey,
+        KDF_ARGON2ID => {
+            let memory_cost = read_u32(&mut reader)?;
+            if memory_cost > MAX_MEMORY_COST {
+                return Err(PyValueError::new_err("key derivation memory cost is too large"));
+            }
+            let iterations = read_u32(&mut reader)?;
+            if iterations > MAX_ITERATIONS {
+                return Err(PyValueError::new_err("key derivation iteration count is too large"));
+            }
+            let parallelism = read_u32(&mut reader)?;
+            let params = Params::new(memory_cost, iterations, parallelism, Some(KEY_LEN))
+                .map_err(|e| PyValueError::new_err(format!("invalid key derivation parameters: {e}")))?;
+            Kdf::Argon2id { params, salt: reader.read_bytes()?.to_vec() }
+        }
+        kdf => return Err(PyValueError::new_err(format!("unknown key derivation: {kdf}"))),
+    };
+    let sealed_party = reader.read_bytes()?;
+    if sealed_party != party.inner.as_bytes() {
+        let sealed_party = ::nillion_client_core::values::PartyId::from(sealed_party.to_vec());
+        return Err(PyValueError::new_err(format!("bundle was sealed for party {sealed_party}, not {}", party.inner)));
+    }
+    let nonce = reader.read_bytes()?;
+    if nonce.len() != NONCE_LEN {
+        return Err(PyValueError::new_err(format!("nonce must be exactly {NONCE_LEN} bytes long")));
+    }
+    let ciphertext = reader.read_bytes()?;
+    reader.finish()?;
+
+    let key = kdf.derive_key(&secret)?;
+    let mut aad = Vec::new();
+    write_header(&mut aad, &kdf, party);
+    let bundle = cipher(&key)?
+        .decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad: &aad })
+        .map_err(|_| PyValueError::new_err("unsealing bundle failed: wrong secret or tampered data"))?;
+    Ok(Zeroizing::new(bundle))
+}
+
+fn write_header(output: &mut Vec<u8>, kdf: &Kdf, party: &PartyId) {
+    output.extend_from_slice(MAGIC);
+    output.push(VERSION);
+    match kdf {
+        Kdf::RawKey => output.push(KDF_RAW_KEY),
+        Kdf::Argon2id { params, salt } => {
+            output.push(KDF_ARGON2ID);
+            write_varint(output, params.m_cost().into());
+            write_varint(output, params.t_cost().into());
+            write_varint(output, params.p_cost().into());
+            write_bytes(output, salt);
+        }
+    }
+    write_bytes(output, party.inner.as_bytes());
+}
+
+fn cipher(key: &[u8; KEY_LEN]) -> PyResult<XChaCha20Poly1305> {
+    XChaCha20Poly1305::new_from_slice(key).map_err(|_| PyValueError::new_err("invalid key length"))
+}
+
+fn read_u32(reader: &mut Reader<'_>) -> PyResult<u32> {
+    u32::try_from(reader.read_varint()?).map_err(|_| PyValueError::new_err("key derivation parameter is too large"))
+}
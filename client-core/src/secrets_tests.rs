@@ -364,3 +364,1620 @@ print("Eddsa signature is: ", eddsa_msg.value)
         .unwrap();
     })
 }
+
+#[test]
+fn test_secret_tuple() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+tuple = Tuple(SecretInteger(22), Integer(44))
+assert len(tuple) == 2
+assert tuple.left == SecretInteger(22)
+assert tuple.right == Integer(44)
+assert tuple.value == (SecretInteger(22), Integer(44))
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_secret_ntuple() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+ntuple = NTuple([SecretInteger(22), SecretBoolean(True), Integer(44)])
+assert len(ntuple) == 3
+assert ntuple.value == [SecretInteger(22), SecretBoolean(True), Integer(44)]
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_secret_object() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+obj = Object({"a": SecretInteger(22), "b": Array([SecretInteger(1), SecretInteger(2)])})
+assert len(obj) == 2
+assert list(obj.value.keys()) == ["a", "b"]
+assert obj.value["a"] == SecretInteger(22)
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_mask_unmask_tuple() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+values = {"tuple": Tuple(SecretInteger(-3), SecretInteger(7))}
+masked = masker.mask(values)
+jar = masker.build_jar()
+for party in parties:
+    jar.add_element(party, masked[party])
+unmasked = masker.unmask(jar)
+assert unmasked["tuple"] == values["tuple"]
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_mask_unmask_ntuple_and_object() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+values = {
+    "ntuple": NTuple([SecretInteger(-3), Integer(7), SecretBoolean(True)]),
+    "object": Object({"a": SecretUnsignedInteger(3), "b": Array([SecretInteger(1), SecretInteger(2)])}),
+}
+masked = masker.mask(values)
+jar = masker.build_jar()
+for party in parties:
+    jar.add_element(party, masked[party])
+unmasked = masker.unmask(jar)
+assert unmasked["ntuple"] == values["ntuple"]
+assert unmasked["object"] == values["object"]
+assert list(unmasked["object"].value.keys()) == ["a", "b"]
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_mask_with_schema() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+schema = NadaValuesSchema({
+    "a": NadaType.secret_integer(),
+    "b": NadaType.array(NadaType.secret_unsigned_integer(), 3),
+    "c": NadaType.boolean(),
+    "d": NadaType.secret_blob(),
+    "e": NadaType.tuple(NadaType.secret_integer(), NadaType.integer()),
+})
+values = {"a": -5, "b": [1, 2, 3], "c": True, "d": b"\x01\x02", "e": (SecretInteger(1), 2)}
+masked = masker.mask(values, schema)
+jar = masker.build_jar()
+for party in parties:
+    jar.add_element(party, masked[party])
+unmasked = masker.unmask(jar)
+assert unmasked["a"] == SecretInteger(-5)
+assert unmasked["b"] == Array([SecretUnsignedInteger(1), SecretUnsignedInteger(2), SecretUnsignedInteger(3)])
+assert unmasked["c"] == Boolean(True)
+assert unmasked["d"] == SecretBlob(bytearray([1, 2]))
+assert unmasked["e"] == Tuple(SecretInteger(1), Integer(2))
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_schema_errors() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+schema = NadaValuesSchema({
+    "a": NadaType.secret_unsigned_integer(),
+    "b": NadaType.array(NadaType.secret_integer(), 2),
+    "c": NadaType.secret_integer(),
+})
+errors = schema.validate({"a": -1, "b": [1, "x"], "c": Integer(1), "d": 4})
+assert set(errors.keys()) == {"a", "b", "c", "d"}
+assert "element 1" in errors["b"]
+assert schema.validate({"a": 1, "b": [1, 2]}) == {}
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+try:
+    masker.mask({"a": -1}, schema)
+    raise AssertionError("Expected ValueError not raised for invalid value")
+except ValueError as e:
+    assert "'a'" in str(e), "Unexpected error message"
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_secret_array_typed() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+array = Array([], NadaType.secret_integer())
+assert len(array) == 0
+assert array.inner_type == NadaType.secret_integer()
+array.append(SecretInteger(1))
+array.append(SecretInteger(2))
+array.append(SecretInteger(3))
+assert len(array) == 3
+
+try:
+    array.append(Integer(4))
+    raise AssertionError("Expected ValueError not raised for wrong element type")
+except ValueError as e:
+    assert "element 3" in str(e), "Unexpected error message"
+
+try:
+    Array([Array([SecretInteger(1)]), Array([SecretInteger(1), SecretInteger(2)])])
+    raise AssertionError("Expected ValueError not raised for mixed inner sizes")
+except ValueError as e:
+    assert "element 1" in str(e), "Unexpected error message"
+
+try:
+    Array([])
+    raise AssertionError("Expected ValueError not raised for untyped empty array")
+except ValueError:
+    pass
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_secret_array_sequence_protocol() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+array = Array([SecretInteger(i) for i in range(5)])
+assert array[0] == SecretInteger(0)
+assert array[-1] == SecretInteger(4)
+assert list(array) == [SecretInteger(i) for i in range(5)]
+assert array[1:4] == Array([SecretInteger(1), SecretInteger(2), SecretInteger(3)])
+assert array[::2] == Array([SecretInteger(0), SecretInteger(2), SecretInteger(4)])
+assert len(array[3:1]) == 0
+
+array[2] = SecretInteger(22)
+assert array[2] == SecretInteger(22)
+
+try:
+    array[5]
+    raise AssertionError("Expected IndexError not raised")
+except IndexError:
+    pass
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_validate_ranges() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+prime = 18446744072637906947
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+values = {
+    "ok": SecretInteger(-5),
+    "big": SecretInteger(prime),
+    "array": Array([SecretUnsignedInteger(1), SecretUnsignedInteger(prime)]),
+}
+violations = masker.validate_ranges(values)
+assert [v.key for v in violations] == ["array[1]", "big"]
+assert violations[0].value == prime
+assert violations[0].min == 0 and violations[0].max == prime - 1
+assert violations[1].min == -(prime - 1) // 2 and violations[1].max == (prime - 1) // 2
+assert masker.validate_ranges({"ok": SecretInteger(-5)}) == []
+
+# The primes come from the upstream modulo types, pin them down for every size.
+wide_primes = [
+    (SecretMasker.new_128_bit_safe_prime(1, parties), 340282366920938463463374607429104828419),
+    (SecretMasker.new_256_bit_safe_prime(1, parties), 115792089237316195423570985008687907853269984665640564039457584007911397392387),
+]
+for wide, wide_prime in wide_primes:
+    [violation] = wide.validate_ranges({"big": SecretUnsignedInteger(wide_prime)})
+    assert violation.max == wide_prime - 1
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_pickle_values() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+import pickle
+
+values = [
+    SecretInteger(-22),
+    Integer(2**70),
+    SecretUnsignedInteger(22),
+    UnsignedInteger(0),
+    SecretBoolean(True),
+    Boolean(False),
+    SecretBlob(bytearray([1, 2, 3])),
+    Array([Array([SecretInteger(1), SecretInteger(2)]), Array([SecretInteger(3), SecretInteger(4)])]),
+    Array([], NadaType.secret_boolean()),
+    Tuple(SecretInteger(1), Integer(2)),
+    NTuple([SecretInteger(1), Boolean(True)]),
+    Object({"a": SecretInteger(1), "b": UnsignedInteger(2)}),
+    EcdsaPrivateKey(bytearray(range(1, 33))),
+    EcdsaDigestMessage(bytearray(range(32))),
+    EcdsaPublicKey(bytearray(range(33))),
+    StoreId(bytearray(range(16))),
+    EddsaMessage(bytearray(b"message")),
+    EddsaPublicKey(bytearray(range(32))),
+]
+for value in values:
+    restored = pickle.loads(pickle.dumps(value))
+    assert type(restored) is type(value), repr(value)
+    assert restored == value, repr(value)
+    assert nada_value_from_bytes(nada_value_to_bytes(value)) == value
+assert pickle.loads(pickle.dumps(SecretInteger(-3, prime_bits=64))).prime_bits == 64
+
+nada_type = NadaType.array(NadaType.tuple(NadaType.secret_integer(), NadaType.boolean()), 2)
+assert pickle.loads(pickle.dumps(nada_type)) == nada_type
+
+try:
+    nada_value_from_bytes(b"\xff\x00")
+    raise AssertionError("Expected ValueError not raised for unsupported version")
+except ValueError as e:
+    assert "unsupported encoding version" in str(e), "Unexpected error message"
+
+# Every level of nesting is a single byte, deeply nested input is rejected instead of overflowing the stack.
+def nested_ntuples(depth):
+    return b"\x01" + b"\x09\x01" * depth + b"\x02\x01"
+
+value = nada_value_from_bytes(nested_ntuples(64))
+for _ in range(64):
+    value = value.value[0]
+assert value == Boolean(True)
+for depth in [65, 1_000_000]:
+    try:
+        nada_value_from_bytes(nested_ntuples(depth))
+        raise AssertionError("Expected ValueError not raised for deeply nested value")
+    except ValueError as e:
+        assert str(e) == "input is nested more than 64 levels deep", str(e)
+try:
+    nada_type_from_bytes(b"\x01" + b"\x08" * 1_000_000)
+    raise AssertionError("Expected ValueError not raised for deeply nested type")
+except ValueError as e:
+    assert str(e) == "input is nested more than 64 levels deep", str(e)
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_values_json() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+import json
+
+values = {
+    "a": SecretInteger(-2**100),
+    "b": UnsignedInteger(7),
+    "c": SecretBoolean(True),
+    "d": SecretBlob(bytearray([0, 255])),
+    "e": Array([], NadaType.secret_unsigned_integer()),
+    "f": Array([Tuple(SecretInteger(1), Boolean(False))]),
+    "g": NTuple([Integer(1), SecretUnsignedInteger(2)]),
+    "h": Object({"z": SecretInteger(1), "a": EcdsaDigestMessage(bytearray(range(32)))}),
+    "i": EcdsaPrivateKey(bytearray(range(1, 33))),
+    "j": StoreId(bytearray(range(16))),
+}
+document = values_to_json(values)
+assert values_from_json(document) == values
+assert values_from_json(values_to_json(values, indent=True)) == values
+
+parsed = json.loads(document)
+assert parsed["version"] == 1
+assert parsed["values"]["a"] == {"type": "SecretInteger", "bits": 128, "value": str(-2**100)}
+assert parsed["values"]["b"] == {"type": "UnsignedInteger", "bits": 64, "value": "7"}
+assert parsed["values"]["d"] == {"type": "SecretBlob", "value": "00ff"}
+assert parsed["values"]["e"]["inner_type"] == {"type": "SecretUnsignedInteger"}
+assert list(parsed["values"]["h"]["value"].keys()) == ["z", "a"]
+
+try:
+    values_from_json('{"version": 1, "values": {"x": {"type": "SecretInteger", "value": 5}}}')
+    raise AssertionError("Expected ValueError not raised for non-string integer")
+except ValueError as e:
+    assert "value 'x'" in str(e), "Unexpected error message"
+
+try:
+    values_from_json('{"version": 1, "values": {"x": {"type": "Float", "value": "1.0"}}}')
+    raise AssertionError("Expected ValueError not raised for unknown type")
+except ValueError as e:
+    assert "unknown type 'Float'" in str(e), "Unexpected error message"
+
+try:
+    values_from_json('{"version": 1, "values": {"x": {"type": "Integer", "bits": 64, "value": "%d"}}}' % 2**64)
+    raise AssertionError("Expected ValueError not raised for integer wider than its width tag")
+except ValueError as e:
+    assert "64 bit prime" in str(e), str(e)
+
+try:
+    values_from_json('{"version": 1, "values": {"x": {"type": "Integer", "bits": 32, "value": "1"}}}')
+    raise AssertionError("Expected ValueError not raised for unsupported width")
+except ValueError as e:
+    assert "unsupported integer width" in str(e), str(e)
+
+huge = {"type": "Array", "inner_type": {"type": "Integer"}, "size": 2**40}
+try:
+    values_from_json(json.dumps({"version": 1, "values": {"x": {"type": "Array", "inner_type": huge, "value": []}}}))
+    raise AssertionError("Expected ValueError not raised for huge array size")
+except ValueError as e:
+    assert "array size 1099511627776 is larger than the maximum" in str(e), str(e)
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_integer_arithmetic() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+assert SecretInteger(7) + SecretInteger(3) == SecretInteger(10)
+assert SecretInteger(7) - Integer(10) == SecretInteger(-3)
+assert Integer(7) * SecretInteger(3) == SecretInteger(21)
+assert 7 // SecretInteger(-2) == SecretInteger(-4)
+assert SecretInteger(-7) % 3 == SecretInteger(2)
+assert Integer(2) + 3 == Integer(5)
+assert SecretUnsignedInteger(5) + UnsignedInteger(1) == SecretUnsignedInteger(6)
+assert UnsignedInteger(9) // 2 == UnsignedInteger(4)
+assert (SecretBoolean(True) & Boolean(False)) == SecretBoolean(False)
+assert (Boolean(True) ^ True) == Boolean(False)
+
+assert int(SecretInteger(-5)) == -5
+assert [1, 2, 3][Integer(1)] == 2
+assert sorted([SecretInteger(3), SecretInteger(-1), SecretInteger(2)]) == [SecretInteger(-1), SecretInteger(2), SecretInteger(3)]
+assert UnsignedInteger(1) < UnsignedInteger(2)
+assert Boolean(False) < Boolean(True)
+assert not Boolean(False) and Boolean(True)
+# Secret booleans refuse truth testing, so a secret isn't branched on by accident.
+try:
+    bool(SecretBoolean(False))
+    raise AssertionError("Expected TypeError not raised for truth testing a secret boolean")
+except TypeError:
+    pass
+
+# Values are immutable, so they can be hashed; equal values hash the same.
+assert len({SecretInteger(1), SecretInteger(1), Integer(1)}) == 2
+assert {UnsignedInteger(3): "a"}[UnsignedInteger(3)] == "a"
+assert hash(SecretBoolean(True)) == hash(SecretBoolean(True))
+assert SecretInteger(1) != Integer(1)
+
+try:
+    SecretInteger(1) + UnsignedInteger(1)
+    raise AssertionError("Expected TypeError not raised for mixed signedness")
+except TypeError:
+    pass
+
+try:
+    SecretInteger(1) // 0
+    raise AssertionError("Expected ZeroDivisionError not raised")
+except ZeroDivisionError:
+    pass
+
+try:
+    UnsignedInteger(1) - 2
+    raise AssertionError("Expected OverflowError not raised for negative unsigned result")
+except OverflowError:
+    pass
+
+try:
+    SecretInteger(2**254) * 4
+    raise AssertionError("Expected OverflowError not raised")
+except OverflowError as e:
+    assert "outside of the range" in str(e), "Unexpected error message"
+
+# Results that don't fit a smaller prime are caught by the masker that uses it.
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+try:
+    masker.mask({"a": SecretInteger(2**62) * 2})
+    raise AssertionError("Expected ValueError not raised")
+except ValueError as e:
+    assert str(e) == "value 'a' can't be represented with the masker's 64 bit prime", str(e)
+
+# Values can choose the prime their arithmetic is checked against, the smallest chosen prime wins.
+assert SecretInteger(5, prime_bits=64).prime_bits == 64
+assert SecretInteger(5).prime_bits is None
+assert (SecretInteger(5, prime_bits=128) + Integer(1, prime_bits=64)).prime_bits == 64
+assert (UnsignedInteger(5, prime_bits=128) * 2).prime_bits == 128
+try:
+    SecretInteger(2**62, prime_bits=64) * 2
+    raise AssertionError("Expected OverflowError not raised")
+except OverflowError as e:
+    assert "64 bit safe prime" in str(e), str(e)
+try:
+    SecretUnsignedInteger(2**64, prime_bits=64)
+    raise AssertionError("Expected OverflowError not raised")
+except OverflowError:
+    pass
+try:
+    Integer(1, prime_bits=32)
+    raise AssertionError("Expected ValueError not raised")
+except ValueError:
+    pass
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_secret_repr_redacted() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+assert repr(SecretInteger(42)) == "SecretInteger(<redacted>)"
+assert "42" in SecretInteger(42).reveal()
+assert repr(SecretUnsignedInteger(42)) == "SecretUnsignedInteger(<redacted>)"
+assert repr(SecretBoolean(True)) == "SecretBoolean(<redacted>)"
+assert repr(SecretBlob(bytearray(b"hidden"))) == "Blob(<redacted>)"
+assert repr(EcdsaPrivateKey(bytearray(range(1, 33)))) == "EcdsaPrivateKey(<redacted>)"
+assert repr(EddsaPrivateKey(bytearray(range(1, 33)))) == "EddsaPrivateKey(<redacted>)"
+assert repr(Integer(42)) == "Integer(42)"
+
+array = Array([SecretInteger(42), SecretInteger(43)])
+assert "42" not in repr(array) and "42" not in str(array)
+assert "42" in array.reveal()
+assert "42" not in repr(Tuple(SecretInteger(42), Integer(1)))
+assert "42" not in repr(NTuple([SecretInteger(42)]))
+assert "42" not in repr(Object({"a": SecretInteger(42)}))
+
+value = SecretInteger(2**200)
+assert value.value == 2**200
+try:
+    value.value = 7
+    raise AssertionError("Expected AttributeError not raised for assignment")
+except AttributeError:
+    pass
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_array_buffer_protocol() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+from array import array
+
+data = array("q", [-1, 0, 5, 2**40])
+assert Array.from_numpy(data) == Array([SecretInteger(-1), SecretInteger(0), SecretInteger(5), SecretInteger(2**40)])
+assert Array.from_numpy(data, secret=False) == Array([Integer(-1), Integer(0), Integer(5), Integer(2**40)])
+assert Array.from_numpy(array("Q", [1, 2**63]), signed=False) == Array([SecretUnsignedInteger(1), SecretUnsignedInteger(2**63)])
+assert Array.from_numpy(memoryview(bytes([1, 0])).cast("?")) == Array([SecretBoolean(True), SecretBoolean(False)])
+assert Array.from_numpy(array("q"), signed=False).inner_type == NadaType.secret_unsigned_integer()
+
+try:
+    Array.from_numpy(data, signed=False)
+    raise AssertionError("Expected ValueError not raised for negative unsigned element")
+except ValueError as e:
+    assert "element 0 is negative" in str(e), "Unexpected error message"
+
+try:
+    Array.from_numpy(array("d", [1.0]))
+    raise AssertionError("Expected ValueError not raised for float buffer")
+except ValueError as e:
+    assert "unsupported buffer format" in str(e), "Unexpected error message"
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+masked = masker.mask({
+    "vector": Array.from_numpy(array("q", range(-50, 50))),
+    "flags": Array.from_numpy(memoryview(bytes([1, 0, 1])).cast("?")),
+})
+jar = masker.build_jar()
+for party in parties:
+    jar.add_element(party, masked[party])
+unmasked = masker.unmask(jar)
+assert unmasked["vector"] == Array([SecretInteger(i) for i in range(-50, 50)])
+assert unmasked["flags"] == Array([SecretBoolean(True), SecretBoolean(False), SecretBoolean(True)])
+
+# Whether a bare buffer is secret or signed can't be guessed.
+try:
+    masker.mask({"vector": array("q", [1, 2])})
+    raise AssertionError("Expected TypeError not raised for a bare buffer")
+except TypeError as e:
+    assert "Array.from_numpy" in str(e), str(e)
+
+schema = NadaValuesSchema({"vector": NadaType.array(NadaType.secret_unsigned_integer(), 2)})
+assert schema.coerce({"vector": array("q", [3, 4])})["vector"] == Array([SecretUnsignedInteger(3), SecretUnsignedInteger(4)])
+assert masker.mask({"vector": array("q", [3, 4])}, schema)
+
+try:
+    import numpy as np
+except ImportError:
+    np = None
+if np is not None:
+    values = np.arange(-5, 5, dtype=np.int64)
+    secret_array = Array.from_numpy(values)
+    assert (secret_array.to_numpy() == values).all()
+    assert secret_array.to_numpy().dtype == np.int64
+    assert Array.from_numpy(values.astype(">i8")) == secret_array
+    assert Array([SecretBoolean(True)]).to_numpy().dtype == np.bool_
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_rationals() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from decimal import Decimal
+from fractions import Fraction
+import pickle
+from nillion_client_core import *
+
+assert SecretRational(1.5, precision=8).scaled_value == 384
+assert SecretRational(Decimal("-0.25")).scaled_value == -(2**14)
+assert Rational(Fraction(1, 3), precision=2).scaled_value == 1
+assert Rational(Fraction(3, 8), precision=2).scaled_value == 2
+assert Rational(Fraction(5, 8), precision=2).scaled_value == 2
+assert Rational(0.5).value == 0.5
+assert float(Rational.from_scaled(-3, 1)) == -1.5
+assert repr(SecretRational(1.5)) == "SecretRational(<redacted>, precision=16)"
+assert SecretRational(1.5).reveal() == "SecretRational(1.5, precision=16)"
+assert repr(Rational(2)) == "Rational(2.0, precision=16)"
+assert pickle.loads(pickle.dumps(SecretRational(0.75, 4))) == SecretRational(0.75, 4)
+assert SecretRational(0.5, 8) == SecretRational(0.5, 16)
+assert hash(SecretRational(0.5, 8)) == hash(SecretRational(0.5, 16))
+assert hash(Rational(0, 8)) == hash(Rational(0, 16))
+assert SecretRational(0.5) != Rational(0.5)
+assert Rational(0.25) < Rational(0.5, 4)
+assert sorted([SecretRational(1.5), SecretRational(-2, 4)]) == [SecretRational(-2), SecretRational(1.5)]
+try:
+    SecretRational(0.5) < Rational(1)
+    raise AssertionError("Expected TypeError not raised for mixed comparison")
+except TypeError:
+    pass
+
+try:
+    SecretRational("1.5")
+    raise AssertionError("Expected TypeError not raised for string")
+except TypeError:
+    pass
+
+try:
+    SecretRational(2.0**64, precision=16)
+    SecretRational(2.0**250, precision=16)
+    raise AssertionError("Expected OverflowError not raised")
+except OverflowError as e:
+    assert "outside of the range" in str(e), "Unexpected error message"
+
+# Precisions are bounded before anything is scaled by them.
+for build in [
+    lambda: SecretRational(1, precision=4_000_000_000),
+    lambda: Rational.from_scaled(1, 255),
+    lambda: NadaType.secret_rational(4_000_000_000),
+    lambda: NadaType.rational(255),
+]:
+    try:
+        build()
+        raise AssertionError("Expected ValueError not raised for a large precision")
+    except ValueError as e:
+        assert str(e).startswith("precision must be at most 254"), str(e)
+assert Rational.from_scaled(-1, 254).scaled_value == -1
+
+try:
+    SecretRational.from_scaled(2**256, 16)
+    raise AssertionError("Expected OverflowError not raised")
+except OverflowError as e:
+    assert "outside of the range" in str(e), "Unexpected error message"
+
+rational_type = NadaType.array(NadaType.secret_rational(8), 2)
+assert rational_type.rational_precision == 8
+assert repr(rational_type) == "Array [SecretRational(8):2]"
+assert pickle.loads(pickle.dumps(rational_type)) == rational_type
+try:
+    NadaType.tuple(NadaType.secret_rational(), NadaType.secret_integer())
+    raise AssertionError("Expected ValueError not raised for nested rational")
+except ValueError:
+    pass
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+schema = NadaValuesSchema({
+    "price": NadaType.secret_rational(8),
+    "weights": rational_type,
+    "count": NadaType.secret_integer(),
+})
+values = {"price": Decimal("-12.5"), "weights": [0.25, Fraction(1, 2)], "count": 3}
+masked = masker.mask(values, schema)
+jar = masker.build_jar()
+for party in parties:
+    jar.add_element(party, masked[party])
+assert masker.unmask(jar, schema) == {"price": -12.5, "weights": [0.25, 0.5], "count": SecretInteger(3)}
+
+masked = masker.mask({"price": SecretRational(1.75, 8)})
+jar = masker.build_jar()
+for party in parties:
+    jar.add_element(party, masked[party])
+assert masker.unmask(jar)["price"] == SecretInteger(448)
+
+errors = schema.validate({"price": Rational(1.0, 8), "weights": [0.5], "count": 1})
+assert errors["price"] == "expected secret rational, got rational", errors
+assert errors["weights"] == "expected 2 elements, got 1", errors
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_encrypted_values_protobuf() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+values = {
+    "a": SecretInteger(-5),
+    "b": Integer(7),
+    "c": Array([SecretUnsignedInteger(1), SecretUnsignedInteger(2)]),
+    "d": Tuple(SecretBoolean(True), Integer(3)),
+    "e": SecretBlob(bytearray(b"hello")),
+}
+masked = masker.mask(values)
+jar = masker.build_jar()
+for party in parties:
+    decoded = {
+        name: EncryptedNadaValue.from_protobuf_bytes(value.to_protobuf_bytes())
+        for name, value in masked[party].items()
+    }
+    jar.add_element(party, decoded)
+assert masker.unmask(jar) == values
+
+party_values = masked[parties[0]]
+single = {"a": party_values["a"]}
+# A NamedValue message holding field 1 (name) and field 2 (the encoded value).
+value = party_values["a"].to_protobuf_bytes()
+named_value = bytes([0x0A, 1]) + b"a" + bytes([0x12, len(value)]) + value
+assert encode_named_values(single) == bytes([0x0A, len(named_value)]) + named_value
+assert encode_named_values(single, field_number=5) == bytes([0x2A, len(named_value)]) + named_value
+assert len(encode_named_values(party_values)) > len(encode_named_values(single))
+
+for invalid in [b"\xff", b""]:
+    try:
+        EncryptedNadaValue.from_protobuf_bytes(invalid)
+        raise AssertionError("Expected ValueError not raised")
+    except ValueError:
+        pass
+
+try:
+    encode_named_values(single, field_number=0)
+    raise AssertionError("Expected ValueError not raised for field number 0")
+except ValueError:
+    pass
+
+# NTuples and objects are only supported locally, the protobuf messages have no variant for them.
+local = masker.mask({"n": NTuple([SecretInteger(1)]), "o": Object({"x": Integer(1)})})[parties[0]]
+assert isinstance(local["n"], EncryptedNadaValue.NTuple)
+assert isinstance(local["o"], EncryptedNadaValue.Object)
+for value in local.values():
+    try:
+        value.to_protobuf_bytes()
+        raise AssertionError("Expected ValueError not raised for local only value")
+    except ValueError as e:
+        assert "no protobuf representation" in str(e), str(e)
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_encoded_payload_size() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_128_bit_safe_prime(1, parties)
+values = {
+    "a": SecretInteger(-5),
+    "b": UnsignedInteger(0),
+    "c": Array([Array([SecretBoolean(True)] * 2)] * 200),
+    "d": Tuple(SecretUnsignedInteger(1), Integer(3)),
+    "e": SecretBlob(bytearray(b"x" * 1000)),
+    "f": EcdsaDigestMessage(bytearray(32)),
+    "g": StoreId(bytearray(16)),
+}
+masked = masker.mask(values)
+for party in parties:
+    for name, value in masked[party].items():
+        assert value.encoded_len() == len(value.to_protobuf_bytes()), name
+
+expected = sum(len(name) + len(value.to_protobuf_bytes()) for name, value in masked[parties[0]].items())
+assert masker.encoded_payload_size(masked) == expected
+
+missing = dict(masked)
+missing[parties[1]] = {k: v for k, v in masked[parties[1]].items() if k != "a"}
+try:
+    masker.encoded_payload_size(missing)
+    raise AssertionError("Expected ValueError not raised for missing value")
+except ValueError as e:
+    assert "'a'" in str(e), str(e)
+
+resized = dict(masked)
+resized[parties[2]] = dict(masked[parties[2]], a=masked[parties[2]]["c"])
+try:
+    masker.encoded_payload_size(resized)
+    raise AssertionError("Expected ValueError not raised for mismatched size")
+except ValueError as e:
+    assert "bytes long" in str(e), str(e)
+
+try:
+    masker.encoded_payload_size({parties[0]: masked[parties[0]]})
+    raise AssertionError("Expected ValueError not raised for missing party")
+except ValueError as e:
+    assert "missing payloads" in str(e), str(e)
+
+# As many payloads as parties, but one of them is for a party the masker doesn't know.
+foreign = dict(masked)
+foreign[PartyId.from_bytes(b"x")] = foreign.pop(parties[2])
+try:
+    masker.encoded_payload_size(foreign)
+    raise AssertionError("Expected ValueError not raised for foreign party")
+except ValueError as e:
+    assert "not one of the masker's parties" in str(e), str(e)
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_checked_share_decoding() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+masked = masker.mask({
+    "a": SecretInteger(1),
+    "b": Array([SecretInteger(2), SecretInteger(3)]),
+})
+
+def add_tampered(values, checked=True):
+    jar = masker.build_jar()
+    jar.add_element(parties[0], values, checked)
+
+share = masked[parties[0]]["a"]
+assert len(share.value) == 8
+
+for tampered, message in [
+    (share.value[:7], "expected 8 bytes for a 64 bit prime, got 7"),
+    ([0xFF] * 8, "encoded number is not lower than the prime"),
+]:
+    try:
+        add_tampered(dict(masked[parties[0]], a=EncryptedNadaValue.ShamirShareInteger(value=tampered)))
+        raise AssertionError("Expected ValueError not raised")
+    except ValueError as e:
+        assert str(e).startswith("party ") and str(e).endswith(f": value 'a': {message}"), str(e)
+
+# Unchecked decoding lets the corrupt share through.
+add_tampered(dict(masked[parties[0]], a=EncryptedNadaValue.ShamirShareInteger(value=[0xFF] * 8)), checked=False)
+
+array = masked[parties[0]]["b"]
+elements = list(array.values)
+elements[1] = EncryptedNadaValue.ShamirShareUnsignedInteger(value=list(elements[1].value))
+try:
+    add_tampered(dict(masked[parties[0]], b=EncryptedNadaValue.Array(inner_type=array.inner_type, values=elements)))
+    raise AssertionError("Expected ValueError not raised for mismatched element")
+except ValueError as e:
+    assert "value 'b[1]': element has type" in str(e), str(e)
+
+# Shares for a bigger prime are rejected by a jar for a smaller one.
+wide = SecretMasker.new_128_bit_safe_prime(1, parties).mask({"a": SecretInteger(1)})
+try:
+    add_tampered(wide[parties[0]])
+    raise AssertionError("Expected ValueError not raised for the wrong prime")
+except ValueError as e:
+    assert "expected 8 bytes for a 64 bit prime, got 16" in str(e), str(e)
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_encrypted_bundle() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+import os
+import tempfile
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+secrets = {
+    "a": SecretInteger(-42),
+    "b": Array([SecretUnsignedInteger(2), SecretUnsignedInteger(3)]),
+    "c": SecretBlob(bytearray(b"hello bundle")),
+    "d": Integer(7),
+    "e": Tuple(SecretBoolean(True), SecretInteger(5)),
+}
+masked = masker.mask(secrets)
+
+bundles = {}
+with tempfile.TemporaryDirectory() as directory:
+    for index, party in enumerate(parties):
+        bundle = EncryptedBundle(party, masked[party], prime_bits=64, polynomial_degree=1, created_at=1700000000)
+        assert bundle.party == party
+        assert bundle.prime_bits == 64
+        assert bundle.polynomial_degree == 1
+        assert bundle.created_at == 1700000000
+        assert bundle.to_bytes().startswith(b"NILB\x01")
+
+        path = os.path.join(directory, f"party-{index}.bundle")
+        bundle.save(path)
+        loaded = EncryptedBundle.load(path)
+        assert loaded.to_bytes() == bundle.to_bytes()
+        assert sorted(loaded.values) == sorted(secrets)
+        bundles[party] = EncryptedBundle.from_bytes(bundle.to_bytes())
+
+jar = masker.build_jar()
+for party in parties:
+    jar.add_element(party, bundles[party])
+unmasked = masker.unmask(jar)
+assert unmasked["a"].value == -42
+assert [v.value for v in unmasked["b"].value] == [2, 3]
+assert unmasked["c"].value == bytearray(b"hello bundle")
+assert unmasked["d"].value == 7
+
+assert EncryptedBundle(parties[0], {}, 64, 1).created_at > 0
+
+try:
+    masker.build_jar().add_element(parties[1], bundles[parties[0]])
+    raise AssertionError("Expected ValueError not raised for the wrong party")
+except ValueError as e:
+    assert "bundle belongs to party" in str(e), str(e)
+
+wide = EncryptedBundle(parties[0], masked[parties[0]], prime_bits=128, polynomial_degree=1)
+try:
+    masker.build_jar().add_element(parties[0], wide)
+    raise AssertionError("Expected ValueError not raised for the wrong prime")
+except ValueError as e:
+    assert str(e) == "bundle uses a 128 bit prime but the jar uses a 64 bit prime", str(e)
+
+steep = EncryptedBundle(parties[0], masked[parties[0]], prime_bits=64, polynomial_degree=2)
+try:
+    masker.build_jar().add_element(parties[0], steep)
+    raise AssertionError("Expected ValueError not raised for the wrong polynomial degree")
+except ValueError as e:
+    assert str(e) == "bundle was masked with polynomial degree 2 but the jar uses polynomial degree 1", str(e)
+
+encoded = bundles[parties[0]].to_bytes()
+for corrupt, message in [
+    (b"XXXX" + encoded[4:], "not an encrypted bundle"),
+    (encoded[:4] + b"\x02" + encoded[5:], "unsupported bundle version: 2"),
+    (encoded[:-1], "unexpected end of input"),
+    (encoded + b"\x00", "trailing bytes"),
+]:
+    try:
+        EncryptedBundle.from_bytes(corrupt)
+        raise AssertionError("Expected ValueError not raised")
+    except ValueError as e:
+        assert message in str(e), str(e)
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_sealed_bundle() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+import os
+import tempfile
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+masked = masker.mask({"a": SecretInteger(42), "b": SecretBlob(bytearray(b"sealed"))})
+bundles = {party: EncryptedBundle(party, masked[party], prime_bits=64, polynomial_degree=1) for party in parties}
+
+def expect_error(function, message):
+    try:
+        function()
+        raise AssertionError("Expected ValueError not raised")
+    except ValueError as e:
+        assert message in str(e), str(e)
+
+key = os.urandom(32)
+with tempfile.TemporaryDirectory() as directory:
+    jar = masker.build_jar()
+    for index, party in enumerate(parties):
+        path = os.path.join(directory, f"party-{index}.sealed")
+        if index == 0:
+            bundles[party].save(path, passphrase="correct horse battery staple")
+            loaded = EncryptedBundle.load(path, party, passphrase="correct horse battery staple")
+            expect_error(lambda: EncryptedBundle.load(path, party, passphrase="wrong"), "wrong secret or tampered data")
+        else:
+            bundles[party].save(path, key=key)
+            loaded = EncryptedBundle.load(path, party, key=key)
+        assert loaded.to_bytes() == bundles[party].to_bytes()
+        expect_error(lambda: EncryptedBundle.load(path), "bundle is sealed")
+        jar.add_element(party, loaded)
+    unmasked = masker.unmask(jar)
+    assert unmasked["a"].value == 42
+    assert unmasked["b"].value == bytearray(b"sealed")
+
+sealed = bundles[parties[0]].seal(key=key)
+assert b"sealed" not in sealed
+assert EncryptedBundle.unseal(sealed, parties[0], key=key).to_bytes() == bundles[parties[0]].to_bytes()
+
+# A bundle sealed for a party can't be opened as another party's, even if its header is rewritten.
+expect_error(lambda: EncryptedBundle.unseal(sealed, parties[1], key=key), "bundle was sealed for party")
+assert sealed[:8] == b"NILS\x01\x00\x01\x00"
+relabeled = sealed[:7] + bytes([1]) + sealed[8:]
+expect_error(lambda: EncryptedBundle.unseal(relabeled, parties[1], key=key), "wrong secret or tampered data")
+
+tampered = sealed[:-1] + bytes([sealed[-1] ^ 1])
+expect_error(lambda: EncryptedBundle.unseal(tampered, parties[0], key=key), "wrong secret or tampered data")
+expect_error(lambda: EncryptedBundle.unseal(sealed, parties[0], key=os.urandom(32)), "wrong secret or tampered data")
+expect_error(lambda: EncryptedBundle.unseal(sealed, parties[0], key=key[:16]), "key must be exactly 32 bytes long")
+expect_error(lambda: EncryptedBundle.unseal(sealed, parties[0], passphrase="key"), "sealed with a key, not a passphrase")
+expect_error(lambda: bundles[parties[0]].seal(), "a passphrase or a key is required")
+expect_error(lambda: bundles[parties[0]].seal(passphrase="a", key=key), "not both")
+
+# The key derivation cost is read before the header is authenticated, so it's bounded.
+sealed = bundles[parties[0]].seal(passphrase="a")
+start = 6
+while sealed[start] & 0x80:
+    start += 1
+slow = sealed[:start + 1] + bytes([0xE8, 0x07]) + sealed[start + 2:]
+expect_error(lambda: EncryptedBundle.unseal(slow, parties[0], passphrase="a"), "key derivation iteration count is too large")
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_blob_streams() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+import io
+import os
+import tempfile
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+data = os.urandom(10000)
+
+with tempfile.TemporaryDirectory() as directory:
+    source = os.path.join(directory, "blob")
+    with open(source, "wb") as f:
+        f.write(data)
+
+    # Paths, file-like objects and callbacks can all be used as sinks.
+    paths = {party: os.path.join(directory, f"party-{i}.shares") for i, party in enumerate(parties)}
+    buffer = io.BytesIO()
+    pieces = []
+    sinks = {parties[0]: paths[parties[0]], parties[1]: buffer, parties[2]: pieces.append}
+    assert masker.mask_blob_stream(source, sinks, chunk_size=4096) == len(data)
+
+    streams = {parties[0]: paths[parties[0]], parties[1]: io.BytesIO(buffer.getvalue()), parties[2]: io.BytesIO(b"".join(pieces))}
+    output = io.BytesIO()
+    assert masker.unmask_blob_stream(streams, output) == len(data)
+    assert output.getvalue() == data
+
+    # Masking from a file-like object gives streams that unmask to a file.
+    sinks = {party: io.BytesIO() for party in parties}
+    assert masker.mask_blob_stream(io.BytesIO(data), sinks) == len(data)
+    target = os.path.join(directory, "unmasked")
+    assert masker.unmask_blob_stream({p: io.BytesIO(s.getvalue()) for p, s in sinks.items()}, target) == len(data)
+    with open(target, "rb") as f:
+        assert f.read() == data
+
+    # Empty blobs round trip.
+    empty = {party: io.BytesIO() for party in parties}
+    assert masker.mask_blob_stream(io.BytesIO(b""), empty) == 0
+    output = io.BytesIO()
+    assert masker.unmask_blob_stream({p: io.BytesIO(s.getvalue()) for p, s in empty.items()}, output) == 0
+    assert output.getvalue() == b""
+
+def expect_error(function, message):
+    try:
+        function()
+        raise AssertionError("Expected ValueError not raised")
+    except ValueError as e:
+        assert message in str(e), str(e)
+
+encoded = {p: s.getvalue() for p, s in sinks.items()}
+truncated = dict(encoded)
+truncated[parties[1]] = truncated[parties[1]][:-20]
+expect_error(
+    lambda: masker.unmask_blob_stream({p: io.BytesIO(s) for p, s in truncated.items()}, io.BytesIO()),
+    "unexpected end of share stream",
+)
+expect_error(
+    lambda: masker.unmask_blob_stream({parties[0]: io.BytesIO(encoded[parties[0]])}, io.BytesIO()),
+    "expected share streams for 3 parties, got 1",
+)
+wide = SecretMasker.new_128_bit_safe_prime(1, parties)
+expect_error(
+    lambda: wide.unmask_blob_stream({p: io.BytesIO(s) for p, s in encoded.items()}, io.BytesIO()),
+    "stream uses a 64 bit prime but the masker uses a 128 bit prime",
+)
+expect_error(lambda: masker.mask_blob_stream(io.BytesIO(data), sinks, chunk_size=0), "chunk size must be between")
+
+# Sinks are checked against the masker's parties before any of them is written to.
+stranger = PartyId.from_bytes(b"stranger")
+sinks = {party: io.BytesIO() for party in parties[:2]}
+sinks[stranger] = io.BytesIO()
+expect_error(lambda: masker.mask_blob_stream(io.BytesIO(data), sinks), "is not one of the masker's parties")
+sinks.pop(stranger)
+expect_error(lambda: masker.mask_blob_stream(io.BytesIO(data), sinks), "missing sinks for parties")
+assert all(sink.getvalue() == b"" for sink in sinks.values())
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_blob_envelope() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+
+def roundtrip(values):
+    masked = masker.mask(values)
+    jar = masker.build_jar()
+    for party in parties:
+        jar.add_element(party, masked[party])
+    return masker.unmask(jar)
+
+data = bytearray(b"gm, builder! " * 200)
+compressed = SecretBlob.with_digest(data, compress=True)
+assert len(compressed.value) < len(data)
+unmasked = roundtrip({
+    "plain": SecretBlob(data),
+    "digest": SecretBlob.with_digest(data),
+    "compressed": compressed,
+    "nested": Array([SecretBlob.with_digest(bytearray(b"a")), SecretBlob.with_digest(bytearray(b"b"), compress=True)]),
+})
+assert unmasked["plain"].value == data
+assert unmasked["digest"].value == data
+assert unmasked["compressed"].value == data
+assert [blob.value for blob in unmasked["nested"].value] == [bytearray(b"a"), bytearray(b"b")]
+
+tampered = SecretBlob.with_digest(data).value
+tampered[-1] ^= 1
+try:
+    roundtrip({"blob": SecretBlob(tampered)})
+    raise AssertionError("Expected BlobIntegrityError not raised")
+except BlobIntegrityError as e:
+    assert str(e).startswith("value 'blob': digest mismatch: expected "), str(e)
+assert issubclass(BlobIntegrityError, ValueError)
+
+corrupt = compressed.value
+corrupt[-4:] = b"\x00\x00\x00\x00"
+try:
+    roundtrip({"nested": Array([SecretBlob(corrupt)])})
+    raise AssertionError("Expected BlobIntegrityError not raised")
+except BlobIntegrityError as e:
+    assert str(e).startswith("value 'nested[0]': "), str(e)
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_parallel_masking() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+serial = SecretMasker.new_64_bit_safe_prime(1, parties)
+assert serial.threads is None and serial.pool is None
+pool = ThreadPool(4)
+parallel = SecretMasker.new_64_bit_safe_prime(1, parties, pool=pool)
+assert parallel.threads == 4
+assert SecretMasker.new_64_bit_safe_prime(1, parties, pool=parallel.pool).threads == 4
+
+values = {f"value-{i}": SecretInteger(i - 100) for i in range(200)}
+values["array"] = Array([SecretUnsignedInteger(1), SecretUnsignedInteger(2)])
+values["large"] = Array([SecretInteger(i) for i in range(1000)])
+values["single"] = Array([SecretBoolean(True)])
+values["blob"] = SecretBlob(bytearray(b"parallel"))
+values["public"] = Integer(9)
+
+def unmask(masker, masked):
+    jar = masker.build_jar()
+    for party in parties:
+        jar.add_element(party, masked[party])
+    return masker.unmask(jar)
+
+serial_masked = serial.mask(values)
+parallel_masked = parallel.mask(values)
+assert set(parallel_masked) == set(serial_masked)
+for party in parties:
+    assert set(parallel_masked[party]) == set(serial_masked[party])
+    assert serial.encoded_payload_size(parallel_masked) == serial.encoded_payload_size(serial_masked)
+
+# Shares masked either way unmask to the same values either way.
+expected = unmask(serial, serial_masked)
+assert unmask(parallel, serial_masked) == expected
+assert unmask(serial, parallel_masked) == expected
+assert unmask(parallel, parallel_masked) == expected
+assert expected["value-0"] == SecretInteger(-100)
+assert expected["large"] == values["large"]
+assert expected["single"] == values["single"]
+
+assert parallel.mask({}) == serial.mask({})
+
+try:
+    ThreadPool(0)
+    raise AssertionError("Expected ValueError not raised")
+except ValueError as e:
+    assert str(e) == "thread count must be at least 1", str(e)
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_seeded_masker() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+values = {
+    "a": SecretInteger(-5),
+    "b": Array([SecretUnsignedInteger(1), SecretUnsignedInteger(2)]),
+    "c": SecretBlob(bytearray(b"golden")),
+}
+
+def snapshot(masked):
+    return {
+        party: {name: value.to_protobuf_bytes() for name, value in party_values.items()}
+        for party, party_values in masked.items()
+    }
+
+golden = snapshot(SecretMasker.new_64_bit_safe_prime(1, parties, seed=b"golden").mask(values))
+assert snapshot(SecretMasker.new_64_bit_safe_prime(1, parties, seed=b"golden").mask(values)) == golden
+
+# The thread pool doesn't change seeded shares.
+parallel = SecretMasker.new_64_bit_safe_prime(1, parties, seed=b"golden", pool=ThreadPool(3))
+assert snapshot(parallel.mask(values)) == golden
+
+assert snapshot(SecretMasker.new_64_bit_safe_prime(1, parties, seed=b"other").mask(values)) != golden
+assert snapshot(SecretMasker.new_64_bit_safe_prime(1, parties).mask(values)) != golden
+
+# Successive calls on the same masker draw fresh randomness, but unmask to the same values.
+masker = SecretMasker.new_64_bit_safe_prime(1, parties, seed=b"golden")
+first = masker.mask(values)
+second = masker.mask(values)
+assert snapshot(first) == golden
+assert snapshot(second) != golden
+for masked in (first, second):
+    jar = masker.build_jar()
+    for party in parties:
+        jar.add_element(party, masked[party])
+    unmasked = masker.unmask(jar)
+    assert unmasked["a"] == SecretInteger(-5)
+    assert unmasked["c"] == SecretBlob(bytearray(b"golden"))
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_threshold_unmask() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+from itertools import combinations
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(5)]
+masker = SecretMasker.new_64_bit_safe_prime(2, parties)
+masked = masker.mask({"a": SecretInteger(-7), "b": SecretBlob(bytearray(b"threshold"))})
+
+# Any 3 of the 5 parties are enough.
+for subset in combinations(parties, 3):
+    jar = masker.build_jar()
+    for party in subset:
+        jar.add_element(party, masked[party])
+    assert jar.is_sufficient()
+    assert jar.missing_parties() == [party for party in parties if party not in subset]
+    unmasked = masker.unmask(jar)
+    assert unmasked["a"] == SecretInteger(-7)
+    assert unmasked["b"] == SecretBlob(bytearray(b"threshold"))
+
+jar = masker.build_jar()
+assert not jar.is_sufficient()
+assert jar.missing_parties() == parties
+jar.add_element(parties[3], masked[parties[3]])
+jar.add_element(parties[1], masked[parties[1]])
+assert not jar.is_sufficient()
+try:
+    masker.unmask(jar)
+    raise AssertionError("Expected ValueError not raised")
+except ValueError as e:
+    missing = ", ".join(repr(p)[len("PartyId('"):-2] for p in [parties[0], parties[2], parties[4]])
+    assert str(e) == f"unmasking requires shares from at least 3 of 5 parties, got 2; missing parties: {missing}", str(e)
+
+try:
+    jar.add_element(parties[1], masked[parties[1]])
+    raise AssertionError("Expected ValueError not raised for a duplicate party")
+except ValueError as e:
+    assert "already put an element in the jar" in str(e), str(e)
+
+try:
+    jar.add_element(PartyId.from_bytes(b"stranger"), masked[parties[0]])
+    raise AssertionError("Expected ValueError not raised for an unknown party")
+except ValueError as e:
+    assert "is not one of the masker's parties" in str(e), str(e)
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_robust_unmask() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(5)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+masked = masker.mask({"a": SecretInteger(42), "b": SecretUnsignedInteger(7), "c": SecretBlob(bytearray(b"robust"))})
+
+def build_jar(shares, contributors=parties):
+    jar = masker.build_jar()
+    for party in contributors:
+        jar.add_element(party, shares[party])
+    return jar
+
+# Consistent shares are unmasked and nothing is reported.
+result = masker.robust_unmask(build_jar(masked))
+assert result.values == masker.unmask(build_jar(masked))
+assert result.faulty_parties == []
+assert result.faulty_keys == {}
+
+# Corrupt one party's share of "a" by swapping in another party's share of it, which lies on the same polynomial
+# at a different abscissa.
+corrupted = {party: dict(values) for party, values in masked.items()}
+corrupted[parties[2]]["a"] = masked[parties[3]]["a"]
+assert masker.unmask(build_jar(corrupted, parties[1:3]))["a"] != SecretInteger(42)
+result = masker.robust_unmask(build_jar(corrupted))
+assert result.values["a"] == SecretInteger(42)
+assert result.values["b"] == SecretUnsignedInteger(7)
+assert result.values["c"] == SecretBlob(bytearray(b"robust"))
+assert result.faulty_parties == [parties[2]]
+assert result.faulty_keys == {"a": [parties[2]]}
+
+# Two faulty shares out of five can't be corrected with a degree 1 polynomial.
+corrupted[parties[4]]["a"] = masked[parties[0]]["a"]
+try:
+    masker.robust_unmask(build_jar(corrupted))
+    raise AssertionError("Expected ValueError not raised")
+except ValueError as e:
+    assert str(e) == "value 'a': too many inconsistent shares, at most 1 of 5 can be corrected", str(e)
+
+# Correcting requires more shares than unmasking does.
+try:
+    masker.robust_unmask(build_jar(masked, parties[:2]))
+    raise AssertionError("Expected ValueError not raised")
+except ValueError as e:
+    assert "correcting faulty shares requires shares from more than 2 parties, got 2" in str(e), str(e)
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_public_value_consistency() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+values = {
+    "a": SecretInteger(1),
+    "p": Integer(5),
+    "o": Object({"s": StoreId(bytearray(range(16)))}),
+    "k": EcdsaPrivateKey(bytearray(range(1, 33))),
+}
+masked = masker.mask(values)
+other = masker.mask({"p": Integer(6)})
+
+def build_jar(shares, contributors=parties):
+    jar = masker.build_jar()
+    for party in contributors:
+        jar.add_element(party, shares[party])
+    return jar
+
+assert build_jar(masked).verify_consistency() == []
+
+tampered = {party: dict(shares) for party, shares in masked.items()}
+tampered[parties[2]]["p"] = other[parties[2]]["p"]
+del tampered[parties[1]]["o"]
+mismatches = build_jar(tampered).verify_consistency()
+assert [mismatch.key for mismatch in mismatches] == ["o.s", "p"], mismatches
+assert mismatches[0].majority == bytes(range(16))
+assert mismatches[0].differing == {parties[1]: None}
+assert mismatches[1].majority == bytes(masked[parties[0]]["p"].value)
+assert mismatches[1].differing == {parties[2]: bytes(other[parties[2]]["p"].value)}
+
+# With two parties that disagree there's no majority, both are listed.
+mismatches = build_jar(tampered, [parties[0], parties[2]]).verify_consistency()
+assert [mismatch.key for mismatch in mismatches] == ["p"], mismatches
+assert mismatches[0].majority is None
+assert list(mismatches[0].differing) == [parties[0], parties[2]]
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_reshare() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+old_parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+new_parties = [PartyId.from_bytes(bytes([i])) for i in range(10, 15)]
+old = SecretMasker.new_64_bit_safe_prime(1, old_parties)
+new = SecretMasker.new_128_bit_safe_prime(2, new_parties)
+
+values = {
+    "a": SecretInteger(-5),
+    "b": Integer(9),
+    "c": SecretBlob(bytearray(b"reshared")),
+    "d": EcdsaPrivateKey(bytearray(range(1, 33))),
+    "e": EddsaPrivateKey(bytearray(range(1, 33))),
+}
+masked = old.mask(values)
+jar = old.build_jar()
+for party in old_parties[:2]:
+    jar.add_element(party, masked[party])
+
+reshared = old.reshare(jar, new)
+assert set(reshared) == set(new_parties)
+jar = new.build_jar()
+for party in new_parties[1:4]:
+    jar.add_element(party, reshared[party])
+assert new.unmask(jar) == values
+
+# Integers must fit in the target's prime.
+masked = new.mask({"big": SecretInteger(2**100)})
+jar = new.build_jar()
+for party in new_parties:
+    jar.add_element(party, masked[party])
+try:
+    new.reshare(jar, old)
+    raise AssertionError("Expected ValueError not raised")
+except ValueError as e:
+    assert str(e) == "value 'big' can't be represented with the target masker's 64 bit prime", str(e)
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
+
+#[test]
+fn test_masker_introspection() {
+    Python::with_gil(|py| {
+        Python::run_bound(
+            py,
+            r#"
+from nillion_client_core import *
+
+parties = [PartyId.from_bytes(bytes([i])) for i in range(4)]
+for prime, bits in [("SAFE_64_BITS", 64), ("SAFE_128_BITS", 128), ("SAFE_256_BITS", 256)]:
+    masker = SecretMasker.from_prime(prime, 1, parties)
+    assert masker.parties == parties
+    assert masker.party_count == 4
+    assert masker.polynomial_degree == 1
+    assert masker.prime_bits == bits
+
+masker = SecretMasker.new_128_bit_safe_prime(2, parties[:3])
+assert masker.prime_bits == 128
+assert masker.party_count == 3
+
+seeded = [SecretMasker.from_prime("SAFE_64_BITS", 1, parties, seed=b"seed") for _ in range(2)]
+shares = [masker.mask({"a": SecretInteger(1)}) for masker in seeded]
+assert shares[0][parties[0]]["a"].to_protobuf_bytes() == shares[1][parties[0]]["a"].to_protobuf_bytes()
+
+try:
+    SecretMasker.from_prime("SAFE_512_BITS", 1, parties)
+    raise AssertionError("Expected ValueError not raised")
+except ValueError as e:
+    assert str(e) == "unknown prime 'SAFE_512_BITS', expected one of SAFE_64_BITS, SAFE_128_BITS or SAFE_256_BITS", str(e)
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    })
+}
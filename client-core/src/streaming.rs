@@ -0,0 +1,397 @@
+//! Streaming masking and unmasking of secret blobs.
+//!
+//! Blobs are read in chunks and every chunk is masked as a secret blob of its own, so memory usage is bounded by the
+//! chunk size rather than the size of the blob. Each party's shares are written to its own share stream:
+//!
+//! * The magic bytes `NILC` followed by a version byte and the size in bits of the safe prime.
+//! * For every chunk, its size, its share count and its shares (length prefixed).
+//! * A chunk size of zero followed by the total size of the blob.
+//!
+//! Numbers are unsigned LEB128 varints.
+
+use crate::{
+    encrypted_value::EncodingBounds,
+    ranges::prime_bits,
+    values::{
+        encoding::{write_bytes, write_varint},
+        secret::wipe,
+    },
+    PartyId,
+};
+use nillion_client_core::values::{BlobPrimitiveType, EncodedModularNumber, EncodedModulo, NadaValue, SecretMasker};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    pybacked::PyBackedBytes,
+    types::PyBytes,
+};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Write},
+    path::PathBuf,
+};
+use zeroize::Zeroizing;
+
+/// The magic bytes that start every share stream.
+const MAGIC: &[u8; 4] = b"NILC";
+
+/// The current version of the share stream format.
+const VERSION: u8 = 1;
+
+/// The default size of the chunks a blob is split into.
+pub(crate) const DEFAULT_CHUNK_SIZE: usize = 1 << 20;
+
+/// The largest chunk size, which bounds the memory used when unmasking a stream.
+const MAX_CHUNK_SIZE: usize = 64 << 20;
+
+/// The largest encoded share, which is the size of a number under the 256 bit prime.
+const MAX_SHARE_LEN: usize = 32;
+
+/// The name a chunk is masked under.
+const CHUNK: &str = "chunk";
+
+/// A python binary file-like object, read through its `read` method.
+struct PyReader(PyObject);
+
+impl Read for PyReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Python::with_gil(|py| {
+            let data: PyBackedBytes = self.0.call_method1(py, "read", (buf.len(),))?.extract(py)?;
+            if data.len() > buf.len() {
+                return Err(io::Error::new(ErrorKind::InvalidData, "read returned more bytes than requested"));
+            }
+            buf[..data.len()].copy_from_slice(&data);
+            Ok(data.len())
+        })
+    }
+}
+
+/// A python object that chunks are written to, either a binary file-like object or a callback.
+enum PyWriter {
+    File(PyObject),
+    Callback(PyObject),
+}
+
+impl Write for PyWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Python::with_gil(|py| {
+            let bytes = PyBytes::new_bound(py, buf);
+            match self {
+                // Raw files may write less than they're given and say so, buffered ones return the full length.
+                Self::File(file) => {
+                    Ok(file.call_method1(py, "write", (bytes,))?.extract::<Option<usize>>(py)?.unwrap_or(buf.len()))
+                }
+                Self::Callback(callback) => {
+                    callback.call1(py, (bytes,))?;
+                    Ok(buf.len())
+                }
+            }
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Python::with_gil(|py| {
+            if let Self::File(file) = self {
+                if file.bind(py).hasattr("flush")? {
+                    file.call_method0(py, "flush")?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Open a path or a binary file-like object for reading.
+fn open_source(source: &Bound<'_, PyAny>) -> PyResult<BufReader<Box<dyn Read>>> {
+    let reader: Box<dyn Read> = if let Ok(path) = source.extract::<PathBuf>() {
+        Box::new(File::open(path)?)
+    } else if source.hasattr("read")? {
+        Box::new(PyReader(source.clone().unbind()))
+    } else {
+        return Err(PyTypeError::new_err("expected a path or a binary file-like object"));
+    };
+    Ok(BufReader::new(reader))
+}
+
+/// Open a path, a binary file-like object or a callback for writing.
+fn open_sink(sink: &Bound<'_, PyAny>) -> PyResult<BufWriter<Box<dyn Write>>> {
+    let writer: Box<dyn Write> = if let Ok(path) = sink.extract::<PathBuf>() {
+        Box::new(File::create(path)?)
+    } else if sink.hasattr("write")? {
+        Box::new(PyWriter::File(sink.clone().unbind()))
+    } else if sink.is_callable() {
+        Box::new(PyWriter::Callback(sink.clone().unbind()))
+    } else {
+        return Err(PyTypeError::new_err("expected a path, a binary file-like object or a callable"));
+    };
+    Ok(BufWriter::new(writer))
+}
+
+/// Mask a blob read from a source, writing each party's shares to its sink.
+///
+/// There must be a sink for every one of the masker's parties and no other, which is checked before any sink is
+/// opened. Returns the size of the blob.
+pub(crate) fn mask_blob(
+    masker: &SecretMasker,
+    modulo: EncodedModulo,
+    parties: &[PartyId],
+    source: &Bound<'_, PyAny>,
+    sinks: HashMap<PartyId, Bound<'_, PyAny>>,
+    chunk_size: usize,
+) -> PyResult<u64> {
+    if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+        return Err(PyValueError::new_err(format!("chunk size must be between 1 and {MAX_CHUNK_SIZE} bytes")));
+    }
+    if let Some(party) = sinks.keys().find(|party| !parties.contains(party)) {
+        return Err(PyValueError::new_err(format!("party {} is not one of the masker's parties", party.inner)));
+    }
+    let missing = parties.iter().filter(|party| !sinks.contains_key(party)).collect::<Vec<_>>();
+    if !missing.is_empty() {
+        let missing = missing.iter().map(|party| party.inner.to_string()).collect::<Vec<_>>();
+        return Err(PyValueError::new_err(format!("missing sinks for parties {}", missing.join(", "))));
+    }
+    let mut source = open_source(source)?;
+    let mut sinks = sinks
+        .into_iter()
+        .map(|(party, sink)| {
+            let mut sink = open_sink(&sink)?;
+            let mut header = MAGIC.to_vec();
+            header.push(VERSION);
+            write_varint(&mut header, prime_bits(modulo).into());
+            sink.write_all(&header)?;
+            Ok((party.inner, sink))
+        })
+        .collect::<PyResult<HashMap<_, _>>>()?;
+
+    let mut total: u64 = 0;
+    let mut chunk = Zeroizing::new(vec![0; chunk_size]);
+    loop {
+        let size = fill(&mut source, &mut chunk)?;
+        if size == 0 {
+            break;
+        }
+        total += size as u64;
+        // The masker takes ownership of this copy and drops it without wiping it, like every value it masks, so it
+        // can't be kept zeroized. The chunk buffer it's copied from is wiped when it's dropped.
+        let values = std::iter::once((CHUNK.to_string(), NadaValue::new_secret_blob(chunk[..size].to_vec()))).collect();
+        let masked = masker.mask(values).map_err(|err| PyValueError::new_err(format!("masking failed: {}", err)))?;
+        for (party, values) in masked {
+            let sink =
+                sinks.get_mut(&party).ok_or_else(|| PyValueError::new_err(format!("no sink for party {party}")))?;
+            for (_, value) in values {
+                let NadaValue::SecretBlob(blob) = value else {
+                    return Err(PyValueError::new_err("masking a blob didn't produce blob shares"));
+                };
+                let mut record = Zeroizing::new(Vec::new());
+                write_varint(&mut record, size as u64);
+                write_varint(&mut record, blob.value.len() as u64);
+                for share in &blob.value {
+                    write_bytes(&mut record, share.as_bytes());
+                }
+                sink.write_all(&record)?;
+            }
+        }
+    }
+    for sink in sinks.values_mut() {
+        let mut trailer = vec![0];
+        write_varint(&mut trailer, total);
+        sink.write_all(&trailer)?;
+        sink.flush()?;
+    }
+    Ok(total)
+}
+
+/// Unmask a blob from every party's share stream, writing it to a sink.
+///
+/// Returns the size of the blob.
+pub(crate) fn unmask_blob(
+    py: Python<'_>,
+    masker: &SecretMasker,
+    modulo: EncodedModulo,
+    party_count: usize,
+    sources: HashMap<PartyId, Bound<'_, PyAny>>,
+    sink: &Bound<'_, PyAny>,
+) -> PyResult<u64> {
+    if sources.len() != party_count {
+        return Err(PyValueError::new_err(format!(
+            "expected share streams for {party_count} parties, got {}",
+            sources.len()
+        )));
+    }
+    let mut streams = sources
+        .into_iter()
+        .map(|(party, source)| {
+            let mut stream = ShareStream { party, reader: open_source(&source)? };
+            stream.read_header(py, modulo)?;
+            Ok(stream)
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    let mut sink = open_sink(sink)?;
+    let bounds = EncodingBounds::new(modulo);
+
+    let mut total: u64 = 0;
+    loop {
+        let sizes = streams.iter_mut().map(|s| s.read_varint(py)).collect::<PyResult<Vec<_>>>()?;
+        let size = check_agreement(&streams, &sizes, "chunk size")?;
+        if size == 0 {
+            let totals = streams.iter_mut().map(|s| s.read_varint(py)).collect::<PyResult<Vec<_>>>()?;
+            if check_agreement(&streams, &totals, "blob size")? != total {
+                return Err(PyValueError::new_err(format!("streams hold {total} bytes but declare {}", totals[0])));
+            }
+            break;
+        }
+        if size > MAX_CHUNK_SIZE as u64 {
+            return Err(PyValueError::new_err(format!("chunk of {size} bytes is larger than {MAX_CHUNK_SIZE} bytes")));
+        }
+        let mut jar = nillion_client_core::values::PartyJar::new(party_count);
+        for stream in &mut streams {
+            let shares = stream.read_shares(py, &bounds, size, modulo)?;
+            let blob = BlobPrimitiveType { value: shares, unencoded_size: size };
+            let values = std::iter::once((CHUNK.to_string(), NadaValue::new_secret_blob(blob))).collect();
+            jar.add_element(stream.party.inner.clone(), values)
+                .map_err(|err| PyValueError::new_err(format!("adding element into party jar failed: {}", err)))?;
+        }
+        let unmasked = masker.unmask(jar).map_err(|err| PyValueError::new_err(format!("unmasking failed: {}", err)))?;
+        for (_, mut value) in unmasked {
+            let chunk = value.as_secret_blob().ok_or_else(|| PyValueError::new_err("expected secret blob"))?;
+            if chunk.len() as u64 != size {
+                return Err(PyValueError::new_err(format!(
+                    "chunk unmasked into {} bytes, expected {size}",
+                    chunk.len()
+                )));
+            }
+            sink.write_all(chunk)?;
+            wipe(&mut value);
+        }
+        total += size;
+    }
+    for stream in &mut streams {
+        stream.finish(py)?;
+    }
+    sink.flush()?;
+    Ok(total)
+}
+
+/// Check that every party's stream agrees on a number.
+fn check_agreement(streams: &[ShareStream], values: &[u64], what: &str) -> PyResult<u64> {
+    let expected = values[0];
+    for (stream, value) in streams.iter().zip(values) {
+        if *value != expected {
+            return Err(PyValueError::new_err(format!(
+                "party {}: {what} {value} doesn't match {what} {expected} of party {}",
+                stream.party.inner, streams[0].party.inner
+            )));
+        }
+    }
+    Ok(expected)
+}
+
+/// Read from a source until a buffer is full or the source is exhausted, returning the number of bytes read.
+fn fill(source: &mut impl Read, buf: &mut [u8]) -> PyResult<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match source.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(filled)
+}
+
+/// A party's share stream.
+struct ShareStream {
+    party: PartyId,
+    reader: BufReader<Box<dyn Read>>,
+}
+
+impl ShareStream {
+    fn error(&self, py: Python<'_>, err: PyErr) -> PyErr {
+        PyValueError::new_err(format!("party {}: {}", self.party.inner, err.value_bound(py)))
+    }
+
+    fn read_exact(&mut self, py: Python<'_>, buf: &mut [u8]) -> PyResult<()> {
+        self.reader.read_exact(buf).map_err(|e| match e.kind() {
+            ErrorKind::UnexpectedEof => self.error(py, PyValueError::new_err("unexpected end of share stream")),
+            _ => e.into(),
+        })
+    }
+
+    fn read_u8(&mut self, py: Python<'_>) -> PyResult<u8> {
+        let mut byte = [0];
+        self.read_exact(py, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn read_varint(&mut self, py: Python<'_>) -> PyResult<u64> {
+        let mut value: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_u8(py)?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(self.error(py, PyValueError::new_err("varint is too long")))
+    }
+
+    fn read_header(&mut self, py: Python<'_>, modulo: EncodedModulo) -> PyResult<()> {
+        let mut magic = [0; MAGIC.len()];
+        self.read_exact(py, &mut magic)?;
+        if &magic != MAGIC {
+            return Err(self.error(py, PyValueError::new_err("not a share stream")));
+        }
+        match self.read_u8(py)? {
+            VERSION => (),
+            version => {
+                let err = PyValueError::new_err(format!("unsupported share stream version: {version}"));
+                return Err(self.error(py, err));
+            }
+        };
+        let bits = self.read_varint(py)?;
+        if bits != u64::from(prime_bits(modulo)) {
+            let err = PyValueError::new_err(format!(
+                "stream uses a {bits} bit prime but the masker uses a {} bit prime",
+                prime_bits(modulo)
+            ));
+            return Err(self.error(py, err));
+        }
+        Ok(())
+    }
+
+    fn read_shares(
+        &mut self,
+        py: Python<'_>,
+        bounds: &EncodingBounds,
+        size: u64,
+        modulo: EncodedModulo,
+    ) -> PyResult<Vec<EncodedModularNumber>> {
+        let count = self.read_varint(py)?;
+        // Every share holds at least one byte of the chunk.
+        if count > size {
+            return Err(self.error(py, PyValueError::new_err(format!("{count} shares for a {size} byte chunk"))));
+        }
+        let mut shares = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let len = self.read_varint(py)?;
+            if len > MAX_SHARE_LEN as u64 {
+                return Err(self.error(py, PyValueError::new_err(format!("share of {len} bytes is too long"))));
+            }
+            let mut share = Zeroizing::new(vec![0; len as usize]);
+            self.read_exact(py, &mut share)?;
+            bounds.check(&share).map_err(|e| self.error(py, PyValueError::new_err(e)))?;
+            shares.push(EncodedModularNumber::new_unchecked(share.to_vec(), modulo));
+        }
+        Ok(shares)
+    }
+
+    fn finish(&mut self, py: Python<'_>) -> PyResult<()> {
+        let mut byte = [0];
+        match self.reader.read(&mut byte)? {
+            0 => Ok(()),
+            _ => Err(self.error(py, PyValueError::new_err("trailing bytes after share stream"))),
+        }
+    }
+}
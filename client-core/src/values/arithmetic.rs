@@ -0,0 +1,116 @@
+//! Local arithmetic on cleartext integer values.
+//!
+//! Integer values can choose the safe prime their arithmetic is range-checked against with the `prime_bits` argument.
+//! A result is checked against the smallest prime chosen by its operands, or against the largest supported prime,
+//! the 256-bit safe prime, if none of them chose one. Results that don't fit the prime of the masker they're masked
+//! with are caught by the masker, see `SecretMasker.validate_ranges`.
+
+use crate::ranges::{modulo_from_prime_bits, prime_bits, signed_range, unsigned_range};
+use nillion_client_core::values::{BigInt, EncodedModulo};
+use pyo3::{
+    exceptions::{PyOverflowError, PyZeroDivisionError},
+    prelude::*,
+    pyclass::CompareOp,
+    types::{PyBool, PyInt},
+};
+use std::{
+    hash::{BuildHasher, Hash, RandomState},
+    sync::OnceLock,
+};
+
+/// The modulo that arithmetic results are checked against when none of the operands chose one.
+pub(crate) const ARITHMETIC_MODULO: EncodedModulo = EncodedModulo::U256SafePrime;
+
+/// The modulo chosen by a value's optional `prime_bits` argument.
+pub(crate) fn chosen_modulo(prime_bits: Option<u32>) -> PyResult<Option<EncodedModulo>> {
+    prime_bits.map(modulo_from_prime_bits).transpose()
+}
+
+/// The modulo a result is checked against: the smallest one chosen by its operands.
+pub(crate) fn narrowest(left: Option<EncodedModulo>, right: Option<EncodedModulo>) -> Option<EncodedModulo> {
+    match (left, right) {
+        (Some(left), Some(right)) => Some(if prime_bits(left) <= prime_bits(right) { left } else { right }),
+        (left, right) => left.or(right),
+    }
+}
+
+/// A binary arithmetic operation.
+#[derive(Clone, Copy)]
+pub(crate) enum Operation {
+    Add,
+    Sub,
+    Mul,
+    FloorDiv,
+    Mod,
+}
+
+/// Whether a result must fit in the signed or the unsigned range of the arithmetic modulo.
+#[derive(Clone, Copy)]
+pub(crate) enum Signedness {
+    Signed,
+    Unsigned,
+}
+
+/// Apply an operation using python semantics: division rounds towards negative infinity and the remainder takes
+/// the sign of the divisor.
+pub(crate) fn apply(operation: Operation, left: &BigInt, right: &BigInt) -> PyResult<BigInt> {
+    let zero = BigInt::default();
+    let result = match operation {
+        Operation::Add => left + right,
+        Operation::Sub => left - right,
+        Operation::Mul => left * right,
+        Operation::FloorDiv | Operation::Mod => {
+            if *right == zero {
+                return Err(PyZeroDivisionError::new_err("integer division or modulo by zero"));
+            }
+            let mut quotient = left / right;
+            let mut remainder = left % right;
+            if remainder != zero && (remainder < zero) != (*right < zero) {
+                quotient -= 1;
+                remainder += right;
+            }
+            match operation {
+                Operation::FloorDiv => quotient,
+                _ => remainder,
+            }
+        }
+    };
+    Ok(result)
+}
+
+/// Check that a value can be represented under the given modulo, or under the arithmetic modulo if there's none.
+pub(crate) fn checked(value: BigInt, signedness: Signedness, modulo: Option<EncodedModulo>) -> PyResult<BigInt> {
+    let modulo = modulo.unwrap_or(ARITHMETIC_MODULO);
+    let (min, max) = match signedness {
+        Signedness::Signed => signed_range(modulo),
+        Signedness::Unsigned => unsigned_range(modulo),
+    };
+    if value < min || value > max {
+        return Err(PyOverflowError::new_err(format!(
+            "{value} is outside of the range [{min}, {max}] of the {} bit safe prime",
+            prime_bits(modulo)
+        )));
+    }
+    Ok(value)
+}
+
+/// Extract a plain python integer operand. Booleans are not accepted as integers.
+pub(crate) fn extract_int(value: &Bound<'_, PyAny>) -> Option<BigInt> {
+    if value.is_instance_of::<PyBool>() || !value.is_instance_of::<PyInt>() {
+        return None;
+    }
+    value.extract().ok()
+}
+
+/// Hash a value along with the name of its class, so equal values of different classes hash differently.
+///
+/// The hasher is keyed randomly once per process, so a secret's hash can't be looked up in a precomputed table.
+pub(crate) fn hash_value(class_name: &str, value: &impl Hash) -> u64 {
+    static STATE: OnceLock<RandomState> = OnceLock::new();
+    STATE.get_or_init(RandomState::new).hash_one((class_name, value))
+}
+
+/// Evaluate a rich comparison between two ordered values.
+pub(crate) fn compare<T: Ord>(left: &T, right: &T, op: CompareOp) -> bool {
+    op.matches(left.cmp(right))
+}
@@ -1,19 +1,31 @@
 //! Python bindings for array and utilities.
 
-use crate::values::{nada_value_clear_to_pyobject, pyany_to_nada_value_clear};
+use crate::values::{
+    buffer::{values_to_numpy, NumericBuffer},
+    nada_type::NadaType,
+    nada_value_clear_to_pyobject, pyany_to_nada_value_clear, reduce_nada_value,
+    secret::{redacted_repr, wipe},
+};
 use nillion_client_core::values::{Clear, NadaValue};
-use pyo3::{exceptions::PyValueError, pyclass, pymethods, PyErr, PyObject, PyResult, Python};
+use pyo3::{
+    exceptions::{PyIndexError, PyValueError},
+    prelude::*,
+    types::{PyBytes, PySlice},
+};
 
 /// This is a :py:class:`Array` class used to
 /// encode a secret array of elements.
 ///
-/// Note: `__len__` method is implemented to allow
-/// getting the length of the array.
+/// Note: the sequence protocol (`__len__`, `__getitem__`,
+/// `__setitem__`, `__iter__`) is implemented, including slicing.
 ///
 /// Arguments
 /// ---------
 /// value : list
 ///     List of secret encoded elements.
+/// inner_type : NadaType, optional
+///     The type of the elements. Required to build an empty array; when
+///     provided every element is checked against it.
 ///
 /// Returns
 /// -------
@@ -25,6 +37,9 @@ use pyo3::{exceptions::PyValueError, pyclass, pymethods, PyErr, PyObject, PyResu
 /// ValueError: invalid secret type
 ///     Raises an error when a public encoded element is included inside a
 ///     secret array.
+/// ValueError: element type mismatch
+///     Raises an error when an element's type differs from the array's
+///     element type, including nested arrays of different sizes.
 ///
 /// Example
 /// -------
@@ -37,6 +52,9 @@ use pyo3::{exceptions::PyValueError, pyclass, pymethods, PyErr, PyObject, PyResu
 ///
 ///     print("The length of the array is: ", len(secret_array))
 ///
+///     empty_array = Array([], NadaType.secret_integer())
+///     empty_array.append(SecretInteger(3))
+///
 /// .. code-block:: text
 ///
 ///     >>> The length of the array is: 2
@@ -54,11 +72,71 @@ impl TryFrom<NadaValue<Clear>> for Array {
     }
 }
 
+impl Drop for Array {
+    fn drop(&mut self) {
+        wipe(&mut self.inner);
+    }
+}
+
+impl Array {
+    fn new_typed(inner_type: nillion_client_core::values::NadaType, values: Vec<NadaValue<Clear>>) -> PyResult<Self> {
+        for (index, value) in values.iter().enumerate() {
+            check_element_type(&inner_type, value, index)?;
+        }
+        Ok(Array { inner: NadaValue::new_array(inner_type, values).map_err(|e| PyValueError::new_err(e.to_string()))? })
+    }
+
+    fn values(&self) -> &Vec<NadaValue<Clear>> {
+        self.inner.as_array().unwrap().1
+    }
+
+    fn values_mut(&mut self) -> &mut Vec<NadaValue<Clear>> {
+        match &mut self.inner {
+            NadaValue::Array { values, .. } => values,
+            _ => unreachable!("array always holds an array value"),
+        }
+    }
+
+    fn element_type(&self) -> &nillion_client_core::values::NadaType {
+        self.inner.as_array().unwrap().0
+    }
+
+    fn normalize_index(&self, index: isize) -> PyResult<usize> {
+        let len = self.values().len() as isize;
+        let normalized = if index < 0 { index + len } else { index };
+        if normalized < 0 || normalized >= len {
+            return Err(PyIndexError::new_err("array index out of range"));
+        }
+        Ok(normalized as usize)
+    }
+
+    fn convert_element(&self, value: Bound<'_, PyAny>, index: usize) -> PyResult<NadaValue<Clear>> {
+        let value = pyany_to_nada_value_clear(value)?;
+        check_element_type(self.element_type(), &value, index)?;
+        Ok(value)
+    }
+}
+
+fn check_element_type(
+    inner_type: &nillion_client_core::values::NadaType,
+    value: &NadaValue<Clear>,
+    index: usize,
+) -> PyResult<()> {
+    let value_type = value.to_type();
+    if &value_type != inner_type {
+        return Err(PyValueError::new_err(format!(
+            "element {index} has type {value_type} but array elements are {inner_type}"
+        )));
+    }
+    Ok(())
+}
+
 #[pymethods]
 impl Array {
     /// Returns a new [`Array`].
     #[new]
-    fn new(value: Vec<PyObject>, py: Python) -> PyResult<Self> {
+    #[pyo3(signature = (value, inner_type=None))]
+    fn new(value: Vec<PyObject>, inner_type: Option<NadaType>, py: Python) -> PyResult<Self> {
         let values = value
             .into_iter()
             .map(|e| {
@@ -67,13 +145,108 @@ impl Array {
                 Ok(e)
             })
             .collect::<Result<Vec<_>, PyErr>>()?;
-        let nada_type =
-            values.first().ok_or_else(|| PyValueError::new_err("Array must have at least one element"))?.to_type();
-        Ok(Array { inner: NadaValue::new_array(nada_type, values).map_err(|e| PyValueError::new_err(e.to_string()))? })
+        let nada_type = match inner_type {
+            Some(inner_type) => inner_type.inner,
+            None => values
+                .first()
+                .ok_or_else(|| PyValueError::new_err("Array must have at least one element or an explicit inner type"))?
+                .to_type(),
+        };
+        Self::new_typed(nada_type, values)
+    }
+
+    /// Build an array from a numpy array, or any object exposing the buffer protocol.
+    ///
+    /// The buffer must be one dimensional with int64, uint64 or bool elements. Elements are
+    /// converted without creating a Python object per element. This isn't zero-copy: the buffer's
+    /// contents are copied twice on the way, and both copies are wiped once converted.
+    ///
+    /// Arguments
+    /// ---------
+    /// data : numpy.ndarray
+    ///     The elements of the array.
+    /// secret : bool
+    ///     Whether the elements are secret.
+    /// signed : bool
+    ///     Whether integer elements are encoded as signed or unsigned integers. Ignored for
+    ///     bool elements.
+    ///
+    /// Returns
+    /// -------
+    /// Array
+    ///     Instance of the :py:class:`Array` class.
+    ///
+    /// Example
+    /// -------
+    ///
+    /// .. code-block:: py3
+    ///
+    ///     import numpy as np
+    ///     from nillion_client import Array
+    ///
+    ///     secret_array = Array.from_numpy(np.arange(100_000, dtype=np.int64))
+    #[staticmethod]
+    #[pyo3(signature = (data, secret=true, signed=true))]
+    fn from_numpy(data: &Bound<'_, PyAny>, secret: bool, signed: bool) -> PyResult<Self> {
+        Ok(Array { inner: NumericBuffer::read(data)?.into_array(secret, signed)? })
+    }
+
+    /// Convert this array into a numpy array.
+    ///
+    /// Integers become int64 elements, unsigned integers uint64 elements and booleans bool
+    /// elements. Arrays holding any other type of element can't be converted.
+    fn to_numpy(&self, py: Python<'_>) -> PyResult<PyObject> {
+        values_to_numpy(py, self.element_type(), self.values())
+    }
+
+    /// The type of the elements in this array.
+    #[getter]
+    fn get_inner_type(&self) -> NadaType {
+        self.element_type().clone().into()
     }
 
     fn __len__(&self) -> PyResult<usize> {
-        Ok(self.inner.as_array().unwrap().1.len())
+        Ok(self.values().len())
+    }
+
+    fn __getitem__(&self, py: Python<'_>, index: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        if let Ok(slice) = index.downcast::<PySlice>() {
+            let indices = slice.indices(self.values().len().try_into()?)?;
+            let mut values = Vec::with_capacity(indices.slicelength as usize);
+            let mut position = indices.start;
+            for _ in 0..indices.slicelength {
+                values.push(self.values()[position as usize].clone());
+                position += indices.step;
+            }
+            return Ok(Self::new_typed(self.element_type().clone(), values)?.into_py(py));
+        }
+        let index = self.normalize_index(index.extract::<isize>()?)?;
+        nada_value_clear_to_pyobject(py, self.values()[index].clone())
+    }
+
+    fn __setitem__(&mut self, index: isize, value: Bound<'_, PyAny>) -> PyResult<()> {
+        let index = self.normalize_index(index)?;
+        let value = self.convert_element(value, index)?;
+        let element = &mut self.values_mut()[index];
+        wipe(element);
+        *element = value;
+        Ok(())
+    }
+
+    fn __iter__(&self) -> ArrayIterator {
+        ArrayIterator { values: self.values().clone().into_iter() }
+    }
+
+    /// Appends an element at the end of this array.
+    ///
+    /// Arguments
+    /// ---------
+    /// value : NadaValue
+    ///     The element to append. Its type must match the array's element type.
+    fn append(&mut self, value: Bound<'_, PyAny>) -> PyResult<()> {
+        let value = self.convert_element(value, self.values().len())?;
+        self.values_mut().push(value);
+        Ok(())
     }
 
     /// Getter method for the `value` inside a
@@ -97,23 +270,43 @@ impl Array {
     ///     >>>  [SecretInteger(1), SecretInteger(2)]
     #[getter]
     fn get_value(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
-        self.inner
-            .as_array()
-            .unwrap()
-            .1
-            .iter()
-            .cloned()
-            .map(|v| nada_value_clear_to_pyobject(py, v))
-            .collect::<Result<Vec<_>, _>>()
+        self.values().iter().cloned().map(|v| nada_value_clear_to_pyobject(py, v)).collect::<Result<Vec<_>, _>>()
     }
 
     fn __str__(&self) -> String {
-        let str_values =
-            self.inner.as_array().unwrap().1.iter().cloned().map(|v| v.to_string()).collect::<Vec<_>>().join(", ");
+        let str_values = self.values().iter().map(redacted_repr).collect::<Vec<_>>().join(", ");
         format!("[{str_values}]")
     }
 
     fn __repr__(&self) -> String {
+        redacted_repr(&self.inner)
+    }
+
+    /// Returns the representation of this array including its secrets.
+    ///
+    /// The default representation redacts secrets so that they don't end up in logs or tracebacks.
+    fn reveal(&self) -> String {
         self.inner.to_string()
     }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+        reduce_nada_value(py, &self.inner)
+    }
+}
+
+/// An iterator over the elements of an :py:class:`Array`.
+#[pyclass]
+pub struct ArrayIterator {
+    values: std::vec::IntoIter<NadaValue<Clear>>,
+}
+
+#[pymethods]
+impl ArrayIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        self.values.next().map(|v| nada_value_clear_to_pyobject(py, v)).transpose()
+    }
 }
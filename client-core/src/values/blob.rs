@@ -1,5 +1,14 @@
+use crate::values::{
+    envelope, reduce_nada_value,
+    secret::{redacted_repr, wipe},
+};
 use nillion_client_core::values::{Clear, NadaValue};
-use pyo3::{exceptions::PyValueError, prelude::*, types::PyByteArray};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyByteArray, PyBytes},
+};
+use zeroize::Zeroizing;
 
 /// This is a :py:class:`SecretBlob` class used to
 /// encode a secret as a blob.
@@ -53,6 +62,12 @@ impl TryFrom<NadaValue<Clear>> for SecretBlob {
     }
 }
 
+impl Drop for SecretBlob {
+    fn drop(&mut self) {
+        wipe(&mut self.inner);
+    }
+}
+
 #[pymethods]
 impl SecretBlob {
     /// Returns a new SecretBlob.
@@ -61,6 +76,32 @@ impl SecretBlob {
         SecretBlob { inner: NadaValue::new_secret_blob(value.to_vec()) }
     }
 
+    /// Returns a new SecretBlob that wraps `value` in an integrity envelope.
+    ///
+    /// The envelope records the SHA-256 digest of `value` and, if `compress` is set, compresses it with zstd
+    /// before it is masked. Unmasking opens the envelope transparently, decompressing the blob and raising
+    /// :py:class:`BlobIntegrityError` if it doesn't match its digest.
+    ///
+    /// Arguments
+    /// ---------
+    /// value : bytearray
+    ///     The bytes to store.
+    /// compress : bool, optional
+    ///     Whether to compress the bytes, defaults to False.
+    ///
+    /// Example
+    /// -------
+    ///
+    /// .. code-block:: py3
+    ///
+    ///     blob = SecretBlob.with_digest(bytearray(b"gm, builder!" * 1000), compress=True)
+    #[staticmethod]
+    #[pyo3(signature = (value, compress=false))]
+    fn with_digest(value: &Bound<'_, PyByteArray>, compress: bool) -> PyResult<SecretBlob> {
+        let envelope = envelope::seal(&Zeroizing::new(value.to_vec()), compress)?;
+        Ok(SecretBlob { inner: NadaValue::new_secret_blob(envelope.to_vec()) })
+    }
+
     /// Getter and setter for the `value` inside a
     /// :py:class:`SecretBlob` instance.
     ///
@@ -96,10 +137,22 @@ impl SecretBlob {
 
     #[setter]
     fn set_value(&mut self, value: &Bound<'_, PyByteArray>) {
+        wipe(&mut self.inner);
         self.inner = NadaValue::new_secret_blob(value.to_vec());
     }
 
     fn __repr__(&self) -> String {
+        redacted_repr(&self.inner)
+    }
+
+    /// Returns the representation of this secret blob including its secrets.
+    ///
+    /// The default representation redacts secrets so that they don't end up in logs or tracebacks.
+    fn reveal(&self) -> String {
         self.inner.to_string()
     }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+        reduce_nada_value(py, &self.inner)
+    }
 }
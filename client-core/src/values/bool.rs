@@ -1,5 +1,15 @@
+use crate::values::{
+    arithmetic::{compare, hash_value},
+    reduce_nada_value,
+    secret::{redacted_repr, wipe},
+};
 use nillion_client_core::values::{Clear, NadaValue};
-use pyo3::{exceptions::PyValueError, prelude::*};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    pyclass::CompareOp,
+    types::{PyBool, PyBytes},
+};
 
 /// This is a :py:class:`SecretBoolean` class used to
 /// encode a secret as a boolean.
@@ -31,7 +41,11 @@ use pyo3::{exceptions::PyValueError, prelude::*};
 ///
 ///     >>> Are the secret booleans the same?  False
 ///
-#[pyclass(eq)]
+/// Secret booleans are ordered and support ``&``, ``|`` and ``^`` with other secret
+/// booleans, public booleans and plain ``bool`` values. Results are secret booleans.
+///
+/// Values are immutable and hashable, so they can be used in sets and as dict keys.
+#[pyclass(frozen)]
 #[derive(PartialEq, Clone)]
 pub struct SecretBoolean {
     pub(crate) inner: NadaValue<Clear>,
@@ -48,6 +62,33 @@ impl TryFrom<NadaValue<Clear>> for SecretBoolean {
     }
 }
 
+impl Drop for SecretBoolean {
+    fn drop(&mut self) {
+        wipe(&mut self.inner);
+    }
+}
+
+impl SecretBoolean {
+    /// Extracts the value of an operand that can be combined with a secret boolean.
+    fn operand(other: &Bound<'_, PyAny>) -> Option<bool> {
+        if let Ok(other) = other.extract::<SecretBoolean>() {
+            return other.get_value().ok();
+        }
+        if let Ok(other) = other.extract::<Boolean>() {
+            return other.get_value().ok();
+        }
+        other.downcast::<PyBool>().ok().map(|other| other.is_true())
+    }
+
+    fn logical_op(&self, other: &Bound<'_, PyAny>, operation: fn(bool, bool) -> bool) -> PyResult<PyObject> {
+        let py = other.py();
+        let Some(other) = Self::operand(other) else {
+            return Ok(py.NotImplemented());
+        };
+        Ok(SecretBoolean::new(operation(self.get_value()?, other)).into_py(py))
+    }
+}
+
 #[pymethods]
 impl SecretBoolean {
     /// Returns a new SecretBoolean.
@@ -61,14 +102,69 @@ impl SecretBoolean {
         self.inner.as_secret_boolean().cloned().ok_or_else(|| PyValueError::new_err("expected secret boolean"))
     }
 
-    #[setter]
-    fn set_value(&mut self, value: bool) {
-        self.inner = NadaValue::new_secret_boolean(value);
+    fn __repr__(&self) -> String {
+        redacted_repr(&self.inner)
     }
 
-    fn __repr__(&self) -> String {
+    /// Returns the representation of this secret boolean including its secrets.
+    ///
+    /// The default representation redacts secrets so that they don't end up in logs or tracebacks.
+    fn reveal(&self) -> String {
         self.inner.to_string()
     }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+        reduce_nada_value(py, &self.inner)
+    }
+
+    fn __and__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.logical_op(other, |left, right| left & right)
+    }
+
+    fn __rand__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.logical_op(other, |left, right| left & right)
+    }
+
+    fn __or__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.logical_op(other, |left, right| left | right)
+    }
+
+    fn __ror__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.logical_op(other, |left, right| left | right)
+    }
+
+    fn __xor__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.logical_op(other, |left, right| left ^ right)
+    }
+
+    fn __rxor__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.logical_op(other, |left, right| left ^ right)
+    }
+
+    /// Secret booleans refuse truth testing, so a secret isn't branched on by accident.
+    fn __bool__(&self) -> PyResult<bool> {
+        Err(PyTypeError::new_err("secret booleans can't be used as truth values, use `.value` instead"))
+    }
+
+    fn __int__(&self) -> PyResult<u8> {
+        Ok(self.get_value()?.into())
+    }
+
+    fn __index__(&self) -> PyResult<u8> {
+        Ok(self.get_value()?.into())
+    }
+
+    fn __hash__(&self) -> PyResult<u64> {
+        Ok(hash_value("SecretBoolean", &self.get_value()?))
+    }
+
+    fn __richcmp__(&self, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<PyObject> {
+        let py = other.py();
+        let Ok(other) = other.extract::<SecretBoolean>() else {
+            return Ok(py.NotImplemented());
+        };
+        Ok(compare(&self.get_value()?, &other.get_value()?, op).into_py(py))
+    }
 }
 
 /// This is a :py:class:`Boolean` class used to encode a public variable value as an boolean.
@@ -99,7 +195,13 @@ impl SecretBoolean {
 /// .. code-block:: text
 ///
 ///     >>> Are the public booleans the same?  False
-#[pyclass(eq)]
+///
+/// Public booleans are ordered and support ``&``, ``|`` and ``^`` with other public
+/// booleans and plain ``bool`` values. Combining a public boolean with a secret boolean results
+/// in a secret boolean.
+///
+/// Values are immutable and hashable, so they can be used in sets and as dict keys.
+#[pyclass(frozen)]
 #[derive(PartialEq, Clone)]
 pub struct Boolean {
     pub(crate) inner: NadaValue<Clear>,
@@ -113,6 +215,24 @@ impl TryFrom<NadaValue<Clear>> for Boolean {
     }
 }
 
+impl Boolean {
+    /// Extracts the value of an operand that can be combined with a public boolean.
+    fn operand(other: &Bound<'_, PyAny>) -> Option<bool> {
+        if let Ok(other) = other.extract::<Boolean>() {
+            return other.get_value().ok();
+        }
+        other.downcast::<PyBool>().ok().map(|other| other.is_true())
+    }
+
+    fn logical_op(&self, other: &Bound<'_, PyAny>, operation: fn(bool, bool) -> bool) -> PyResult<PyObject> {
+        let py = other.py();
+        let Some(other) = Self::operand(other) else {
+            return Ok(py.NotImplemented());
+        };
+        Ok(Boolean::new(operation(self.get_value()?, other)).into_py(py))
+    }
+}
+
 #[pymethods]
 impl Boolean {
     /// Returns a new Boolean.
@@ -126,12 +246,59 @@ impl Boolean {
         self.inner.as_boolean().cloned().ok_or_else(|| PyValueError::new_err("expected boolean"))
     }
 
-    #[setter]
-    fn set_value(&mut self, value: bool) {
-        self.inner = NadaValue::new_boolean(value);
-    }
-
     fn __repr__(&self) -> String {
         self.inner.to_string()
     }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+        reduce_nada_value(py, &self.inner)
+    }
+
+    fn __and__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.logical_op(other, |left, right| left & right)
+    }
+
+    fn __rand__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.logical_op(other, |left, right| left & right)
+    }
+
+    fn __or__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.logical_op(other, |left, right| left | right)
+    }
+
+    fn __ror__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.logical_op(other, |left, right| left | right)
+    }
+
+    fn __xor__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.logical_op(other, |left, right| left ^ right)
+    }
+
+    fn __rxor__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.logical_op(other, |left, right| left ^ right)
+    }
+
+    fn __bool__(&self) -> PyResult<bool> {
+        self.get_value()
+    }
+
+    fn __int__(&self) -> PyResult<u8> {
+        Ok(self.get_value()?.into())
+    }
+
+    fn __index__(&self) -> PyResult<u8> {
+        Ok(self.get_value()?.into())
+    }
+
+    fn __hash__(&self) -> PyResult<u64> {
+        Ok(hash_value("Boolean", &self.get_value()?))
+    }
+
+    fn __richcmp__(&self, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<PyObject> {
+        let py = other.py();
+        let Ok(other) = other.extract::<Boolean>() else {
+            return Ok(py.NotImplemented());
+        };
+        Ok(compare(&self.get_value()?, &other.get_value()?, op).into_py(py))
+    }
 }
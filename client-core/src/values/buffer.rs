@@ -0,0 +1,203 @@
+//! Conversion between arrays of primitive values and objects exposing the buffer protocol, e.g. numpy arrays.
+//!
+//! Buffers are read through `memoryview` so that any object exposing the buffer protocol is supported. This isn't
+//! zero-copy: their contents are copied into a `bytearray` and from there into native memory, without creating a
+//! Python object per element. Both copies are wiped, the `bytearray` as soon as it's been read and the native copy
+//! once converted.
+
+use nillion_client_core::values::{BigInt, BigUint, Clear, NadaType, NadaValue};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyByteArray, PyBytes, PySlice},
+};
+use zeroize::Zeroizing;
+
+/// The type of the elements in a buffer.
+#[derive(Clone, Copy, PartialEq)]
+enum ElementKind {
+    Int64,
+    UInt64,
+    Bool,
+}
+
+/// The contents of a one dimensional buffer of int64, uint64 or bool elements.
+pub(crate) struct NumericBuffer {
+    kind: ElementKind,
+    big_endian: bool,
+    bytes: Zeroizing<Vec<u8>>,
+}
+
+/// Whether a value exposes the buffer protocol and isn't a byte string.
+pub(crate) fn is_numeric_buffer(value: &Bound<'_, PyAny>) -> bool {
+    memoryview(value).is_ok()
+}
+
+fn memoryview<'py>(value: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    if value.is_instance_of::<PyBytes>() || value.is_instance_of::<PyByteArray>() {
+        return Err(PyTypeError::new_err("byte strings are not numeric buffers"));
+    }
+    let builtins = value.py().import_bound("builtins")?;
+    builtins
+        .getattr("memoryview")?
+        .call1((value,))
+        .map_err(|_| PyTypeError::new_err("object does not support the buffer protocol"))
+}
+
+impl NumericBuffer {
+    /// Read the contents of an object exposing the buffer protocol.
+    pub(crate) fn read(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let view = memoryview(value)?;
+        let ndim = view.getattr("ndim")?.extract::<usize>()?;
+        if ndim != 1 {
+            return Err(PyValueError::new_err(format!("expected a one dimensional buffer, got {ndim} dimensions")));
+        }
+        let format = view.getattr("format")?.extract::<String>()?;
+        let itemsize = view.getattr("itemsize")?.extract::<usize>()?;
+        let (big_endian, code) = match format.chars().next() {
+            Some('<') => (false, &format[1..]),
+            Some('>' | '!') => (true, &format[1..]),
+            Some('@' | '=') => (cfg!(target_endian = "big"), &format[1..]),
+            _ => (cfg!(target_endian = "big"), format.as_str()),
+        };
+        let kind = match (code, itemsize) {
+            ("q" | "l", 8) => ElementKind::Int64,
+            ("Q" | "L", 8) => ElementKind::UInt64,
+            ("?", 1) => ElementKind::Bool,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "unsupported buffer format '{format}', expected int64, uint64 or bool elements"
+                )));
+            }
+        };
+        let copy = PyByteArray::from_bound(&view)?;
+        let bytes = Zeroizing::new(copy.to_vec());
+        // Overwriting every byte with a slice of the same length writes in place rather than reallocating.
+        copy.set_item(PySlice::full_bound(value.py()), PyBytes::new_bound(value.py(), &vec![0; bytes.len()]))?;
+        Ok(Self { kind, big_endian, bytes })
+    }
+
+    /// Whether the elements in this buffer are unsigned integers.
+    pub(crate) fn is_unsigned(&self) -> bool {
+        self.kind == ElementKind::UInt64
+    }
+
+    /// Convert this buffer into an array, with integers encoded as signed or unsigned integers.
+    pub(crate) fn into_array(self, secret: bool, signed: bool) -> PyResult<NadaValue<Clear>> {
+        let element_type = self.element_type(secret, signed);
+        let values = self.into_values(&element_type)?;
+        NadaValue::new_array(element_type, values).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    fn element_type(&self, secret: bool, signed: bool) -> NadaType {
+        match (self.kind, secret, signed) {
+            (ElementKind::Bool, true, _) => NadaType::SecretBoolean,
+            (ElementKind::Bool, false, _) => NadaType::Boolean,
+            (_, true, true) => NadaType::SecretInteger,
+            (_, true, false) => NadaType::SecretUnsignedInteger,
+            (_, false, true) => NadaType::Integer,
+            (_, false, false) => NadaType::UnsignedInteger,
+        }
+    }
+
+    /// Convert the elements in this buffer into values of the given type.
+    pub(crate) fn into_values(self, element_type: &NadaType) -> PyResult<Vec<NadaValue<Clear>>> {
+        let is_bool = self.kind == ElementKind::Bool;
+        match element_type {
+            NadaType::Boolean | NadaType::SecretBoolean if is_bool => {
+                let new: fn(bool) -> NadaValue<Clear> = match element_type {
+                    NadaType::SecretBoolean => NadaValue::new_secret_boolean,
+                    _ => NadaValue::new_boolean,
+                };
+                Ok(self.bytes.iter().map(|b| new(*b != 0)).collect())
+            }
+            NadaType::Integer | NadaType::SecretInteger if !is_bool => {
+                let new: fn(BigInt) -> NadaValue<Clear> = match element_type {
+                    NadaType::SecretInteger => NadaValue::new_secret_integer,
+                    _ => NadaValue::new_integer,
+                };
+                Ok(self.integers().map(new).collect())
+            }
+            NadaType::UnsignedInteger | NadaType::SecretUnsignedInteger if !is_bool => {
+                let new: fn(BigUint) -> NadaValue<Clear> = match element_type {
+                    NadaType::SecretUnsignedInteger => NadaValue::new_secret_unsigned_integer,
+                    _ => NadaValue::new_unsigned_integer,
+                };
+                self.integers()
+                    .enumerate()
+                    .map(|(index, value)| match value.to_biguint() {
+                        Some(value) => Ok(new(value)),
+                        None => Err(PyValueError::new_err(format!("element {index} is negative: {value}"))),
+                    })
+                    .collect()
+            }
+            _ => Err(PyValueError::new_err(format!("buffer elements can't be converted into {element_type}"))),
+        }
+    }
+
+    fn integers(&self) -> impl Iterator<Item = BigInt> + '_ {
+        let (kind, big_endian) = (self.kind, self.big_endian);
+        self.bytes.chunks_exact(8).map(move |chunk| {
+            let chunk: [u8; 8] = chunk.try_into().expect("chunk is 8 bytes long");
+            match (kind, big_endian) {
+                (ElementKind::UInt64, false) => BigInt::from(u64::from_le_bytes(chunk)),
+                (ElementKind::UInt64, true) => BigInt::from(u64::from_be_bytes(chunk)),
+                (_, false) => BigInt::from(i64::from_le_bytes(chunk)),
+                (_, true) => BigInt::from(i64::from_be_bytes(chunk)),
+            }
+        })
+    }
+}
+
+/// Build a numpy array holding the given primitive values.
+///
+/// Integers become int64 elements, unsigned integers uint64 elements and booleans bool elements.
+pub(crate) fn values_to_numpy(
+    py: Python<'_>,
+    element_type: &NadaType,
+    values: &[NadaValue<Clear>],
+) -> PyResult<PyObject> {
+    let mut bytes = Zeroizing::new(Vec::with_capacity(values.len() * 8));
+    let dtype = match element_type {
+        NadaType::Integer | NadaType::SecretInteger => {
+            for (index, value) in values.iter().enumerate() {
+                let value = match value {
+                    NadaValue::Integer(value) | NadaValue::SecretInteger(value) => BigInt::from(value.clone()),
+                    _ => return Err(PyValueError::new_err(format!("element {index} is not an integer"))),
+                };
+                let value = i64::try_from(&value)
+                    .map_err(|_| PyValueError::new_err(format!("element {index} does not fit in int64: {value}")))?;
+                bytes.extend_from_slice(&value.to_ne_bytes());
+            }
+            "int64"
+        }
+        NadaType::UnsignedInteger | NadaType::SecretUnsignedInteger => {
+            for (index, value) in values.iter().enumerate() {
+                let value = match value {
+                    NadaValue::UnsignedInteger(value) | NadaValue::SecretUnsignedInteger(value) => {
+                        BigUint::from(value.clone())
+                    }
+                    _ => return Err(PyValueError::new_err(format!("element {index} is not an unsigned integer"))),
+                };
+                let value = u64::try_from(&value)
+                    .map_err(|_| PyValueError::new_err(format!("element {index} does not fit in uint64: {value}")))?;
+                bytes.extend_from_slice(&value.to_ne_bytes());
+            }
+            "uint64"
+        }
+        NadaType::Boolean | NadaType::SecretBoolean => {
+            for (index, value) in values.iter().enumerate() {
+                let value = match value {
+                    NadaValue::Boolean(value) | NadaValue::SecretBoolean(value) => *value,
+                    _ => return Err(PyValueError::new_err(format!("element {index} is not a boolean"))),
+                };
+                bytes.push(u8::from(value));
+            }
+            "bool"
+        }
+        _ => return Err(PyValueError::new_err(format!("arrays of {element_type} can't be converted to numpy"))),
+    };
+    let numpy = py.import_bound("numpy")?;
+    let array = numpy.call_method1("frombuffer", (PyBytes::new_bound(py, &bytes), dtype))?;
+    Ok(array.call_method0("copy")?.unbind())
+}
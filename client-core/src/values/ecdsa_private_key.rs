@@ -1,8 +1,14 @@
+use crate::values::{reduce_nada_value, secret::redacted_repr};
 use nillion_client_core::{
     privatekey,
     values::{Clear, NadaValue},
 };
-use pyo3::{exceptions::PyValueError, prelude::*, types::PyByteArray};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyByteArray, PyBytes},
+};
+use zeroize::Zeroizing;
 
 /// This is a :py:class:`EcdsaPrivateKey` class used to
 /// encode a secret bytearray as an ecdsa private key.
@@ -62,11 +68,12 @@ impl EcdsaPrivateKey {
     /// Returns a new EcdsaPrivateKey. The byte array should be in big-endian format.
     #[new]
     fn new(value: &Bound<'_, PyByteArray>) -> PyResult<EcdsaPrivateKey> {
-        let ecdsa_private_key = privatekey::ThresholdPrivateKey::from_be_bytes(&value.to_vec()).map_err(|_| {
-            PyValueError::new_err(
-                "Private key format error. Check your ecdsa secret key is exactly 32 bytes and different from 0.",
-            )
-        })?;
+        let ecdsa_private_key = privatekey::ThresholdPrivateKey::from_be_bytes(&Zeroizing::new(value.to_vec()))
+            .map_err(|_| {
+                PyValueError::new_err(
+                    "Private key format error. Check your ecdsa secret key is exactly 32 bytes and different from 0.",
+                )
+            })?;
         Ok(EcdsaPrivateKey { inner: NadaValue::new_ecdsa_private_key(ecdsa_private_key) })
     }
 
@@ -96,13 +103,14 @@ impl EcdsaPrivateKey {
     ///     >>> Ecdsa private key is now:  bytearray(b'these are good random 32 bytes!!')
     #[getter]
     fn get_value(&self, py: Python<'_>) -> PyResult<Py<PyByteArray>> {
-        let bytes = self
-            .inner
-            .as_ecdsa_private_key()
-            .ok_or_else(|| PyValueError::new_err("expected ecdsa private key"))?
-            .clone()
-            .to_be_bytes();
-        Ok(PyByteArray::new_bound(py, &bytes).into())
+        let bytes = Zeroizing::new(
+            self.inner
+                .as_ecdsa_private_key()
+                .ok_or_else(|| PyValueError::new_err("expected ecdsa private key"))?
+                .clone()
+                .to_be_bytes(),
+        );
+        Ok(PyByteArray::new_bound(py, &bytes[..]).into())
     }
 
     #[setter]
@@ -112,6 +120,17 @@ impl EcdsaPrivateKey {
     }
 
     fn __repr__(&self) -> String {
+        redacted_repr(&self.inner)
+    }
+
+    /// Returns the representation of this private key including its secrets.
+    ///
+    /// The default representation redacts secrets so that they don't end up in logs or tracebacks.
+    fn reveal(&self) -> String {
         self.inner.to_string()
     }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+        reduce_nada_value(py, &self.inner)
+    }
 }
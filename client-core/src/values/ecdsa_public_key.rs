@@ -1,5 +1,10 @@
+use crate::values::reduce_nada_value;
 use nillion_client_core::values::{Clear, NadaValue};
-use pyo3::{exceptions::PyValueError, prelude::*, types::PyByteArray};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyByteArray, PyBytes},
+};
 
 /// This is a :py:class:`EcdsaPublicKey` class used to
 /// encode an ecdsa public key.
@@ -101,6 +106,10 @@ impl EcdsaPublicKey {
         *self = Self::new(value)?;
         Ok(())
     }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+        reduce_nada_value(py, &self.inner)
+    }
 }
 
 fn to_33_byte_array(value: &Bound<'_, PyByteArray>) -> PyResult<[u8; 33]> {
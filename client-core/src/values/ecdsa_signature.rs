@@ -1,3 +1,4 @@
+use crate::values::reduce_nada_value;
 use nillion_client_core::{
     generic_ec::{curves::Secp256k1, NonZero, Scalar},
     signature,
@@ -6,7 +7,7 @@ use nillion_client_core::{
 use pyo3::{
     exceptions::PyValueError,
     prelude::*,
-    types::{PyByteArray, PyTuple},
+    types::{PyByteArray, PyBytes, PyTuple},
 };
 
 /// This is a :py:class:`EcdsaSignature` class used to
@@ -114,10 +115,14 @@ impl EcdsaSignature {
 
         Ok((r_pybytes, s_pybytes))
     }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+        reduce_nada_value(py, &self.inner)
+    }
 }
 
 /// parse a scalar from bytes in big-endian order
-fn parse_scalar(bytes: &[u8], param: &str) -> PyResult<NonZero<Scalar<Secp256k1>>> {
+pub(crate) fn parse_scalar(bytes: &[u8], param: &str) -> PyResult<NonZero<Scalar<Secp256k1>>> {
     let scalar = Scalar::from_be_bytes(bytes).map_err(|_| {
         PyValueError::new_err(format!(
             "Ecdsa signature parameter {} format error as the encoded integer is larger than group order. Note that byte representation should be in big-endian format.",
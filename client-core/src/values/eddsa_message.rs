@@ -1,5 +1,10 @@
+use crate::values::reduce_nada_value;
 use nillion_client_core::values::{Clear, NadaValue};
-use pyo3::{exceptions::PyValueError, prelude::*, types::PyByteArray};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyByteArray, PyBytes},
+};
 
 /// This is a :py:class:`EddsaMessage` class used to
 /// encode a secret as a message digest.
@@ -101,4 +106,8 @@ impl EddsaMessage {
         *self = Self::new(value)?;
         Ok(())
     }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+        reduce_nada_value(py, &self.inner)
+    }
 }
@@ -1,3 +1,4 @@
+use crate::values::reduce_nada_value;
 use nillion_client_core::{
     generic_ec::Scalar,
     signature,
@@ -6,7 +7,7 @@ use nillion_client_core::{
 use pyo3::{
     exceptions::PyValueError,
     prelude::*,
-    types::{PyByteArray, PyTuple},
+    types::{PyByteArray, PyBytes, PyTuple},
 };
 
 /// This is a :py:class:`EddsaSignature` class used to
@@ -113,4 +114,8 @@ impl EddsaSignature {
 
         Ok((r_pybytes, z_pybytes))
     }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+        reduce_nada_value(py, &self.inner)
+    }
 }
@@ -0,0 +1,490 @@
+//! Compact binary encoding of cleartext Nada values.
+//!
+//! An encoded value is a version byte followed by the value itself. Every value starts with a one byte tag
+//! identifying its type, followed by its payload:
+//!
+//! * Integers are length prefixed little-endian bytes, two's complement for signed integers.
+//! * Booleans are a single byte.
+//! * Byte strings (blobs, keys, messages, signature components) are length prefixed.
+//! * Arrays are their element type, the element count and the elements.
+//! * Tuples are their two elements; n-tuples are the element count and the elements.
+//! * Objects are the field count and each field name (length prefixed utf-8) followed by its value.
+//!
+//! Lengths and counts are unsigned LEB128 varints. Types use the same tags as values, with arrays carrying their
+//! inner type and size, and compound types carrying their element types. Decoding fails for values and types nested
+//! more than [`MAX_DEPTH`] levels deep, and for array types declaring more than [`MAX_ARRAY_SIZE`] elements.
+
+use crate::values::ecdsa_signature::parse_scalar;
+use indexmap::IndexMap;
+use nillion_client_core::{
+    generic_ec::Scalar,
+    privatekey::ThresholdPrivateKey,
+    signature,
+    values::{BigInt, BigUint, Clear, NadaType, NadaValue},
+};
+use pyo3::{exceptions::PyValueError, PyResult};
+
+/// The current version of the encoding.
+const VERSION: u8 = 1;
+
+const TAG_INTEGER: u8 = 0;
+const TAG_UNSIGNED_INTEGER: u8 = 1;
+const TAG_BOOLEAN: u8 = 2;
+const TAG_SECRET_INTEGER: u8 = 3;
+const TAG_SECRET_UNSIGNED_INTEGER: u8 = 4;
+const TAG_SECRET_BOOLEAN: u8 = 5;
+const TAG_SECRET_BLOB: u8 = 6;
+const TAG_ARRAY: u8 = 7;
+const TAG_TUPLE: u8 = 8;
+const TAG_NTUPLE: u8 = 9;
+const TAG_OBJECT: u8 = 10;
+const TAG_ECDSA_PRIVATE_KEY: u8 = 11;
+const TAG_ECDSA_DIGEST_MESSAGE: u8 = 12;
+const TAG_ECDSA_SIGNATURE: u8 = 13;
+const TAG_ECDSA_PUBLIC_KEY: u8 = 14;
+const TAG_STORE_ID: u8 = 15;
+const TAG_EDDSA_PRIVATE_KEY: u8 = 16;
+const TAG_EDDSA_PUBLIC_KEY: u8 = 17;
+const TAG_EDDSA_SIGNATURE: u8 = 18;
+const TAG_EDDSA_MESSAGE: u8 = 19;
+
+/// The deepest nesting of values and types that can be decoded.
+///
+/// Every level of nesting costs a single byte of input, this keeps small crafted inputs from overflowing the stack.
+pub(crate) const MAX_DEPTH: usize = 64;
+
+/// The largest size an array type can declare.
+///
+/// Array types carry their size without their elements, so nothing else bounds it, and code sizing buffers or
+/// defaults from a decoded type would otherwise trust an arbitrary number.
+pub(crate) const MAX_ARRAY_SIZE: usize = 1 << 24;
+
+/// Encode a cleartext value.
+pub(crate) fn encode_value(value: &NadaValue<Clear>) -> PyResult<Vec<u8>> {
+    let mut output = vec![VERSION];
+    write_value(&mut output, value)?;
+    Ok(output)
+}
+
+/// Decode a cleartext value produced by [`encode_value`].
+pub(crate) fn decode_value(bytes: &[u8]) -> PyResult<NadaValue<Clear>> {
+    let mut reader = Reader::new(bytes);
+    reader.read_version()?;
+    let value = reader.read_value(0)?;
+    reader.finish()?;
+    Ok(value)
+}
+
+/// Encode a type.
+pub(crate) fn encode_type(nada_type: &NadaType) -> PyResult<Vec<u8>> {
+    let mut output = vec![VERSION];
+    write_type(&mut output, nada_type)?;
+    Ok(output)
+}
+
+/// Decode a type produced by [`encode_type`].
+pub(crate) fn decode_type(bytes: &[u8]) -> PyResult<NadaType> {
+    let mut reader = Reader::new(bytes);
+    reader.read_version()?;
+    let nada_type = reader.read_type(0)?;
+    reader.finish()?;
+    Ok(nada_type)
+}
+
+pub(crate) fn write_varint(output: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        output.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
+
+pub(crate) fn write_bytes(output: &mut Vec<u8>, bytes: &[u8]) {
+    write_varint(output, bytes.len() as u64);
+    output.extend_from_slice(bytes);
+}
+
+fn write_value(output: &mut Vec<u8>, value: &NadaValue<Clear>) -> PyResult<()> {
+    match value {
+        NadaValue::Integer(value) => {
+            output.push(TAG_INTEGER);
+            write_signed_integer(output, value.clone().into());
+        }
+        NadaValue::SecretInteger(value) => {
+            output.push(TAG_SECRET_INTEGER);
+            write_signed_integer(output, value.clone().into());
+        }
+        NadaValue::UnsignedInteger(value) => {
+            output.push(TAG_UNSIGNED_INTEGER);
+            write_unsigned_integer(output, value.clone().into());
+        }
+        NadaValue::SecretUnsignedInteger(value) => {
+            output.push(TAG_SECRET_UNSIGNED_INTEGER);
+            write_unsigned_integer(output, value.clone().into());
+        }
+        NadaValue::Boolean(value) => {
+            output.extend_from_slice(&[TAG_BOOLEAN, u8::from(*value)]);
+        }
+        NadaValue::SecretBoolean(value) => {
+            output.extend_from_slice(&[TAG_SECRET_BOOLEAN, u8::from(*value)]);
+        }
+        NadaValue::SecretBlob(value) => {
+            output.push(TAG_SECRET_BLOB);
+            write_bytes(output, value);
+        }
+        NadaValue::Array { inner_type, values } => {
+            output.push(TAG_ARRAY);
+            write_type(output, inner_type)?;
+            write_varint(output, values.len() as u64);
+            for value in values {
+                write_value(output, value)?;
+            }
+        }
+        NadaValue::Tuple { left, right } => {
+            output.push(TAG_TUPLE);
+            write_value(output, left)?;
+            write_value(output, right)?;
+        }
+        NadaValue::NTuple { values } => {
+            output.push(TAG_NTUPLE);
+            write_varint(output, values.len() as u64);
+            for value in values {
+                write_value(output, value)?;
+            }
+        }
+        NadaValue::Object { values } => {
+            output.push(TAG_OBJECT);
+            write_varint(output, values.len() as u64);
+            for (name, value) in values {
+                write_bytes(output, name.as_bytes());
+                write_value(output, value)?;
+            }
+        }
+        NadaValue::EcdsaPrivateKey(key) => {
+            output.push(TAG_ECDSA_PRIVATE_KEY);
+            let bytes = key.clone().to_be_bytes();
+            write_bytes(output, &bytes);
+        }
+        NadaValue::EcdsaDigestMessage(digest) => {
+            output.push(TAG_ECDSA_DIGEST_MESSAGE);
+            write_bytes(output, digest);
+        }
+        NadaValue::EcdsaSignature(signature) => {
+            output.push(TAG_ECDSA_SIGNATURE);
+            let signature::EcdsaSignature { r, s } = signature;
+            write_bytes(output, &Scalar::to_be_bytes(r));
+            write_bytes(output, &Scalar::to_be_bytes(s));
+        }
+        NadaValue::EcdsaPublicKey(key) => {
+            output.push(TAG_ECDSA_PUBLIC_KEY);
+            write_bytes(output, &key.0);
+        }
+        NadaValue::StoreId(store_id) => {
+            output.push(TAG_STORE_ID);
+            write_bytes(output, store_id);
+        }
+        NadaValue::EddsaPrivateKey(key) => {
+            output.push(TAG_EDDSA_PRIVATE_KEY);
+            let bytes = key.clone().to_le_bytes();
+            write_bytes(output, &bytes);
+        }
+        NadaValue::EddsaPublicKey(key) => {
+            output.push(TAG_EDDSA_PUBLIC_KEY);
+            write_bytes(output, key);
+        }
+        NadaValue::EddsaSignature(signature) => {
+            output.push(TAG_EDDSA_SIGNATURE);
+            let signature::EddsaSignature { signature } = signature;
+            write_bytes(output, &signature.r.to_bytes());
+            write_bytes(output, &Scalar::to_le_bytes(&signature.z));
+        }
+        NadaValue::EddsaMessage(message) => {
+            output.push(TAG_EDDSA_MESSAGE);
+            write_bytes(output, message);
+        }
+        NadaValue::ShamirShareInteger(_)
+        | NadaValue::ShamirShareUnsignedInteger(_)
+        | NadaValue::ShamirShareBoolean(_) => {
+            return Err(PyValueError::new_err("shares can't be encoded as cleartext values"));
+        }
+    }
+    Ok(())
+}
+
+fn write_signed_integer(output: &mut Vec<u8>, value: BigInt) {
+    write_bytes(output, &value.to_signed_bytes_le());
+}
+
+fn write_unsigned_integer(output: &mut Vec<u8>, value: BigUint) {
+    write_bytes(output, &value.to_bytes_le());
+}
+
+fn write_type(output: &mut Vec<u8>, nada_type: &NadaType) -> PyResult<()> {
+    let tag = match nada_type {
+        NadaType::Integer => TAG_INTEGER,
+        NadaType::UnsignedInteger => TAG_UNSIGNED_INTEGER,
+        NadaType::Boolean => TAG_BOOLEAN,
+        NadaType::SecretInteger => TAG_SECRET_INTEGER,
+        NadaType::SecretUnsignedInteger => TAG_SECRET_UNSIGNED_INTEGER,
+        NadaType::SecretBoolean => TAG_SECRET_BOOLEAN,
+        NadaType::SecretBlob => TAG_SECRET_BLOB,
+        NadaType::EcdsaPrivateKey => TAG_ECDSA_PRIVATE_KEY,
+        NadaType::EcdsaDigestMessage => TAG_ECDSA_DIGEST_MESSAGE,
+        NadaType::EcdsaSignature => TAG_ECDSA_SIGNATURE,
+        NadaType::EcdsaPublicKey => TAG_ECDSA_PUBLIC_KEY,
+        NadaType::StoreId => TAG_STORE_ID,
+        NadaType::EddsaPrivateKey => TAG_EDDSA_PRIVATE_KEY,
+        NadaType::EddsaPublicKey => TAG_EDDSA_PUBLIC_KEY,
+        NadaType::EddsaSignature => TAG_EDDSA_SIGNATURE,
+        NadaType::EddsaMessage => TAG_EDDSA_MESSAGE,
+        NadaType::Array { inner_type, size } => {
+            output.push(TAG_ARRAY);
+            write_type(output, inner_type)?;
+            write_varint(output, *size as u64);
+            return Ok(());
+        }
+        NadaType::Tuple { left_type, right_type } => {
+            output.push(TAG_TUPLE);
+            write_type(output, left_type)?;
+            write_type(output, right_type)?;
+            return Ok(());
+        }
+        NadaType::NTuple { types } => {
+            output.push(TAG_NTUPLE);
+            write_varint(output, types.len() as u64);
+            for nada_type in types {
+                write_type(output, nada_type)?;
+            }
+            return Ok(());
+        }
+        NadaType::Object { types } => {
+            output.push(TAG_OBJECT);
+            write_varint(output, types.len() as u64);
+            for (name, nada_type) in types {
+                write_bytes(output, name.as_bytes());
+                write_type(output, nada_type)?;
+            }
+            return Ok(());
+        }
+        NadaType::ShamirShareInteger | NadaType::ShamirShareUnsignedInteger | NadaType::ShamirShareBoolean => {
+            return Err(PyValueError::new_err(format!("type {nada_type} can't be encoded as a cleartext type")));
+        }
+    };
+    output.push(tag);
+    Ok(())
+}
+
+/// Fail if a value or type at the given depth is nested too deeply to be decoded.
+pub(crate) fn check_depth(depth: usize) -> PyResult<()> {
+    if depth > MAX_DEPTH {
+        return Err(PyValueError::new_err(format!("input is nested more than {MAX_DEPTH} levels deep")));
+    }
+    Ok(())
+}
+
+/// A cursor over an encoded buffer.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn read_version(&mut self) -> PyResult<()> {
+        match self.read_u8()? {
+            VERSION => Ok(()),
+            version => Err(PyValueError::new_err(format!("unsupported encoding version: {version}"))),
+        }
+    }
+
+    pub(crate) fn finish(&self) -> PyResult<()> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(PyValueError::new_err(format!("{} trailing bytes after encoded value", self.bytes.len())))
+        }
+    }
+
+    pub(crate) fn read_u8(&mut self) -> PyResult<u8> {
+        let (first, rest) = self.bytes.split_first().ok_or_else(|| PyValueError::new_err("unexpected end of input"))?;
+        self.bytes = rest;
+        Ok(*first)
+    }
+
+    pub(crate) fn read_varint(&mut self) -> PyResult<u64> {
+        let mut value: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(PyValueError::new_err("varint is too long"))
+    }
+
+    fn read_length(&mut self) -> PyResult<usize> {
+        let length = usize::try_from(self.read_varint()?).map_err(|_| PyValueError::new_err("length is too large"))?;
+        if length > self.bytes.len() {
+            return Err(PyValueError::new_err("unexpected end of input"));
+        }
+        Ok(length)
+    }
+
+    pub(crate) fn read_bytes(&mut self) -> PyResult<&'a [u8]> {
+        let length = self.read_length()?;
+        let (bytes, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(bytes)
+    }
+
+    fn read_byte_array<const N: usize>(&mut self, name: &str) -> PyResult<[u8; N]> {
+        self.read_bytes()?
+            .try_into()
+            .map_err(|_| PyValueError::new_err(format!("{name} must be exactly {N} bytes long")))
+    }
+
+    pub(crate) fn read_string(&mut self) -> PyResult<String> {
+        String::from_utf8(self.read_bytes()?.to_vec()).map_err(|_| PyValueError::new_err("invalid utf-8 string"))
+    }
+
+    fn read_bool(&mut self) -> PyResult<bool> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(PyValueError::new_err(format!("invalid boolean: {other}"))),
+        }
+    }
+
+    fn read_signed_integer(&mut self) -> PyResult<BigInt> {
+        Ok(BigInt::from_signed_bytes_le(self.read_bytes()?))
+    }
+
+    fn read_unsigned_integer(&mut self) -> PyResult<BigUint> {
+        Ok(BigUint::from_bytes_le(self.read_bytes()?))
+    }
+
+    pub(crate) fn read_count(&mut self) -> PyResult<usize> {
+        // Every element takes at least one byte, which bounds the count by the remaining input.
+        self.read_length()
+    }
+
+    fn read_value(&mut self, depth: usize) -> PyResult<NadaValue<Clear>> {
+        check_depth(depth)?;
+        let value = match self.read_u8()? {
+            TAG_INTEGER => NadaValue::new_integer(self.read_signed_integer()?),
+            TAG_SECRET_INTEGER => NadaValue::new_secret_integer(self.read_signed_integer()?),
+            TAG_UNSIGNED_INTEGER => NadaValue::new_unsigned_integer(self.read_unsigned_integer()?),
+            TAG_SECRET_UNSIGNED_INTEGER => NadaValue::new_secret_unsigned_integer(self.read_unsigned_integer()?),
+            TAG_BOOLEAN => NadaValue::new_boolean(self.read_bool()?),
+            TAG_SECRET_BOOLEAN => NadaValue::new_secret_boolean(self.read_bool()?),
+            TAG_SECRET_BLOB => NadaValue::new_secret_blob(self.read_bytes()?.to_vec()),
+            TAG_ARRAY => {
+                let inner_type = self.read_type(depth + 1)?;
+                let count = self.read_count()?;
+                let values = (0..count).map(|_| self.read_value(depth + 1)).collect::<PyResult<Vec<_>>>()?;
+                NadaValue::new_array(inner_type, values).map_err(|e| PyValueError::new_err(e.to_string()))?
+            }
+            TAG_TUPLE => {
+                let left = self.read_value(depth + 1)?;
+                let right = self.read_value(depth + 1)?;
+                NadaValue::new_tuple(left, right).map_err(|e| PyValueError::new_err(e.to_string()))?
+            }
+            TAG_NTUPLE => {
+                let count = self.read_count()?;
+                let values = (0..count).map(|_| self.read_value(depth + 1)).collect::<PyResult<Vec<_>>>()?;
+                NadaValue::new_n_tuple(values).map_err(|e| PyValueError::new_err(e.to_string()))?
+            }
+            TAG_OBJECT => {
+                let count = self.read_count()?;
+                let mut values = IndexMap::with_capacity(count);
+                for _ in 0..count {
+                    let name = self.read_string()?;
+                    values.insert(name, self.read_value(depth + 1)?);
+                }
+                NadaValue::new_object(values).map_err(|e| PyValueError::new_err(e.to_string()))?
+            }
+            TAG_ECDSA_PRIVATE_KEY => {
+                let key = ThresholdPrivateKey::from_be_bytes(self.read_bytes()?)
+                    .map_err(|_| PyValueError::new_err("invalid ecdsa private key"))?;
+                NadaValue::new_ecdsa_private_key(key)
+            }
+            TAG_ECDSA_DIGEST_MESSAGE => NadaValue::new_ecdsa_digest_message(self.read_byte_array("digest message")?),
+            TAG_ECDSA_SIGNATURE => {
+                let r = parse_scalar(self.read_bytes()?, "r")?;
+                let s = parse_scalar(self.read_bytes()?, "s")?;
+                NadaValue::new_ecdsa_signature(signature::EcdsaSignature { r, s })
+            }
+            TAG_ECDSA_PUBLIC_KEY => NadaValue::new_ecdsa_public_key(self.read_byte_array("ecdsa public key")?),
+            TAG_STORE_ID => NadaValue::new_store_id(self.read_byte_array("store id")?),
+            TAG_EDDSA_PRIVATE_KEY => {
+                let key = ThresholdPrivateKey::from_le_bytes(self.read_bytes()?)
+                    .map_err(|_| PyValueError::new_err("invalid eddsa private key"))?;
+                NadaValue::new_eddsa_private_key(key)
+            }
+            TAG_EDDSA_PUBLIC_KEY => NadaValue::new_eddsa_public_key(self.read_byte_array("eddsa public key")?),
+            TAG_EDDSA_SIGNATURE => {
+                let r = self.read_bytes()?;
+                let z = self.read_bytes()?;
+                let signature = signature::EddsaSignature::from_components_bytes(r, z)
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?;
+                NadaValue::new_eddsa_signature(signature)
+            }
+            TAG_EDDSA_MESSAGE => NadaValue::new_eddsa_message(self.read_bytes()?.to_vec()),
+            tag => return Err(PyValueError::new_err(format!("unknown value tag: {tag}"))),
+        };
+        Ok(value)
+    }
+
+    fn read_type(&mut self, depth: usize) -> PyResult<NadaType> {
+        check_depth(depth)?;
+        let nada_type = match self.read_u8()? {
+            TAG_INTEGER => NadaType::Integer,
+            TAG_UNSIGNED_INTEGER => NadaType::UnsignedInteger,
+            TAG_BOOLEAN => NadaType::Boolean,
+            TAG_SECRET_INTEGER => NadaType::SecretInteger,
+            TAG_SECRET_UNSIGNED_INTEGER => NadaType::SecretUnsignedInteger,
+            TAG_SECRET_BOOLEAN => NadaType::SecretBoolean,
+            TAG_SECRET_BLOB => NadaType::SecretBlob,
+            TAG_ECDSA_PRIVATE_KEY => NadaType::EcdsaPrivateKey,
+            TAG_ECDSA_DIGEST_MESSAGE => NadaType::EcdsaDigestMessage,
+            TAG_ECDSA_SIGNATURE => NadaType::EcdsaSignature,
+            TAG_ECDSA_PUBLIC_KEY => NadaType::EcdsaPublicKey,
+            TAG_STORE_ID => NadaType::StoreId,
+            TAG_EDDSA_PRIVATE_KEY => NadaType::EddsaPrivateKey,
+            TAG_EDDSA_PUBLIC_KEY => NadaType::EddsaPublicKey,
+            TAG_EDDSA_SIGNATURE => NadaType::EddsaSignature,
+            TAG_EDDSA_MESSAGE => NadaType::EddsaMessage,
+            TAG_ARRAY => {
+                let inner_type = self.read_type(depth + 1)?;
+                let size = self.read_varint()?;
+                let size = usize::try_from(size).ok().filter(|size| *size <= MAX_ARRAY_SIZE).ok_or_else(|| {
+                    PyValueError::new_err(format!("array size {size} is larger than the maximum of {MAX_ARRAY_SIZE}"))
+                })?;
+                NadaType::Array { inner_type: Box::new(inner_type), size }
+            }
+            TAG_TUPLE => {
+                let left_type = self.read_type(depth + 1)?;
+                let right_type = self.read_type(depth + 1)?;
+                NadaType::Tuple { left_type: Box::new(left_type), right_type: Box::new(right_type) }
+            }
+            TAG_NTUPLE => {
+                let count = self.read_count()?;
+                NadaType::NTuple { types: (0..count).map(|_| self.read_type(depth + 1)).collect::<PyResult<_>>()? }
+            }
+            TAG_OBJECT => {
+                let count = self.read_count()?;
+                let mut types = IndexMap::with_capacity(count);
+                for _ in 0..count {
+                    let name = self.read_string()?;
+                    types.insert(name, self.read_type(depth + 1)?);
+                }
+                NadaType::Object { types }
+            }
+            tag => return Err(PyValueError::new_err(format!("unknown type tag: {tag}"))),
+        };
+        Ok(nada_type)
+    }
+}
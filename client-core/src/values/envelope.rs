@@ -0,0 +1,110 @@
+//! Opt-in integrity envelope for secret blobs.
+//!
+//! An enveloped blob is:
+//!
+//! * The magic bytes `\0NILENV` followed by a version byte.
+//! * A flags byte, with bit 0 set when the payload is zstd compressed.
+//! * The SHA-256 digest of the original bytes.
+//! * The payload: the original bytes, compressed if flagged.
+//!
+//! Envelopes are opened transparently when unmasking: the payload is decompressed and its digest verified, raising
+//! `BlobIntegrityError` if it doesn't match.
+
+use nillion_client_core::values::{Clear, NadaValue};
+use pyo3::{create_exception, exceptions::PyValueError, PyResult};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use zeroize::{Zeroize, Zeroizing};
+
+create_exception!(
+    nillion_client_core,
+    BlobIntegrityError,
+    PyValueError,
+    "An enveloped secret blob doesn't match the digest it was created with."
+);
+
+/// The magic bytes that start every envelope.
+const MAGIC: &[u8; 7] = b"\0NILENV";
+
+/// The current version of the envelope format.
+const VERSION: u8 = 1;
+
+/// Set in the flags when the payload is zstd compressed.
+const FLAG_ZSTD: u8 = 1;
+
+const DIGEST_LEN: usize = 32;
+
+/// Wrap some bytes in an envelope, compressing them first if requested.
+pub(crate) fn seal(data: &[u8], compress: bool) -> PyResult<Zeroizing<Vec<u8>>> {
+    let mut output = Zeroizing::new(MAGIC.to_vec());
+    output.push(VERSION);
+    output.push(if compress { FLAG_ZSTD } else { 0 });
+    output.extend_from_slice(&Sha256::digest(data));
+    if compress {
+        let payload = Zeroizing::new(
+            zstd::encode_all(data, 0).map_err(|e| PyValueError::new_err(format!("compressing blob failed: {e}")))?,
+        );
+        output.extend_from_slice(&payload);
+    } else {
+        output.extend_from_slice(data);
+    }
+    Ok(output)
+}
+
+/// Open an envelope, returning `None` if the bytes aren't one.
+fn open(bytes: &[u8]) -> Result<Option<Zeroizing<Vec<u8>>>, String> {
+    let Some(bytes) = bytes.strip_prefix(MAGIC) else {
+        return Ok(None);
+    };
+    let [version, flags, bytes @ ..] = bytes else {
+        return Err("envelope is truncated".into());
+    };
+    if *version != VERSION {
+        return Err(format!("unsupported envelope version: {version}"));
+    }
+    if bytes.len() < DIGEST_LEN {
+        return Err("envelope is truncated".into());
+    }
+    let (digest, payload) = bytes.split_at(DIGEST_LEN);
+    let data = match *flags {
+        0 => Zeroizing::new(payload.to_vec()),
+        FLAG_ZSTD => Zeroizing::new(zstd::decode_all(payload).map_err(|e| format!("decompressing blob failed: {e}"))?),
+        flags => return Err(format!("unknown envelope flags: {flags}")),
+    };
+    let actual = Sha256::digest(&*data);
+    if actual.as_slice() != digest {
+        return Err(format!("digest mismatch: expected {}, got {}", hex::encode(digest), hex::encode(actual)));
+    }
+    Ok(Some(data))
+}
+
+/// Open every enveloped blob in a set of unmasked values, in place.
+pub(crate) fn open_values(values: &mut HashMap<String, NadaValue<Clear>>) -> PyResult<()> {
+    for (name, value) in values.iter_mut() {
+        open_value(value, name).map_err(BlobIntegrityError::new_err)?;
+    }
+    Ok(())
+}
+
+fn open_value(value: &mut NadaValue<Clear>, path: &str) -> Result<(), String> {
+    match value {
+        NadaValue::SecretBlob(bytes) => {
+            if let Some(data) = open(bytes).map_err(|e| format!("value '{path}': {e}"))? {
+                bytes.zeroize();
+                bytes.extend_from_slice(&data);
+            }
+            Ok(())
+        }
+        NadaValue::Array { values, .. } | NadaValue::NTuple { values } => {
+            values.iter_mut().enumerate().try_for_each(|(index, value)| open_value(value, &format!("{path}[{index}]")))
+        }
+        NadaValue::Tuple { left, right } => {
+            open_value(left, &format!("{path}[0]"))?;
+            open_value(right, &format!("{path}[1]"))
+        }
+        NadaValue::Object { values } => {
+            values.iter_mut().try_for_each(|(name, value)| open_value(value, &format!("{path}.{name}")))
+        }
+        _ => Ok(()),
+    }
+}
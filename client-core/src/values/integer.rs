@@ -1,5 +1,14 @@
-use nillion_client_core::values::{BigInt, Clear, NadaValue};
-use pyo3::{exceptions::PyValueError, prelude::*};
+use crate::{
+    ranges::prime_bits,
+    values::{
+        arithmetic::{
+            apply, checked, chosen_modulo, compare, extract_int, hash_value, narrowest, Operation, Signedness,
+        },
+        secret::{redacted_repr, wipe},
+    },
+};
+use nillion_client_core::values::{BigInt, Clear, EncodedModulo, NadaValue};
+use pyo3::{exceptions::PyValueError, prelude::*, pyclass::CompareOp};
 
 /// This is a :py:class:`SecretInteger` class used to encode a secret as an integer.
 ///
@@ -7,6 +16,10 @@ use pyo3::{exceptions::PyValueError, prelude::*};
 /// ---------
 /// value : int
 ///     Value of the secret encoded element.
+/// prime_bits : int, optional
+///     The size in bits of the safe prime (64, 128 or 256) that the value and the results of
+///     arithmetic on it are range-checked against. Defaults to checking arithmetic results
+///     against the 256 bit safe prime.
 ///
 /// Returns
 /// -------
@@ -29,10 +42,18 @@ use pyo3::{exceptions::PyValueError, prelude::*};
 /// .. code-block:: text
 ///
 ///     >>> Are the secret integers the same?  False
-#[pyclass(eq)]
+///
+/// Secret integers are ordered and support ``+``, ``-``, ``*``, ``//`` and ``%``
+/// with other secret integers, public integers and plain ``int`` values. Results are secret
+/// integers, checked against the smallest prime chosen by the operands. Masking checks values
+/// against the masker's own prime (see :py:meth:`SecretMasker.validate_ranges`).
+///
+/// Values are immutable and hashable, so they can be used in sets and as dict keys.
+#[pyclass(frozen)]
 #[derive(PartialEq, Clone)]
 pub struct SecretInteger {
     pub(crate) inner: NadaValue<Clear>,
+    pub(crate) modulo: Option<EncodedModulo>,
 }
 
 impl TryFrom<NadaValue<Clear>> for SecretInteger {
@@ -41,17 +62,51 @@ impl TryFrom<NadaValue<Clear>> for SecretInteger {
     fn try_from(value: NadaValue<Clear>) -> PyResult<Self> {
         value
             .is_secret_integer()
-            .then(|| SecretInteger { inner: value })
+            .then(|| SecretInteger { inner: value, modulo: None })
             .ok_or_else(|| PyValueError::new_err("expected secret integer"))
     }
 }
 
+impl Drop for SecretInteger {
+    fn drop(&mut self) {
+        wipe(&mut self.inner);
+    }
+}
+
+impl SecretInteger {
+    /// Extracts the value of an operand that can be combined with a secret integer.
+    fn operand(other: &Bound<'_, PyAny>) -> Option<(BigInt, Option<EncodedModulo>)> {
+        if let Ok(other) = other.extract::<SecretInteger>() {
+            return other.get_value().ok().map(|value| (value, other.modulo));
+        }
+        if let Ok(other) = other.extract::<Integer>() {
+            return other.get_value().ok().map(|value| (value, other.modulo));
+        }
+        extract_int(other).map(|value| (value, None))
+    }
+
+    fn binary_op(&self, other: &Bound<'_, PyAny>, operation: Operation, reflected: bool) -> PyResult<PyObject> {
+        let py = other.py();
+        let Some((other, other_modulo)) = Self::operand(other) else {
+            return Ok(py.NotImplemented());
+        };
+        let value = self.get_value()?;
+        let modulo = narrowest(self.modulo, other_modulo);
+        let (left, right) = if reflected { (other, value) } else { (value, other) };
+        let result = checked(apply(operation, &left, &right)?, Signedness::Signed, modulo)?;
+        Ok(SecretInteger { inner: NadaValue::new_secret_integer(result), modulo }.into_py(py))
+    }
+}
+
 #[pymethods]
 impl SecretInteger {
     /// Returns a new SecretInteger.
     #[new]
-    pub(crate) fn new(value: BigInt) -> SecretInteger {
-        SecretInteger { inner: NadaValue::new_secret_integer(value) }
+    #[pyo3(signature = (value, prime_bits=None))]
+    pub(crate) fn new(value: BigInt, prime_bits: Option<u32>) -> PyResult<SecretInteger> {
+        let modulo = chosen_modulo(prime_bits)?;
+        let value = if modulo.is_some() { checked(value, Signedness::Signed, modulo)? } else { value };
+        Ok(SecretInteger { inner: NadaValue::new_secret_integer(value), modulo })
     }
 
     #[getter]
@@ -64,14 +119,86 @@ impl SecretInteger {
             .into())
     }
 
-    #[setter]
-    fn set_value(&mut self, value: BigInt) {
-        self.inner = NadaValue::new_secret_integer(value);
+    /// The size in bits of the safe prime chosen for this value, if any.
+    #[getter]
+    fn get_prime_bits(&self) -> Option<u32> {
+        self.modulo.map(prime_bits)
     }
 
     fn __repr__(&self) -> String {
+        redacted_repr(&self.inner)
+    }
+
+    /// Returns the representation of this secret integer including its secrets.
+    ///
+    /// The default representation redacts secrets so that they don't end up in logs or tracebacks.
+    fn reveal(&self) -> String {
         self.inner.to_string()
     }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (BigInt, Option<u32>))> {
+        Ok((py.get_type_bound::<SecretInteger>().into_any().unbind(), (self.get_value()?, self.get_prime_bits())))
+    }
+
+    fn __add__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Add, false)
+    }
+
+    fn __radd__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Add, true)
+    }
+
+    fn __sub__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Sub, false)
+    }
+
+    fn __rsub__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Sub, true)
+    }
+
+    fn __mul__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mul, false)
+    }
+
+    fn __rmul__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mul, true)
+    }
+
+    fn __floordiv__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::FloorDiv, false)
+    }
+
+    fn __rfloordiv__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::FloorDiv, true)
+    }
+
+    fn __mod__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mod, false)
+    }
+
+    fn __rmod__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mod, true)
+    }
+
+    fn __int__(&self) -> PyResult<BigInt> {
+        self.get_value()
+    }
+
+    fn __index__(&self) -> PyResult<BigInt> {
+        self.get_value()
+    }
+
+    fn __hash__(&self) -> PyResult<u64> {
+        Ok(hash_value("SecretInteger", &self.get_value()?))
+    }
+
+    fn __richcmp__(&self, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<PyObject> {
+        let py = other.py();
+        let Ok(other) = other.extract::<SecretInteger>() else {
+            return Ok(py.NotImplemented());
+        };
+        Ok(compare(&self.get_value()?, &other.get_value()?, op).into_py(py))
+    }
 }
 
 /// This is a :py:class:`Integer` class used to
@@ -81,6 +208,10 @@ impl SecretInteger {
 /// ---------
 /// value : int
 ///     Value of the public encoded element.
+/// prime_bits : int, optional
+///     The size in bits of the safe prime (64, 128 or 256) that the value and the results of
+///     arithmetic on it are range-checked against. Defaults to checking arithmetic results
+///     against the 256 bit safe prime.
 ///
 /// Returns
 /// -------
@@ -103,17 +234,49 @@ impl SecretInteger {
 /// .. code-block:: text
 ///
 ///     >>> Are the public integers the same?  False
-#[pyclass(eq)]
+///
+/// Public integers are ordered and support ``+``, ``-``, ``*``, ``//`` and ``%``
+/// with other public integers and plain ``int`` values. Combining a public integer with a
+/// secret integer results in a secret integer.
+///
+/// Values are immutable and hashable, so they can be used in sets and as dict keys.
+#[pyclass(frozen)]
 #[derive(PartialEq, Clone)]
 pub struct Integer {
     pub(crate) inner: NadaValue<Clear>,
+    pub(crate) modulo: Option<EncodedModulo>,
 }
 
 impl TryFrom<NadaValue<Clear>> for Integer {
     type Error = PyErr;
 
     fn try_from(value: NadaValue<Clear>) -> PyResult<Self> {
-        value.is_integer().then(|| Integer { inner: value }).ok_or_else(|| PyValueError::new_err("expected integer"))
+        value
+            .is_integer()
+            .then(|| Integer { inner: value, modulo: None })
+            .ok_or_else(|| PyValueError::new_err("expected integer"))
+    }
+}
+
+impl Integer {
+    /// Extracts the value of an operand that can be combined with a public integer.
+    fn operand(other: &Bound<'_, PyAny>) -> Option<(BigInt, Option<EncodedModulo>)> {
+        if let Ok(other) = other.extract::<Integer>() {
+            return other.get_value().ok().map(|value| (value, other.modulo));
+        }
+        extract_int(other).map(|value| (value, None))
+    }
+
+    fn binary_op(&self, other: &Bound<'_, PyAny>, operation: Operation, reflected: bool) -> PyResult<PyObject> {
+        let py = other.py();
+        let Some((other, other_modulo)) = Self::operand(other) else {
+            return Ok(py.NotImplemented());
+        };
+        let value = self.get_value()?;
+        let modulo = narrowest(self.modulo, other_modulo);
+        let (left, right) = if reflected { (other, value) } else { (value, other) };
+        let result = checked(apply(operation, &left, &right)?, Signedness::Signed, modulo)?;
+        Ok(Integer { inner: NadaValue::new_integer(result), modulo }.into_py(py))
     }
 }
 
@@ -121,8 +284,11 @@ impl TryFrom<NadaValue<Clear>> for Integer {
 impl Integer {
     /// Returns a new Integer.
     #[new]
-    fn new(value: BigInt) -> Integer {
-        Integer { inner: NadaValue::new_integer(value) }
+    #[pyo3(signature = (value, prime_bits=None))]
+    fn new(value: BigInt, prime_bits: Option<u32>) -> PyResult<Integer> {
+        let modulo = chosen_modulo(prime_bits)?;
+        let value = if modulo.is_some() { checked(value, Signedness::Signed, modulo)? } else { value };
+        Ok(Integer { inner: NadaValue::new_integer(value), modulo })
     }
 
     #[getter]
@@ -130,12 +296,77 @@ impl Integer {
         Ok(self.inner.as_integer().ok_or_else(|| PyValueError::new_err("expected integer"))?.clone().into())
     }
 
-    #[setter]
-    fn set_value(&mut self, value: BigInt) {
-        self.inner = NadaValue::new_integer(value);
+    /// The size in bits of the safe prime chosen for this value, if any.
+    #[getter]
+    fn get_prime_bits(&self) -> Option<u32> {
+        self.modulo.map(prime_bits)
     }
 
     fn __repr__(&self) -> String {
         self.inner.to_string()
     }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (BigInt, Option<u32>))> {
+        Ok((py.get_type_bound::<Integer>().into_any().unbind(), (self.get_value()?, self.get_prime_bits())))
+    }
+
+    fn __add__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Add, false)
+    }
+
+    fn __radd__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Add, true)
+    }
+
+    fn __sub__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Sub, false)
+    }
+
+    fn __rsub__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Sub, true)
+    }
+
+    fn __mul__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mul, false)
+    }
+
+    fn __rmul__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mul, true)
+    }
+
+    fn __floordiv__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::FloorDiv, false)
+    }
+
+    fn __rfloordiv__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::FloorDiv, true)
+    }
+
+    fn __mod__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mod, false)
+    }
+
+    fn __rmod__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mod, true)
+    }
+
+    fn __int__(&self) -> PyResult<BigInt> {
+        self.get_value()
+    }
+
+    fn __index__(&self) -> PyResult<BigInt> {
+        self.get_value()
+    }
+
+    fn __hash__(&self) -> PyResult<u64> {
+        Ok(hash_value("Integer", &self.get_value()?))
+    }
+
+    fn __richcmp__(&self, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<PyObject> {
+        let py = other.py();
+        let Ok(other) = other.extract::<Integer>() else {
+            return Ok(py.NotImplemented());
+        };
+        Ok(compare(&self.get_value()?, &other.get_value()?, op).into_py(py))
+    }
 }
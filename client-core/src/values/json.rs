@@ -0,0 +1,350 @@
+//! Typed JSON import and export of cleartext values.
+//!
+//! Values are exported as a document of the form:
+//!
+//! ```json
+//! {"version": 1, "values": {"my_value": {"type": "SecretInteger", "bits": 64, "value": "-5"}}}
+//! ```
+//!
+//! Every value carries a `type` tag naming its Nada type. Integers are encoded as decimal strings so no precision
+//! is lost, along with a `bits` tag holding the size of the smallest safe prime (64, 128 or 256 bits) they can be
+//! masked with, which they're checked against on import. Byte strings (blobs, keys, messages, store ids) are hex
+//! encoded. Compound values carry the types needed to rebuild them: arrays include their `inner_type`, which allows
+//! empty arrays to round trip.
+
+use crate::{
+    ranges::{modulo_from_prime_bits, prime_bits, signed_range, unsigned_range},
+    values::{
+        ecdsa_signature::parse_scalar, encoding::MAX_ARRAY_SIZE, nada_values_clear_to_pydict,
+        pydict_to_nada_values_clear, secret::wipe,
+    },
+};
+use indexmap::IndexMap;
+use nillion_client_core::{
+    generic_ec::Scalar,
+    privatekey::ThresholdPrivateKey,
+    signature,
+    values::{BigInt, BigUint, Clear, EncodedModulo, NadaType, NadaValue},
+};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// The current version of the JSON document format.
+const VERSION: u64 = 1;
+
+/// Serialize a set of values into a typed JSON document.
+#[pyfunction]
+#[pyo3(signature = (values, indent=false))]
+pub(crate) fn values_to_json(values: &Bound<'_, PyDict>, indent: bool) -> PyResult<String> {
+    let mut values = pydict_to_nada_values_clear(values, None)?;
+    let mut names = values.keys().cloned().collect::<Vec<_>>();
+    names.sort();
+    let output = names
+        .into_iter()
+        .map(|name| Ok((name.clone(), value_to_json(&values[&name])?)))
+        .collect::<PyResult<Map<_, _>>>();
+    values.values_mut().for_each(wipe);
+    let output = output?;
+    let document = json!({ "version": VERSION, "values": output });
+    let document = if indent { serde_json::to_string_pretty(&document) } else { serde_json::to_string(&document) };
+    document.map_err(|e| PyValueError::new_err(format!("failed to serialize values: {e}")))
+}
+
+/// Deserialize a set of values from a typed JSON document produced by `values_to_json`.
+#[pyfunction]
+pub(crate) fn values_from_json<'py>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, PyDict>> {
+    let document: Value =
+        serde_json::from_str(json).map_err(|e| PyValueError::new_err(format!("invalid json document: {e}")))?;
+    match document.get("version").and_then(Value::as_u64) {
+        Some(VERSION) => (),
+        Some(version) => return Err(PyValueError::new_err(format!("unsupported document version: {version}"))),
+        None => return Err(PyValueError::new_err("document has no version")),
+    };
+    let values = document
+        .get("values")
+        .and_then(Value::as_object)
+        .ok_or_else(|| PyValueError::new_err("document has no values"))?;
+    let values = values
+        .iter()
+        .map(|(name, value)| {
+            let value = value_from_json(value).map_err(|e| PyValueError::new_err(format!("value '{name}': {e}")))?;
+            Ok((name.clone(), value))
+        })
+        .collect::<PyResult<HashMap<_, _>>>()?;
+    nada_values_clear_to_pydict(py, values)
+}
+
+fn value_to_json(value: &NadaValue<Clear>) -> PyResult<Value> {
+    let node = match value {
+        NadaValue::Integer(value) => integer("Integer", BigInt::from(value.clone()), true)?,
+        NadaValue::SecretInteger(value) => integer("SecretInteger", BigInt::from(value.clone()), true)?,
+        NadaValue::UnsignedInteger(value) => integer("UnsignedInteger", BigUint::from(value.clone()).into(), false)?,
+        NadaValue::SecretUnsignedInteger(value) => {
+            integer("SecretUnsignedInteger", BigUint::from(value.clone()).into(), false)?
+        }
+        NadaValue::Boolean(value) => tagged("Boolean", *value),
+        NadaValue::SecretBoolean(value) => tagged("SecretBoolean", *value),
+        NadaValue::SecretBlob(value) => tagged("SecretBlob", hex::encode(value)),
+        NadaValue::EcdsaPrivateKey(key) => tagged("EcdsaPrivateKey", hex::encode(key.clone().to_be_bytes())),
+        NadaValue::EcdsaDigestMessage(digest) => tagged("EcdsaDigestMessage", hex::encode(digest)),
+        NadaValue::EcdsaSignature(signature::EcdsaSignature { r, s }) => tagged(
+            "EcdsaSignature",
+            json!({ "r": hex::encode(Scalar::to_be_bytes(r)), "s": hex::encode(Scalar::to_be_bytes(s)) }),
+        ),
+        NadaValue::EcdsaPublicKey(key) => tagged("EcdsaPublicKey", hex::encode(key.0)),
+        NadaValue::StoreId(store_id) => tagged("StoreId", hex::encode(store_id)),
+        NadaValue::EddsaPrivateKey(key) => tagged("EddsaPrivateKey", hex::encode(key.clone().to_le_bytes())),
+        NadaValue::EddsaPublicKey(key) => tagged("EddsaPublicKey", hex::encode(key)),
+        NadaValue::EddsaSignature(signature::EddsaSignature { signature }) => tagged(
+            "EddsaSignature",
+            json!({ "r": hex::encode(signature.r.to_bytes()), "z": hex::encode(Scalar::to_le_bytes(&signature.z)) }),
+        ),
+        NadaValue::EddsaMessage(message) => tagged("EddsaMessage", hex::encode(message)),
+        NadaValue::Array { inner_type, values } => {
+            let values = values.iter().map(value_to_json).collect::<PyResult<Vec<_>>>()?;
+            json!({ "type": "Array", "inner_type": type_to_json(inner_type)?, "value": values })
+        }
+        NadaValue::Tuple { left, right } => tagged("Tuple", vec![value_to_json(left)?, value_to_json(right)?]),
+        NadaValue::NTuple { values } => {
+            tagged("NTuple", values.iter().map(value_to_json).collect::<PyResult<Vec<_>>>()?)
+        }
+        NadaValue::Object { values } => {
+            let mut fields = Map::new();
+            for (name, value) in values {
+                fields.insert(name.clone(), value_to_json(value)?);
+            }
+            tagged("Object", fields)
+        }
+        NadaValue::ShamirShareInteger(_)
+        | NadaValue::ShamirShareUnsignedInteger(_)
+        | NadaValue::ShamirShareBoolean(_) => {
+            return Err(PyValueError::new_err("shares can't be exported as cleartext values"));
+        }
+    };
+    Ok(node)
+}
+
+fn tagged(type_name: &str, value: impl Into<Value>) -> Value {
+    json!({ "type": type_name, "value": value.into() })
+}
+
+/// The moduli integers can be masked with, from the smallest prime to the largest.
+const MODULI: [EncodedModulo; 3] =
+    [EncodedModulo::U64SafePrime, EncodedModulo::U128SafePrime, EncodedModulo::U256SafePrime];
+
+fn integer(type_name: &str, value: BigInt, signed: bool) -> PyResult<Value> {
+    let modulo = MODULI.into_iter().find(|modulo| fits(&value, *modulo, signed)).ok_or_else(|| {
+        PyValueError::new_err(format!("{type_name} can't be represented with any of the supported primes"))
+    })?;
+    Ok(json!({ "type": type_name, "bits": prime_bits(modulo), "value": value.to_string() }))
+}
+
+fn fits(value: &BigInt, modulo: EncodedModulo, signed: bool) -> bool {
+    let (min, max) = if signed { signed_range(modulo) } else { unsigned_range(modulo) };
+    &min <= value && value <= &max
+}
+
+fn type_to_json(nada_type: &NadaType) -> PyResult<Value> {
+    let name = match nada_type {
+        NadaType::Integer => "Integer",
+        NadaType::UnsignedInteger => "UnsignedInteger",
+        NadaType::Boolean => "Boolean",
+        NadaType::SecretInteger => "SecretInteger",
+        NadaType::SecretUnsignedInteger => "SecretUnsignedInteger",
+        NadaType::SecretBoolean => "SecretBoolean",
+        NadaType::SecretBlob => "SecretBlob",
+        NadaType::EcdsaPrivateKey => "EcdsaPrivateKey",
+        NadaType::EcdsaDigestMessage => "EcdsaDigestMessage",
+        NadaType::EcdsaSignature => "EcdsaSignature",
+        NadaType::EcdsaPublicKey => "EcdsaPublicKey",
+        NadaType::StoreId => "StoreId",
+        NadaType::EddsaPrivateKey => "EddsaPrivateKey",
+        NadaType::EddsaPublicKey => "EddsaPublicKey",
+        NadaType::EddsaSignature => "EddsaSignature",
+        NadaType::EddsaMessage => "EddsaMessage",
+        NadaType::Array { inner_type, size } => {
+            return Ok(json!({ "type": "Array", "inner_type": type_to_json(inner_type)?, "size": size }));
+        }
+        NadaType::Tuple { left_type, right_type } => {
+            return Ok(json!({
+                "type": "Tuple",
+                "left_type": type_to_json(left_type)?,
+                "right_type": type_to_json(right_type)?,
+            }));
+        }
+        NadaType::NTuple { types } => {
+            let types = types.iter().map(type_to_json).collect::<PyResult<Vec<_>>>()?;
+            return Ok(json!({ "type": "NTuple", "types": types }));
+        }
+        NadaType::Object { types } => {
+            let mut fields = Map::new();
+            for (name, nada_type) in types {
+                fields.insert(name.clone(), type_to_json(nada_type)?);
+            }
+            return Ok(json!({ "type": "Object", "types": fields }));
+        }
+        NadaType::ShamirShareInteger | NadaType::ShamirShareUnsignedInteger | NadaType::ShamirShareBoolean => {
+            return Err(PyValueError::new_err(format!("type {nada_type} can't be exported as a cleartext type")));
+        }
+    };
+    Ok(json!({ "type": name }))
+}
+
+fn type_from_json(node: &Value) -> Result<NadaType, String> {
+    let nada_type = match type_tag(node)? {
+        "Integer" => NadaType::Integer,
+        "UnsignedInteger" => NadaType::UnsignedInteger,
+        "Boolean" => NadaType::Boolean,
+        "SecretInteger" => NadaType::SecretInteger,
+        "SecretUnsignedInteger" => NadaType::SecretUnsignedInteger,
+        "SecretBoolean" => NadaType::SecretBoolean,
+        "SecretBlob" => NadaType::SecretBlob,
+        "EcdsaPrivateKey" => NadaType::EcdsaPrivateKey,
+        "EcdsaDigestMessage" => NadaType::EcdsaDigestMessage,
+        "EcdsaSignature" => NadaType::EcdsaSignature,
+        "EcdsaPublicKey" => NadaType::EcdsaPublicKey,
+        "StoreId" => NadaType::StoreId,
+        "EddsaPrivateKey" => NadaType::EddsaPrivateKey,
+        "EddsaPublicKey" => NadaType::EddsaPublicKey,
+        "EddsaSignature" => NadaType::EddsaSignature,
+        "EddsaMessage" => NadaType::EddsaMessage,
+        "Array" => {
+            let inner_type = type_from_json(field(node, "inner_type")?)?;
+            let size = field(node, "size")?.as_u64().ok_or("array size must be an unsigned integer")?;
+            let size = usize::try_from(size)
+                .ok()
+                .filter(|size| *size <= MAX_ARRAY_SIZE)
+                .ok_or_else(|| format!("array size {size} is larger than the maximum of {MAX_ARRAY_SIZE}"))?;
+            NadaType::Array { inner_type: Box::new(inner_type), size }
+        }
+        "Tuple" => NadaType::Tuple {
+            left_type: Box::new(type_from_json(field(node, "left_type")?)?),
+            right_type: Box::new(type_from_json(field(node, "right_type")?)?),
+        },
+        "NTuple" => NadaType::NTuple {
+            types: field(node, "types")?
+                .as_array()
+                .ok_or("ntuple types must be a list")?
+                .iter()
+                .map(type_from_json)
+                .collect::<Result<_, _>>()?,
+        },
+        "Object" => NadaType::Object {
+            types: field(node, "types")?
+                .as_object()
+                .ok_or("object types must be an object")?
+                .iter()
+                .map(|(name, t)| Ok((name.clone(), type_from_json(t)?)))
+                .collect::<Result<IndexMap<_, _>, String>>()?,
+        },
+        other => return Err(format!("unknown type '{other}'")),
+    };
+    Ok(nada_type)
+}
+
+fn value_from_json(node: &Value) -> Result<NadaValue<Clear>, String> {
+    let value = field(node, "value")?;
+    let output = match type_tag(node)? {
+        "Integer" => NadaValue::new_integer(parse_integer(node, true)?),
+        "SecretInteger" => NadaValue::new_secret_integer(parse_integer(node, true)?),
+        "UnsignedInteger" => NadaValue::new_unsigned_integer(parse_unsigned_integer(node)?),
+        "SecretUnsignedInteger" => NadaValue::new_secret_unsigned_integer(parse_unsigned_integer(node)?),
+        "Boolean" => NadaValue::new_boolean(value.as_bool().ok_or("expected a boolean")?),
+        "SecretBoolean" => NadaValue::new_secret_boolean(value.as_bool().ok_or("expected a boolean")?),
+        "SecretBlob" => NadaValue::new_secret_blob(parse_hex(value)?),
+        "EcdsaPrivateKey" => NadaValue::new_ecdsa_private_key(
+            ThresholdPrivateKey::from_be_bytes(&parse_hex(value)?).map_err(|_| "invalid ecdsa private key")?,
+        ),
+        "EcdsaDigestMessage" => NadaValue::new_ecdsa_digest_message(parse_hex_array(value)?),
+        "EcdsaSignature" => {
+            let r = parse_scalar(&parse_hex(field(value, "r")?)?, "r").map_err(|e| e.to_string())?;
+            let s = parse_scalar(&parse_hex(field(value, "s")?)?, "s").map_err(|e| e.to_string())?;
+            NadaValue::new_ecdsa_signature(signature::EcdsaSignature { r, s })
+        }
+        "EcdsaPublicKey" => NadaValue::new_ecdsa_public_key(parse_hex_array(value)?),
+        "StoreId" => NadaValue::new_store_id(parse_hex_array(value)?),
+        "EddsaPrivateKey" => NadaValue::new_eddsa_private_key(
+            ThresholdPrivateKey::from_le_bytes(&parse_hex(value)?).map_err(|_| "invalid eddsa private key")?,
+        ),
+        "EddsaPublicKey" => NadaValue::new_eddsa_public_key(parse_hex_array(value)?),
+        "EddsaSignature" => {
+            let r = parse_hex(field(value, "r")?)?;
+            let z = parse_hex(field(value, "z")?)?;
+            NadaValue::new_eddsa_signature(
+                signature::EddsaSignature::from_components_bytes(&r, &z).map_err(|e| e.to_string())?,
+            )
+        }
+        "EddsaMessage" => NadaValue::new_eddsa_message(parse_hex(value)?),
+        "Array" => {
+            let inner_type = type_from_json(field(node, "inner_type")?)?;
+            let values = parse_list(value)?;
+            NadaValue::new_array(inner_type, values).map_err(|e| e.to_string())?
+        }
+        "Tuple" => {
+            let values = parse_list(value)?;
+            let [left, right]: [NadaValue<Clear>; 2] =
+                values.try_into().map_err(|_| "tuple must have exactly two elements")?;
+            NadaValue::new_tuple(left, right).map_err(|e| e.to_string())?
+        }
+        "NTuple" => NadaValue::new_n_tuple(parse_list(value)?).map_err(|e| e.to_string())?,
+        "Object" => {
+            let values = value
+                .as_object()
+                .ok_or("expected an object")?
+                .iter()
+                .map(|(name, v)| Ok((name.clone(), value_from_json(v).map_err(|e| format!("field '{name}': {e}"))?)))
+                .collect::<Result<IndexMap<_, _>, String>>()?;
+            NadaValue::new_object(values).map_err(|e| e.to_string())?
+        }
+        other => return Err(format!("unknown type '{other}'")),
+    };
+    Ok(output)
+}
+
+fn type_tag(node: &Value) -> Result<&str, String> {
+    field(node, "type")?.as_str().ok_or_else(|| "type tag must be a string".to_string())
+}
+
+fn field<'a>(node: &'a Value, name: &str) -> Result<&'a Value, String> {
+    node.get(name).ok_or_else(|| format!("missing field '{name}'"))
+}
+
+/// Parse an integer node, checking that its value fits the width in its `bits` tag.
+fn parse_integer(node: &Value, signed: bool) -> Result<BigInt, String> {
+    let value = field(node, "value")?
+        .as_str()
+        .and_then(|s| s.parse::<BigInt>().ok())
+        .ok_or("expected an integer encoded as a string")?;
+    let bits =
+        field(node, "bits")?.as_u64().and_then(|bits| u32::try_from(bits).ok()).ok_or("invalid integer width")?;
+    let modulo = modulo_from_prime_bits(bits).map_err(|_| format!("unsupported integer width: {bits} bits"))?;
+    if !fits(&value, modulo, signed) {
+        return Err(format!("integer can't be represented with a {bits} bit prime"));
+    }
+    Ok(value)
+}
+
+fn parse_unsigned_integer(node: &Value) -> Result<BigUint, String> {
+    // Unsigned ranges start at zero, so the sign is never negative.
+    let (_, magnitude) = parse_integer(node, false)?.into_parts();
+    Ok(magnitude)
+}
+
+fn parse_hex(value: &Value) -> Result<Vec<u8>, String> {
+    hex::decode(value.as_str().ok_or("expected a hex string")?).map_err(|e| format!("invalid hex string: {e}"))
+}
+
+fn parse_hex_array<const N: usize>(value: &Value) -> Result<[u8; N], String> {
+    parse_hex(value)?.try_into().map_err(|_| format!("expected exactly {N} bytes"))
+}
+
+fn parse_list(value: &Value) -> Result<Vec<NadaValue<Clear>>, String> {
+    value
+        .as_array()
+        .ok_or("expected a list")?
+        .iter()
+        .enumerate()
+        .map(|(i, v)| value_from_json(v).map_err(|e| format!("element {i}: {e}")))
+        .collect()
+}
@@ -2,6 +2,7 @@ use self::{
     array::Array,
     blob::SecretBlob,
     bool::{Boolean, SecretBoolean},
+    buffer::is_numeric_buffer,
     ecdsa_digest_message::EcdsaDigestMessage,
     ecdsa_private_key::EcdsaPrivateKey,
     ecdsa_public_key::EcdsaPublicKey,
@@ -11,16 +12,29 @@ use self::{
     eddsa_public_key::EddsaPublicKey,
     eddsa_signature::EddsaSignature,
     integer::{Integer, SecretInteger},
+    nada_type::NadaType,
+    object::Object,
+    rational::{Rational, SecretRational},
+    schema::NadaValuesSchema,
+    secret::wipe,
     store_id::StoreId,
+    tuple::{NTuple, Tuple},
     unsigned_integer::{SecretUnsignedInteger, UnsignedInteger},
 };
 use nillion_client_core::values::{Clear, NadaValue};
-use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyBytes, PyDict},
+};
 use std::collections::HashMap;
+use zeroize::Zeroizing;
 
+pub(crate) mod arithmetic;
 pub mod array;
 pub mod blob;
 pub mod bool;
+pub(crate) mod buffer;
 pub mod ecdsa_digest_message;
 pub mod ecdsa_private_key;
 pub mod ecdsa_public_key;
@@ -29,8 +43,17 @@ pub mod eddsa_message;
 pub mod eddsa_private_key;
 pub mod eddsa_public_key;
 pub mod eddsa_signature;
+pub(crate) mod encoding;
+pub(crate) mod envelope;
 pub mod integer;
+pub(crate) mod json;
+pub mod nada_type;
+pub mod object;
+pub mod rational;
+pub mod schema;
+pub(crate) mod secret;
 pub mod store_id;
+pub mod tuple;
 pub mod unsigned_integer;
 
 pub(crate) fn nada_value_clear_to_pyobject(py: Python<'_>, value: NadaValue<Clear>) -> PyResult<PyObject> {
@@ -58,10 +81,10 @@ pub(crate) fn nada_value_clear_to_pyobject(py: Python<'_>, value: NadaValue<Clea
         NadaValue::EddsaPublicKey(value) => EddsaPublicKey::try_from(NadaValue::EddsaPublicKey(value))?.into_py(py),
         NadaValue::EddsaSignature(value) => EddsaSignature::try_from(NadaValue::EddsaSignature(value))?.into_py(py),
         NadaValue::EddsaMessage(value) => EddsaMessage::try_from(NadaValue::EddsaMessage(value))?.into_py(py),
-        NadaValue::Tuple { .. }
-        | NadaValue::NTuple { .. }
-        | NadaValue::Object { .. }
-        | NadaValue::ShamirShareInteger(_)
+        NadaValue::Tuple { left, right } => Tuple::try_from(NadaValue::Tuple { left, right })?.into_py(py),
+        NadaValue::NTuple { values } => NTuple::try_from(NadaValue::NTuple { values })?.into_py(py),
+        NadaValue::Object { values } => Object::try_from(NadaValue::Object { values })?.into_py(py),
+        NadaValue::ShamirShareInteger(_)
         | NadaValue::ShamirShareUnsignedInteger(_)
         | NadaValue::ShamirShareBoolean(_) => {
             Err(PyValueError::new_err("Unsupported NadaValue variant for conversion to PyObject"))?
@@ -70,21 +93,25 @@ pub(crate) fn nada_value_clear_to_pyobject(py: Python<'_>, value: NadaValue<Clea
     Ok(object)
 }
 
+/// Convert a value object into the value it holds.
+///
+/// Secret values are wiped when their object is dropped, but the value returned is a copy that callers need to wipe
+/// once they're done with it, unless they hand it over to another value object or to the masker.
 fn pyany_to_nada_value_clear(value: Bound<PyAny>) -> Result<NadaValue<Clear>, PyErr> {
     let value = if let Ok(value) = value.extract::<Integer>() {
         value.inner
-    } else if let Ok(value) = value.extract::<SecretInteger>() {
-        value.inner
+    } else if let Ok(value) = value.downcast::<SecretInteger>() {
+        value.borrow().inner.clone()
     } else if let Ok(value) = value.extract::<UnsignedInteger>() {
         value.inner
-    } else if let Ok(value) = value.extract::<SecretUnsignedInteger>() {
-        value.inner
+    } else if let Ok(value) = value.downcast::<SecretUnsignedInteger>() {
+        value.borrow().inner.clone()
     } else if let Ok(value) = value.extract::<Boolean>() {
         value.inner
-    } else if let Ok(value) = value.extract::<SecretBoolean>() {
-        value.inner
-    } else if let Ok(value) = value.extract::<SecretBlob>() {
-        value.inner
+    } else if let Ok(value) = value.downcast::<SecretBoolean>() {
+        value.borrow().inner.clone()
+    } else if let Ok(value) = value.downcast::<SecretBlob>() {
+        value.borrow().inner.clone()
     } else if let Ok(value) = value.extract::<EcdsaPrivateKey>() {
         value.inner
     } else if let Ok(value) = value.extract::<EcdsaDigestMessage>() {
@@ -95,8 +122,8 @@ fn pyany_to_nada_value_clear(value: Bound<PyAny>) -> Result<NadaValue<Clear>, Py
         value.inner
     } else if let Ok(value) = value.extract::<StoreId>() {
         value.inner
-    } else if let Ok(value) = value.extract::<Array>() {
-        value.inner
+    } else if let Ok(value) = value.downcast::<Array>() {
+        value.borrow().inner.clone()
     } else if let Ok(value) = value.extract::<EddsaPrivateKey>() {
         value.inner
     } else if let Ok(value) = value.extract::<EddsaPublicKey>() {
@@ -105,21 +132,55 @@ fn pyany_to_nada_value_clear(value: Bound<PyAny>) -> Result<NadaValue<Clear>, Py
         value.inner
     } else if let Ok(value) = value.extract::<EddsaMessage>() {
         value.inner
+    } else if let Ok(value) = value.downcast::<Tuple>() {
+        value.borrow().inner.clone()
+    } else if let Ok(value) = value.downcast::<NTuple>() {
+        value.borrow().inner.clone()
+    } else if let Ok(value) = value.downcast::<Object>() {
+        value.borrow().inner.clone()
+    } else if let Ok(value) = value.downcast::<SecretRational>() {
+        value.borrow().inner.clone()
+    } else if let Ok(value) = value.extract::<Rational>() {
+        value.inner
     } else {
         Err(PyValueError::new_err("Unsupported NadaValue variant for conversion to PyObject"))?
     };
     Ok(value)
 }
 
-pub(crate) fn pydict_to_nada_values_clear(values: &Bound<'_, PyDict>) -> PyResult<HashMap<String, NadaValue<Clear>>> {
-    values
-        .into_iter()
-        .map(|(key, value)| {
-            let key = key.extract::<String>()?;
-            let value = pyany_to_nada_value_clear(value)?;
+pub(crate) fn pydict_to_nada_values_clear(
+    values: &Bound<'_, PyDict>,
+    schema: Option<&NadaValuesSchema>,
+) -> PyResult<HashMap<String, NadaValue<Clear>>> {
+    if let Some(schema) = schema {
+        return schema.coerce_values(values).map_err(schema::coercion_error);
+    }
+    let mut output = HashMap::new();
+    for (key, value) in values {
+        let value = key.extract::<String>().and_then(|key| {
+            let value = pyany_to_nada_value_clear(value.clone()).map_err(|e| {
+                // Whether a numeric buffer is secret or signed can't be guessed, so it has to be wrapped or declared.
+                if is_numeric_buffer(&value) {
+                    PyTypeError::new_err(format!(
+                        "value '{key}' is a buffer, wrap it with `Array.from_numpy` or declare it as an array in a schema"
+                    ))
+                } else {
+                    e
+                }
+            })?;
             Ok((key, value))
-        })
-        .collect::<Result<HashMap<_, _>, _>>()
+        });
+        match value {
+            Ok((key, value)) => {
+                output.insert(key, value);
+            }
+            Err(e) => {
+                output.values_mut().for_each(wipe);
+                return Err(e);
+            }
+        }
+    }
+    Ok(output)
 }
 
 pub fn nada_values_clear_to_pydict(
@@ -133,7 +194,40 @@ pub fn nada_values_clear_to_pydict(
     Ok(res)
 }
 
-pub fn add_module(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+/// Build the `__reduce__` tuple used to pickle a value class.
+///
+/// Values are pickled as their binary encoding and rebuilt through [`nada_value_from_bytes`].
+pub(crate) fn reduce_nada_value(py: Python<'_>, value: &NadaValue<Clear>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+    let constructor = py.import_bound("nillion_client_core")?.getattr("nada_value_from_bytes")?;
+    let encoded = Zeroizing::new(encoding::encode_value(value)?);
+    let bytes = PyBytes::new_bound(py, &encoded).unbind();
+    Ok((constructor.unbind(), (bytes,)))
+}
+
+/// Encode a value into its compact binary representation.
+#[pyfunction]
+fn nada_value_to_bytes<'py>(py: Python<'py>, value: Bound<'py, PyAny>) -> PyResult<Bound<'py, PyBytes>> {
+    let mut value = pyany_to_nada_value_clear(value)?;
+    let bytes = encoding::encode_value(&value).map(Zeroizing::new);
+    wipe(&mut value);
+    Ok(PyBytes::new_bound(py, &bytes?))
+}
+
+/// Decode a value from the binary representation produced by `nada_value_to_bytes`.
+#[pyfunction]
+fn nada_value_from_bytes(py: Python<'_>, bytes: &[u8]) -> PyResult<PyObject> {
+    nada_value_clear_to_pyobject(py, encoding::decode_value(bytes)?)
+}
+
+/// Decode a type from the binary representation used when pickling a `NadaType`.
+#[pyfunction]
+#[pyo3(signature = (bytes, rational_precision=None))]
+fn nada_type_from_bytes(bytes: &[u8], rational_precision: Option<u32>) -> PyResult<NadaType> {
+    let rational_precision = rational_precision.map(rational::check_precision_bound).transpose()?;
+    Ok(NadaType { inner: encoding::decode_type(bytes)?, rational_precision })
+}
+
+pub fn add_module(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<SecretUnsignedInteger>()?;
     m.add_class::<SecretInteger>()?;
     m.add_class::<SecretBoolean>()?;
@@ -151,5 +245,18 @@ pub fn add_module(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<EddsaPublicKey>()?;
     m.add_class::<EddsaSignature>()?;
     m.add_class::<EddsaMessage>()?;
+    m.add_class::<Tuple>()?;
+    m.add_class::<NTuple>()?;
+    m.add_class::<Object>()?;
+    m.add_class::<SecretRational>()?;
+    m.add_class::<Rational>()?;
+    m.add_class::<NadaType>()?;
+    m.add_class::<NadaValuesSchema>()?;
+    m.add("BlobIntegrityError", py.get_type_bound::<envelope::BlobIntegrityError>())?;
+    m.add_function(wrap_pyfunction!(nada_value_to_bytes, m)?)?;
+    m.add_function(wrap_pyfunction!(nada_value_from_bytes, m)?)?;
+    m.add_function(wrap_pyfunction!(nada_type_from_bytes, m)?)?;
+    m.add_function(wrap_pyfunction!(json::values_to_json, m)?)?;
+    m.add_function(wrap_pyfunction!(json::values_from_json, m)?)?;
     Ok(())
 }
@@ -0,0 +1,231 @@
+//! Python bindings for nada types.
+
+use crate::values::{
+    encoding::encode_type,
+    rational::{check_precision_bound, DEFAULT_PRECISION},
+};
+use indexmap::IndexMap;
+use nillion_client_core::values::NadaType as T;
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyBytes, PyDict},
+};
+
+/// This is a :py:class:`NadaType` class used to describe the
+/// type of a cleartext value.
+///
+/// Instances are built through the static constructors, one per type.
+///
+/// Example
+/// -------
+///
+/// .. code-block:: py3
+///
+///     from nillion_client_core import NadaType
+///
+///     array_type = NadaType.array(NadaType.secret_integer(), 3)
+///
+///     print("The type is: ", array_type)
+///
+/// .. code-block:: text
+///
+///     >>> The type is: Array [SecretInteger:3]
+///
+/// Rationals are encoded as integers, so rational types are integer types that also carry the
+/// precision used to decode them. They can be used on their own or as the elements of an array.
+#[pyclass(eq, frozen)]
+#[derive(PartialEq, Clone)]
+pub struct NadaType {
+    pub(crate) inner: T,
+    /// The number of fractional bits of the rationals described by this type, if any.
+    pub(crate) rational_precision: Option<u32>,
+}
+
+impl From<T> for NadaType {
+    fn from(inner: T) -> Self {
+        Self { inner, rational_precision: None }
+    }
+}
+
+impl NadaType {
+    /// Ensure a type is not a rational type, as rationals can't be nested in tuples and objects.
+    fn not_rational(self) -> PyResult<T> {
+        match self.rational_precision {
+            Some(_) => Err(PyValueError::new_err("rational types can only be used on their own or as array elements")),
+            None => Ok(self.inner),
+        }
+    }
+
+    fn display(inner: &T, rational_precision: u32) -> String {
+        match inner {
+            T::Integer => format!("Rational({rational_precision})"),
+            T::Array { inner_type, size } => {
+                format!("Array [{}:{size}]", Self::display(inner_type, rational_precision))
+            }
+            _ => format!("SecretRational({rational_precision})"),
+        }
+    }
+}
+
+#[pymethods]
+impl NadaType {
+    /// A public integer.
+    #[staticmethod]
+    fn integer() -> Self {
+        T::Integer.into()
+    }
+
+    /// A public unsigned integer.
+    #[staticmethod]
+    fn unsigned_integer() -> Self {
+        T::UnsignedInteger.into()
+    }
+
+    /// A public boolean.
+    #[staticmethod]
+    fn boolean() -> Self {
+        T::Boolean.into()
+    }
+
+    /// A secret integer.
+    #[staticmethod]
+    fn secret_integer() -> Self {
+        T::SecretInteger.into()
+    }
+
+    /// A secret unsigned integer.
+    #[staticmethod]
+    fn secret_unsigned_integer() -> Self {
+        T::SecretUnsignedInteger.into()
+    }
+
+    /// A secret boolean.
+    #[staticmethod]
+    fn secret_boolean() -> Self {
+        T::SecretBoolean.into()
+    }
+
+    /// A public rational encoded with `precision` fractional bits, at most 254.
+    #[staticmethod]
+    #[pyo3(signature = (precision=DEFAULT_PRECISION))]
+    fn rational(precision: u32) -> PyResult<Self> {
+        Ok(Self { inner: T::Integer, rational_precision: Some(check_precision_bound(precision)?) })
+    }
+
+    /// A secret rational encoded with `precision` fractional bits, at most 254.
+    #[staticmethod]
+    #[pyo3(signature = (precision=DEFAULT_PRECISION))]
+    fn secret_rational(precision: u32) -> PyResult<Self> {
+        Ok(Self { inner: T::SecretInteger, rational_precision: Some(check_precision_bound(precision)?) })
+    }
+
+    /// A secret blob.
+    #[staticmethod]
+    fn secret_blob() -> Self {
+        T::SecretBlob.into()
+    }
+
+    /// An ecdsa private key.
+    #[staticmethod]
+    fn ecdsa_private_key() -> Self {
+        T::EcdsaPrivateKey.into()
+    }
+
+    /// An ecdsa digest message.
+    #[staticmethod]
+    fn ecdsa_digest_message() -> Self {
+        T::EcdsaDigestMessage.into()
+    }
+
+    /// An ecdsa signature.
+    #[staticmethod]
+    fn ecdsa_signature() -> Self {
+        T::EcdsaSignature.into()
+    }
+
+    /// An ecdsa public key.
+    #[staticmethod]
+    fn ecdsa_public_key() -> Self {
+        T::EcdsaPublicKey.into()
+    }
+
+    /// A store id.
+    #[staticmethod]
+    fn store_id() -> Self {
+        T::StoreId.into()
+    }
+
+    /// An eddsa private key.
+    #[staticmethod]
+    fn eddsa_private_key() -> Self {
+        T::EddsaPrivateKey.into()
+    }
+
+    /// An eddsa public key.
+    #[staticmethod]
+    fn eddsa_public_key() -> Self {
+        T::EddsaPublicKey.into()
+    }
+
+    /// An eddsa signature.
+    #[staticmethod]
+    fn eddsa_signature() -> Self {
+        T::EddsaSignature.into()
+    }
+
+    /// An eddsa message.
+    #[staticmethod]
+    fn eddsa_message() -> Self {
+        T::EddsaMessage.into()
+    }
+
+    /// An array of `size` elements of type `inner_type`.
+    #[staticmethod]
+    fn array(inner_type: NadaType, size: usize) -> Self {
+        let rational_precision = inner_type.rational_precision;
+        Self { inner: T::Array { inner_type: Box::new(inner_type.inner), size }, rational_precision }
+    }
+
+    /// A tuple of two elements.
+    #[staticmethod]
+    fn tuple(left: NadaType, right: NadaType) -> PyResult<Self> {
+        Ok(T::Tuple { left_type: Box::new(left.not_rational()?), right_type: Box::new(right.not_rational()?) }.into())
+    }
+
+    /// A tuple of any number of elements.
+    #[staticmethod]
+    fn ntuple(types: Vec<NadaType>) -> PyResult<Self> {
+        Ok(T::NTuple { types: types.into_iter().map(NadaType::not_rational).collect::<PyResult<_>>()? }.into())
+    }
+
+    /// An object with the given named fields.
+    #[staticmethod]
+    fn object(types: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let types = types
+            .into_iter()
+            .map(|(key, value)| Ok((key.extract::<String>()?, value.extract::<NadaType>()?.not_rational()?)))
+            .collect::<PyResult<IndexMap<_, _>>>()?;
+        Ok(T::Object { types }.into())
+    }
+
+    /// The number of fractional bits of the rationals described by this type, or `None` if this is
+    /// not a rational type.
+    #[getter]
+    fn get_rational_precision(&self) -> Option<u32> {
+        self.rational_precision
+    }
+
+    pub(crate) fn __repr__(&self) -> String {
+        match self.rational_precision {
+            Some(precision) => Self::display(&self.inner, precision),
+            None => self.inner.to_string(),
+        }
+    }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (Py<PyBytes>, Option<u32>))> {
+        let constructor = py.import_bound("nillion_client_core")?.getattr("nada_type_from_bytes")?;
+        let bytes = PyBytes::new_bound(py, &encode_type(&self.inner)?).unbind();
+        Ok((constructor.unbind(), (bytes, self.rational_precision)))
+    }
+}
@@ -0,0 +1,123 @@
+//! Python bindings for objects and utilities.
+
+use crate::values::{
+    nada_value_clear_to_pyobject, pyany_to_nada_value_clear, reduce_nada_value,
+    secret::{redacted_repr, wipe},
+};
+use indexmap::IndexMap;
+use nillion_client_core::values::{Clear, NadaValue};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyBytes, PyDict},
+};
+
+/// This is a :py:class:`Object` class used to
+/// encode a set of named elements.
+///
+/// Note: `__len__` method is implemented to allow
+/// getting the number of fields in the object. Field
+/// order is preserved.
+///
+/// Arguments
+/// ---------
+/// value : dict
+///     Dictionary of encoded elements keyed by field name.
+///
+/// Returns
+/// -------
+/// Object
+///     Instance of the :py:class:`Object` class.
+///
+/// Example
+/// -------
+///
+/// .. code-block:: py3
+///
+///     from nillion_client import Object, SecretInteger, Boolean
+///
+///     obj = Object({"age": SecretInteger(32), "active": Boolean(True)})
+///
+///     print("The object has fields: ", list(obj.value.keys()))
+///
+/// .. code-block:: text
+///
+///     >>> The object has fields: ['age', 'active']
+#[pyclass(eq)]
+#[derive(PartialEq, Clone)]
+pub struct Object {
+    pub(crate) inner: NadaValue<Clear>,
+}
+
+impl TryFrom<NadaValue<Clear>> for Object {
+    type Error = PyErr;
+
+    fn try_from(value: NadaValue<Clear>) -> Result<Self, Self::Error> {
+        matches!(value, NadaValue::Object { .. })
+            .then(|| Object { inner: value })
+            .ok_or_else(|| PyValueError::new_err("expected object"))
+    }
+}
+
+impl Drop for Object {
+    fn drop(&mut self) {
+        wipe(&mut self.inner);
+    }
+}
+
+impl Object {
+    fn fields(&self) -> PyResult<&IndexMap<String, NadaValue<Clear>>> {
+        match &self.inner {
+            NadaValue::Object { values } => Ok(values),
+            _ => Err(PyValueError::new_err("expected object")),
+        }
+    }
+}
+
+#[pymethods]
+impl Object {
+    /// Returns a new [`Object`].
+    #[new]
+    fn new(value: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let values = value
+            .into_iter()
+            .map(|(key, value)| Ok((key.extract::<String>()?, pyany_to_nada_value_clear(value)?)))
+            .collect::<Result<IndexMap<_, _>, PyErr>>()?;
+        Ok(Object { inner: NadaValue::new_object(values).map_err(|e| PyValueError::new_err(e.to_string()))? })
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        Ok(self.fields()?.len())
+    }
+
+    /// Getter method for the `value` inside a
+    /// :py:class:`Object` instance.
+    ///
+    /// Returns
+    /// -------
+    /// dict
+    ///     Dictionary of encoded elements keyed by field name.
+    #[getter]
+    fn get_value<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        for (key, value) in self.fields()? {
+            dict.set_item(key, nada_value_clear_to_pyobject(py, value.clone())?)?;
+        }
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        redacted_repr(&self.inner)
+    }
+
+    /// Returns the representation of this object including its secrets.
+    ///
+    /// The default representation redacts secrets so that they don't end up in logs or tracebacks.
+    fn reveal(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+        reduce_nada_value(py, &self.inner)
+    }
+}
@@ -0,0 +1,424 @@
+//! Python bindings for fixed-point rationals.
+//!
+//! Rationals are encoded as integers scaled by `2^precision`, where the precision is the number of fractional
+//! bits. They are masked as integers and decoded back into floats when unmasking with a schema that declares them.
+
+use crate::{
+    ranges::signed_range,
+    values::{
+        arithmetic::{compare, hash_value, ARITHMETIC_MODULO},
+        secret::{wipe, REDACTED},
+    },
+};
+use nillion_client_core::values::{BigInt, Clear, NadaType as T, NadaValue};
+use pyo3::{
+    exceptions::{PyOverflowError, PyTypeError, PyValueError},
+    prelude::*,
+    pyclass::CompareOp,
+    types::{PyBool, PyByteArray, PyBytes, PyFloat, PyString},
+};
+
+/// The default number of fractional bits used to encode a rational.
+pub(crate) const DEFAULT_PRECISION: u32 = 16;
+
+/// The largest number of fractional bits a rational can be encoded with.
+///
+/// With more fractional bits, not even 1 fits in the signed range of the 256 bit safe prime.
+pub(crate) const MAX_PRECISION: u32 = 254;
+
+/// Check that a precision is at most `MAX_PRECISION`, before anything is scaled by it.
+pub(crate) fn check_precision_bound(precision: u32) -> PyResult<u32> {
+    if precision > MAX_PRECISION {
+        return Err(PyValueError::new_err(format!("precision must be at most {MAX_PRECISION}, got {precision}")));
+    }
+    Ok(precision)
+}
+
+/// Check that a scaled integer fits in the signed range of the 256 bit safe prime.
+fn check_scaled_range(scaled: &BigInt, description: impl FnOnce() -> String) -> PyResult<()> {
+    let (min, max) = signed_range(ARITHMETIC_MODULO);
+    if *scaled < min || *scaled > max {
+        return Err(PyOverflowError::new_err(format!(
+            "{} is outside of the range [{min}, {max}] of the 256 bit safe prime",
+            description()
+        )));
+    }
+    Ok(())
+}
+
+/// Encode a `float`, `Decimal`, `Fraction` or `int` as an integer scaled by `2^precision`.
+///
+/// The value is converted exactly and then rounded to the nearest integer, with ties rounded to even like Python's
+/// `round`. The precision must be at most `MAX_PRECISION` and the result must fit in the signed range of the 256 bit
+/// safe prime.
+pub(crate) fn encode(value: &Bound<'_, PyAny>, precision: u32) -> PyResult<BigInt> {
+    check_precision_bound(precision)?;
+    if value.is_instance_of::<PyBool>()
+        || value.is_instance_of::<PyString>()
+        || value.is_instance_of::<PyBytes>()
+        || value.is_instance_of::<PyByteArray>()
+    {
+        return Err(PyTypeError::new_err(format!(
+            "expected float, Decimal, Fraction or int, got {}",
+            value.get_type().name()?
+        )));
+    }
+    let fraction = value.py().import_bound("fractions")?.getattr("Fraction")?.call1((value,))?;
+    let numerator = fraction.getattr("numerator")?.extract::<BigInt>()?;
+    let denominator = fraction.getattr("denominator")?.extract::<BigInt>()?;
+    let scaled = round_half_even(numerator << precision as usize, &denominator);
+    check_scaled_range(&scaled, || format!("{value} scaled by 2^{precision}"))?;
+    Ok(scaled)
+}
+
+/// Check a precision and an integer already scaled by `2^precision` the same way `encode` checks its result.
+fn check_scaled(scaled: BigInt, precision: u32) -> PyResult<BigInt> {
+    check_precision_bound(precision)?;
+    check_scaled_range(&scaled, || format!("scaled value {scaled}"))?;
+    Ok(scaled)
+}
+
+/// Decode an integer scaled by `2^precision` into the nearest float.
+pub(crate) fn decode(py: Python<'_>, scaled: &BigInt, precision: u32) -> PyResult<f64> {
+    let denominator = BigInt::from(1u32) << precision as usize;
+    // Integer true division in Python is correctly rounded, even for integers that don't fit in a float.
+    scaled.clone().into_py(py).call_method1(py, "__truediv__", (denominator,))?.extract(py)
+}
+
+/// Compare two rationals by their exact values, whatever their precisions.
+fn compare_rationals(left: &BigInt, left_precision: u32, right: &BigInt, right_precision: u32, op: CompareOp) -> bool {
+    compare(&(left << right_precision as usize), &(right << left_precision as usize), op)
+}
+
+/// Hash a rational by its exact value, so that rationals that compare equal hash the same whatever their precisions.
+fn hash_rational(class_name: &str, scaled: &BigInt, precision: u32) -> u64 {
+    // Dropping trailing zero bits gives every value a single representation, zero has none to keep.
+    let shift = scaled.trailing_zeros().map_or(precision, |zeros| zeros.min(u64::from(precision)) as u32);
+    hash_value(class_name, &(scaled >> shift, precision - shift))
+}
+
+fn round_half_even(numerator: BigInt, denominator: &BigInt) -> BigInt {
+    let zero = BigInt::default();
+    let mut quotient = &numerator / denominator;
+    let mut remainder = &numerator % denominator;
+    if remainder < zero {
+        quotient -= 1;
+        remainder += denominator;
+    }
+    let twice = remainder * 2;
+    if twice > *denominator || (twice == *denominator && &quotient % 2 != zero) {
+        quotient += 1;
+    }
+    quotient
+}
+
+/// Coerce a value declared as a rational in a schema.
+///
+/// Rationals are declared either directly or as the elements of an array, in which case the value must be a list or
+/// tuple of numbers.
+pub(crate) fn coerce(value: &Bound<'_, PyAny>, nada_type: &T, precision: u32) -> Result<NadaValue<Clear>, String> {
+    match nada_type {
+        T::Integer | T::SecretInteger => {
+            let secret = matches!(nada_type, T::SecretInteger);
+            let scaled = if let Ok(value) = value.extract::<SecretRational>() {
+                if !secret {
+                    return Err("expected rational, got secret rational".to_string());
+                }
+                check_precision(value.precision, precision)?;
+                value.scaled()
+            } else if let Ok(value) = value.extract::<Rational>() {
+                if secret {
+                    return Err("expected secret rational, got rational".to_string());
+                }
+                check_precision(value.precision, precision)?;
+                value.scaled()
+            } else {
+                encode(value, precision).map_err(|e| e.to_string())?
+            };
+            Ok(if secret { NadaValue::new_secret_integer(scaled) } else { NadaValue::new_integer(scaled) })
+        }
+        T::Array { inner_type, size } => {
+            let elements = value
+                .extract::<Vec<Bound<'_, PyAny>>>()
+                .map_err(|_| format!("expected list or tuple for array of {size} rationals"))?;
+            if elements.len() != *size {
+                return Err(format!("expected {size} elements, got {}", elements.len()));
+            }
+            let values = elements
+                .iter()
+                .enumerate()
+                .map(|(i, e)| coerce(e, inner_type, precision).map_err(|e| format!("element {i}: {e}")))
+                .collect::<Result<Vec<_>, _>>()?;
+            NadaValue::new_array(*inner_type.clone(), values).map_err(|e| e.to_string())
+        }
+        _ => Err(format!("{nada_type} can't hold rationals")),
+    }
+}
+
+fn check_precision(actual: u32, expected: u32) -> Result<(), String> {
+    if actual != expected {
+        return Err(format!("expected precision {expected}, got {actual}"));
+    }
+    Ok(())
+}
+
+/// Convert an unmasked value declared as a rational into a float, or a list of floats for arrays.
+pub(crate) fn decode_value(py: Python<'_>, value: &NadaValue<Clear>, precision: u32) -> PyResult<PyObject> {
+    match value {
+        NadaValue::Integer(value) | NadaValue::SecretInteger(value) => {
+            Ok(decode(py, &BigInt::from(value.clone()), precision)?.into_py(py))
+        }
+        NadaValue::Array { values, .. } => {
+            let values = values.iter().map(|v| decode_value(py, v, precision)).collect::<PyResult<Vec<_>>>()?;
+            Ok(values.into_py(py))
+        }
+        value => Err(PyValueError::new_err(format!("{} can't be decoded as a rational", value.to_type()))),
+    }
+}
+
+/// This is a :py:class:`SecretRational` class used to encode a secret as a fixed-point rational.
+///
+/// The value is stored as a secret integer scaled by ``2 ** precision`` and rounded to the nearest
+/// integer, so a precision of 16 represents multiples of ``1 / 65536``. Secret rationals are
+/// compared and hashed by their exact values, so secret rationals with different precisions can
+/// be equal, and are never equal to public rationals.
+///
+/// Arguments
+/// ---------
+/// value : float, Decimal, Fraction or int
+///     Value of the secret encoded element.
+/// precision : int, optional
+///     The number of fractional bits used to encode the value, at most 254. Defaults to 16.
+///
+/// Returns
+/// -------
+/// SecretRational
+///     Instance of the :py:class:`SecretRational` class.
+///
+/// Raises
+/// -------
+/// ValueError: precision too large
+///     Raises an error when the precision is larger than 254 bits.
+/// OverflowError: value out of range
+///     Raises an error when the encoded value is outside of the range of the 256 bit safe
+///     prime. Masking checks values against the masker's own prime.
+///
+/// Example
+/// -------
+///
+/// .. code-block:: py3
+///
+///     from nillion_client import SecretRational
+///
+///     sec_rational = SecretRational(1.5, precision=8)
+///
+///     print("The encoded secret is: ", sec_rational.scaled_value)
+///
+/// .. code-block:: text
+///
+///     >>> The encoded secret is:  384
+#[pyclass(frozen)]
+#[derive(PartialEq, Clone)]
+pub struct SecretRational {
+    pub(crate) inner: NadaValue<Clear>,
+    pub(crate) precision: u32,
+}
+
+impl Drop for SecretRational {
+    fn drop(&mut self) {
+        wipe(&mut self.inner);
+    }
+}
+
+impl SecretRational {
+    fn scaled(&self) -> BigInt {
+        self.inner.as_secret_integer().cloned().map(BigInt::from).unwrap_or_default()
+    }
+}
+
+#[pymethods]
+impl SecretRational {
+    /// Returns a new SecretRational.
+    #[new]
+    #[pyo3(signature = (value, precision=DEFAULT_PRECISION))]
+    fn new(value: &Bound<'_, PyAny>, precision: u32) -> PyResult<Self> {
+        Ok(SecretRational { inner: NadaValue::new_secret_integer(encode(value, precision)?), precision })
+    }
+
+    /// Build a secret rational from an integer already scaled by ``2 ** precision``.
+    #[staticmethod]
+    #[pyo3(signature = (scaled_value, precision=DEFAULT_PRECISION))]
+    fn from_scaled(scaled_value: BigInt, precision: u32) -> PyResult<Self> {
+        Ok(SecretRational { inner: NadaValue::new_secret_integer(check_scaled(scaled_value, precision)?), precision })
+    }
+
+    /// The value of this secret rational, rounded to the nearest float.
+    #[getter]
+    fn get_value(&self, py: Python<'_>) -> PyResult<f64> {
+        decode(py, &self.scaled(), self.precision)
+    }
+
+    /// The value of this secret rational as an integer scaled by ``2 ** precision``.
+    #[getter]
+    fn get_scaled_value(&self) -> BigInt {
+        self.scaled()
+    }
+
+    /// The number of fractional bits used to encode this secret rational.
+    #[getter]
+    fn get_precision(&self) -> u32 {
+        self.precision
+    }
+
+    fn __float__(&self, py: Python<'_>) -> PyResult<f64> {
+        self.get_value(py)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("SecretRational({REDACTED}, precision={})", self.precision)
+    }
+
+    /// Returns the representation of this secret rational including its secrets.
+    ///
+    /// The default representation redacts secrets so that they don't end up in logs or tracebacks.
+    fn reveal(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "SecretRational({}, precision={})",
+            PyFloat::new_bound(py, self.get_value(py)?).repr()?,
+            self.precision
+        ))
+    }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (BigInt, u32))> {
+        let constructor = py.get_type_bound::<SecretRational>().getattr("from_scaled")?;
+        Ok((constructor.unbind(), (self.scaled(), self.precision)))
+    }
+
+    fn __hash__(&self) -> u64 {
+        hash_rational("SecretRational", &self.scaled(), self.precision)
+    }
+
+    fn __richcmp__(&self, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<PyObject> {
+        let py = other.py();
+        let Ok(other) = other.extract::<SecretRational>() else {
+            return Ok(py.NotImplemented());
+        };
+        Ok(compare_rationals(&self.scaled(), self.precision, &other.scaled(), other.precision, op).into_py(py))
+    }
+}
+
+/// This is a :py:class:`Rational` class used to
+/// encode a public variable value as a fixed-point rational.
+///
+/// The value is stored as a public integer scaled by ``2 ** precision`` and rounded to the
+/// nearest integer, so a precision of 16 represents multiples of ``1 / 65536``. Rationals are
+/// compared and hashed by their exact values, so rationals with different precisions can be
+/// equal, and are never equal to secret rationals.
+///
+/// Arguments
+/// ---------
+/// value : float, Decimal, Fraction or int
+///     Value of the public encoded element.
+/// precision : int, optional
+///     The number of fractional bits used to encode the value, at most 254. Defaults to 16.
+///
+/// Returns
+/// -------
+/// Rational
+///     Instance of the :py:class:`Rational` class.
+///
+/// Raises
+/// -------
+/// ValueError: precision too large
+///     Raises an error when the precision is larger than 254 bits.
+/// OverflowError: value out of range
+///     Raises an error when the encoded value is outside of the range of the 256 bit safe
+///     prime. Masking checks values against the masker's own prime.
+///
+/// Example
+/// -------
+///
+/// .. code-block:: py3
+///
+///     from fractions import Fraction
+///     from nillion_client import Rational
+///
+///     pub_rational = Rational(Fraction(1, 3))
+///
+///     print("The public rational is: ", pub_rational.value)
+///
+/// .. code-block:: text
+///
+///     >>> The public rational is:  0.3333282470703125
+#[pyclass(frozen)]
+#[derive(PartialEq, Clone)]
+pub struct Rational {
+    pub(crate) inner: NadaValue<Clear>,
+    pub(crate) precision: u32,
+}
+
+impl Rational {
+    fn scaled(&self) -> BigInt {
+        self.inner.as_integer().cloned().map(BigInt::from).unwrap_or_default()
+    }
+}
+
+#[pymethods]
+impl Rational {
+    /// Returns a new Rational.
+    #[new]
+    #[pyo3(signature = (value, precision=DEFAULT_PRECISION))]
+    fn new(value: &Bound<'_, PyAny>, precision: u32) -> PyResult<Self> {
+        Ok(Rational { inner: NadaValue::new_integer(encode(value, precision)?), precision })
+    }
+
+    /// Build a rational from an integer already scaled by ``2 ** precision``.
+    #[staticmethod]
+    #[pyo3(signature = (scaled_value, precision=DEFAULT_PRECISION))]
+    fn from_scaled(scaled_value: BigInt, precision: u32) -> PyResult<Self> {
+        Ok(Rational { inner: NadaValue::new_integer(check_scaled(scaled_value, precision)?), precision })
+    }
+
+    /// The value of this rational, rounded to the nearest float.
+    #[getter]
+    fn get_value(&self, py: Python<'_>) -> PyResult<f64> {
+        decode(py, &self.scaled(), self.precision)
+    }
+
+    /// The value of this rational as an integer scaled by ``2 ** precision``.
+    #[getter]
+    fn get_scaled_value(&self) -> BigInt {
+        self.scaled()
+    }
+
+    /// The number of fractional bits used to encode this rational.
+    #[getter]
+    fn get_precision(&self) -> u32 {
+        self.precision
+    }
+
+    fn __float__(&self, py: Python<'_>) -> PyResult<f64> {
+        self.get_value(py)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!("Rational({}, precision={})", PyFloat::new_bound(py, self.get_value(py)?).repr()?, self.precision))
+    }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (BigInt, u32))> {
+        let constructor = py.get_type_bound::<Rational>().getattr("from_scaled")?;
+        Ok((constructor.unbind(), (self.scaled(), self.precision)))
+    }
+
+    fn __hash__(&self) -> u64 {
+        hash_rational("Rational", &self.scaled(), self.precision)
+    }
+
+    fn __richcmp__(&self, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<PyObject> {
+        let py = other.py();
+        let Ok(other) = other.extract::<Rational>() else {
+            return Ok(py.NotImplemented());
+        };
+        Ok(compare_rationals(&self.scaled(), self.precision, &other.scaled(), other.precision, op).into_py(py))
+    }
+}
@@ -0,0 +1,297 @@
+//! Schema driven coercion of plain Python values into Nada values.
+
+use crate::values::{
+    buffer::NumericBuffer, nada_type::NadaType, nada_value_clear_to_pyobject, nada_values_clear_to_pydict,
+    pyany_to_nada_value_clear, rational, secret::wipe,
+};
+use indexmap::IndexMap;
+use nillion_client_core::{
+    privatekey::ThresholdPrivateKey,
+    values::{BigInt, BigUint, Clear, NadaType as T, NadaValue},
+};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyBool, PyByteArray, PyBytes, PyDict},
+};
+use std::collections::HashMap;
+
+/// This is a :py:class:`NadaValuesSchema` class that maps value names to their :py:class:`NadaType`.
+///
+/// A schema allows providing plain Python values (`int`, `bool`, `bytes`, `list`, `tuple`, `dict`)
+/// instead of value classes when masking. Every plain value is coerced into the type declared for its
+/// name; value classes are accepted as long as their type matches the declared one.
+///
+/// Values declared with a rational type (see :py:meth:`NadaType.secret_rational`) accept
+/// `float`, `Decimal` and `Fraction` values, and are decoded back into floats when the schema
+/// is passed to :py:meth:`SecretMasker.unmask`.
+///
+/// Arguments
+/// ---------
+/// types : dict
+///     Dictionary of :py:class:`NadaType` keyed by value name.
+///
+/// Example
+/// -------
+///
+/// .. code-block:: py3
+///
+///     from nillion_client_core import NadaType, NadaValuesSchema
+///
+///     schema = NadaValuesSchema({
+///         "age": NadaType.secret_integer(),
+///         "scores": NadaType.array(NadaType.secret_unsigned_integer(), 3),
+///     })
+///     masked = masker.mask({"age": 32, "scores": [1, 2, 3]}, schema)
+#[pyclass]
+#[derive(Clone)]
+pub struct NadaValuesSchema {
+    pub(crate) types: HashMap<String, T>,
+    /// The precision of the values declared as rationals.
+    pub(crate) rational_precisions: HashMap<String, u32>,
+}
+
+impl NadaValuesSchema {
+    /// Coerce all values in the given dictionary, collecting the error for every key that doesn't fit.
+    pub(crate) fn coerce_values(
+        &self,
+        values: &Bound<'_, PyDict>,
+    ) -> Result<HashMap<String, NadaValue<Clear>>, HashMap<String, String>> {
+        let mut output = HashMap::new();
+        let mut errors = HashMap::new();
+        for (key, value) in values {
+            let key = match key.extract::<String>() {
+                Ok(key) => key,
+                Err(_) => {
+                    errors.insert(key.to_string(), "value name must be a string".to_string());
+                    continue;
+                }
+            };
+            let Some(nada_type) = self.types.get(&key) else {
+                errors.insert(key, "value is not declared in schema".to_string());
+                continue;
+            };
+            let value = match self.rational_precisions.get(&key) {
+                Some(precision) => rational::coerce(&value, nada_type, *precision),
+                None => coerce_value(&value, nada_type),
+            };
+            match value {
+                Ok(value) => {
+                    output.insert(key, value);
+                }
+                Err(e) => {
+                    errors.insert(key, e);
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(output)
+        } else {
+            output.values_mut().for_each(wipe);
+            Err(errors)
+        }
+    }
+
+    /// Convert unmasked values into Python objects, decoding the values declared as rationals into floats.
+    pub(crate) fn decode_values<'py>(
+        &self,
+        py: Python<'py>,
+        values: HashMap<String, NadaValue<Clear>>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let output = PyDict::new_bound(py);
+        for (key, value) in values {
+            let value = match self.rational_precisions.get(&key) {
+                Some(precision) => rational::decode_value(py, &value, *precision)
+                    .map_err(|e| PyValueError::new_err(format!("value '{key}': {e}")))?,
+                None => nada_value_clear_to_pyobject(py, value)?,
+            };
+            output.set_item(key, value)?;
+        }
+        Ok(output)
+    }
+
+    fn nada_type(&self, key: &str, nada_type: &T) -> NadaType {
+        NadaType { inner: nada_type.clone(), rational_precision: self.rational_precisions.get(key).copied() }
+    }
+}
+
+#[pymethods]
+impl NadaValuesSchema {
+    /// Returns a new [`NadaValuesSchema`].
+    #[new]
+    fn new(types: HashMap<String, NadaType>) -> Self {
+        let rational_precisions =
+            types.iter().filter_map(|(k, v)| v.rational_precision.map(|precision| (k.clone(), precision))).collect();
+        Self { types: types.into_iter().map(|(k, v)| (k, v.inner)).collect(), rational_precisions }
+    }
+
+    /// The types declared in this schema.
+    #[getter]
+    fn get_types(&self) -> HashMap<String, NadaType> {
+        self.types.iter().map(|(k, v)| (k.clone(), self.nada_type(k, v))).collect()
+    }
+
+    /// Validate the given values against this schema.
+    ///
+    /// Returns a dictionary with an error message for every value that can't be coerced into its declared
+    /// type. An empty dictionary means all values fit.
+    fn validate(&self, values: &Bound<'_, PyDict>) -> HashMap<String, String> {
+        self.coerce_values(values).err().unwrap_or_default()
+    }
+
+    /// Coerce the given values into value classes according to this schema.
+    fn coerce<'py>(&self, py: Python<'py>, values: &Bound<'py, PyDict>) -> PyResult<Bound<'py, PyDict>> {
+        let values = self.coerce_values(values).map_err(coercion_error)?;
+        nada_values_clear_to_pydict(py, values)
+    }
+
+    fn __repr__(&self) -> String {
+        let mut types =
+            self.types.iter().map(|(k, v)| format!("'{k}': {}", self.nada_type(k, v).__repr__())).collect::<Vec<_>>();
+        types.sort();
+        format!("NadaValuesSchema({{{}}})", types.join(", "))
+    }
+}
+
+/// Build the error raised when one or more values don't fit a schema.
+pub(crate) fn coercion_error(errors: HashMap<String, String>) -> PyErr {
+    let mut errors = errors.into_iter().map(|(k, e)| format!("'{k}': {e}")).collect::<Vec<_>>();
+    errors.sort();
+    PyValueError::new_err(format!("values do not match schema: {}", errors.join(", ")))
+}
+
+fn coerce_value(value: &Bound<'_, PyAny>, nada_type: &T) -> Result<NadaValue<Clear>, String> {
+    if let Ok(mut value) = pyany_to_nada_value_clear(value.clone()) {
+        let value_type = value.to_type();
+        return if &value_type == nada_type {
+            Ok(value)
+        } else {
+            wipe(&mut value);
+            Err(format!("expected {nada_type}, got {value_type}"))
+        };
+    }
+    let value = match nada_type {
+        T::Integer => NadaValue::new_integer(extract_integer::<BigInt>(value, nada_type)?),
+        T::SecretInteger => NadaValue::new_secret_integer(extract_integer::<BigInt>(value, nada_type)?),
+        T::UnsignedInteger => NadaValue::new_unsigned_integer(extract_integer::<BigUint>(value, nada_type)?),
+        T::SecretUnsignedInteger => {
+            NadaValue::new_secret_unsigned_integer(extract_integer::<BigUint>(value, nada_type)?)
+        }
+        T::Boolean => NadaValue::new_boolean(extract_boolean(value, nada_type)?),
+        T::SecretBoolean => NadaValue::new_secret_boolean(extract_boolean(value, nada_type)?),
+        T::SecretBlob => NadaValue::new_secret_blob(extract_bytes(value, nada_type)?),
+        T::EddsaMessage => NadaValue::new_eddsa_message(extract_bytes(value, nada_type)?),
+        T::EcdsaDigestMessage => NadaValue::new_ecdsa_digest_message(extract_byte_array(value, nada_type)?),
+        T::EcdsaPublicKey => NadaValue::new_ecdsa_public_key(extract_byte_array(value, nada_type)?),
+        T::EddsaPublicKey => NadaValue::new_eddsa_public_key(extract_byte_array(value, nada_type)?),
+        T::StoreId => NadaValue::new_store_id(extract_byte_array(value, nada_type)?),
+        T::EcdsaPrivateKey => {
+            let key = ThresholdPrivateKey::from_be_bytes(&extract_bytes(value, nada_type)?)
+                .map_err(|_| "invalid ecdsa private key".to_string())?;
+            NadaValue::new_ecdsa_private_key(key)
+        }
+        T::EddsaPrivateKey => {
+            let key = ThresholdPrivateKey::from_le_bytes(&extract_bytes(value, nada_type)?)
+                .map_err(|_| "invalid eddsa private key".to_string())?;
+            NadaValue::new_eddsa_private_key(key)
+        }
+        T::Array { inner_type, size } => {
+            let values = match NumericBuffer::read(value) {
+                Ok(buffer) => {
+                    let values = buffer.into_values(inner_type).map_err(|e| e.to_string())?;
+                    if values.len() != *size {
+                        return Err(format!("expected {size} elements, got {}", values.len()));
+                    }
+                    values
+                }
+                Err(_) => {
+                    let elements = extract_sequence(value, nada_type)?;
+                    if elements.len() != *size {
+                        return Err(format!("expected {size} elements, got {}", elements.len()));
+                    }
+                    elements
+                        .iter()
+                        .enumerate()
+                        .map(|(i, e)| coerce_value(e, inner_type).map_err(|e| format!("element {i}: {e}")))
+                        .collect::<Result<Vec<_>, _>>()?
+                }
+            };
+            NadaValue::new_array(*inner_type.clone(), values).map_err(|e| e.to_string())?
+        }
+        T::Tuple { left_type, right_type } => {
+            let elements = extract_sequence(value, nada_type)?;
+            let [left, right] = elements.as_slice() else {
+                return Err(format!("expected 2 elements, got {}", elements.len()));
+            };
+            let left = coerce_value(left, left_type).map_err(|e| format!("element 0: {e}"))?;
+            let right = coerce_value(right, right_type).map_err(|e| format!("element 1: {e}"))?;
+            NadaValue::new_tuple(left, right).map_err(|e| e.to_string())?
+        }
+        T::NTuple { types } => {
+            let elements = extract_sequence(value, nada_type)?;
+            if elements.len() != types.len() {
+                return Err(format!("expected {} elements, got {}", types.len(), elements.len()));
+            }
+            let values = elements
+                .iter()
+                .zip(types)
+                .enumerate()
+                .map(|(i, (e, t))| coerce_value(e, t).map_err(|e| format!("element {i}: {e}")))
+                .collect::<Result<Vec<_>, _>>()?;
+            NadaValue::new_n_tuple(values).map_err(|e| e.to_string())?
+        }
+        T::Object { types } => {
+            let fields = value.downcast::<PyDict>().map_err(|_| format!("expected dict for {nada_type}"))?;
+            if fields.len() != types.len() {
+                return Err(format!("expected {} fields, got {}", types.len(), fields.len()));
+            }
+            let mut values = IndexMap::new();
+            for (name, field_type) in types {
+                let field = fields
+                    .get_item(name)
+                    .map_err(|e| e.to_string())?
+                    .ok_or_else(|| format!("missing field '{name}'"))?;
+                let field = coerce_value(&field, field_type).map_err(|e| format!("field '{name}': {e}"))?;
+                values.insert(name.clone(), field);
+            }
+            NadaValue::new_object(values).map_err(|e| e.to_string())?
+        }
+        T::EcdsaSignature
+        | T::EddsaSignature
+        | T::ShamirShareInteger
+        | T::ShamirShareUnsignedInteger
+        | T::ShamirShareBoolean => {
+            return Err(format!("{nada_type} can only be provided as a value class"));
+        }
+    };
+    Ok(value)
+}
+
+fn extract_integer<'py, I: FromPyObject<'py>>(value: &Bound<'py, PyAny>, nada_type: &T) -> Result<I, String> {
+    if value.is_instance_of::<PyBool>() {
+        return Err(format!("expected int for {nada_type}, got bool"));
+    }
+    value.extract::<I>().map_err(|e| format!("invalid value for {nada_type}: {e}"))
+}
+
+fn extract_boolean(value: &Bound<'_, PyAny>, nada_type: &T) -> Result<bool, String> {
+    value.downcast::<PyBool>().map(|b| b.is_true()).map_err(|_| format!("expected bool for {nada_type}"))
+}
+
+fn extract_bytes(value: &Bound<'_, PyAny>, nada_type: &T) -> Result<Vec<u8>, String> {
+    if let Ok(bytes) = value.downcast::<PyBytes>() {
+        Ok(bytes.as_bytes().to_vec())
+    } else if let Ok(bytes) = value.downcast::<PyByteArray>() {
+        Ok(bytes.to_vec())
+    } else {
+        Err(format!("expected bytes or bytearray for {nada_type}"))
+    }
+}
+
+fn extract_byte_array<const N: usize>(value: &Bound<'_, PyAny>, nada_type: &T) -> Result<[u8; N], String> {
+    extract_bytes(value, nada_type)?.try_into().map_err(|_| format!("{nada_type} must be exactly {N} bytes long"))
+}
+
+fn extract_sequence<'py>(value: &Bound<'py, PyAny>, nada_type: &T) -> Result<Vec<Bound<'py, PyAny>>, String> {
+    value.extract::<Vec<Bound<'py, PyAny>>>().map_err(|_| format!("expected list or tuple for {nada_type}"))
+}
@@ -0,0 +1,67 @@
+//! Handling of secret values: redacted representations and wiping on drop.
+//!
+//! Value objects wipe their secrets when dropped, and so do the copies made while converting them into the values
+//! that are masked, encoded or inspected. Wiping stops at the upstream masker, which drops the cleartext values it
+//! masks and the shares it produces or unmasks without wiping them.
+
+use nillion_client_core::values::{BigInt, BigUint, Clear, NadaValue};
+use zeroize::Zeroize;
+
+/// The placeholder shown instead of a secret.
+pub(crate) const REDACTED: &str = "<redacted>";
+
+/// Returns a representation of a value where every secret is replaced by a placeholder.
+pub(crate) fn redacted_repr(value: &NadaValue<Clear>) -> String {
+    match value {
+        NadaValue::SecretInteger(_) => format!("SecretInteger({REDACTED})"),
+        NadaValue::SecretUnsignedInteger(_) => format!("SecretUnsignedInteger({REDACTED})"),
+        NadaValue::SecretBoolean(_) => format!("SecretBoolean({REDACTED})"),
+        NadaValue::SecretBlob(_) => format!("Blob({REDACTED})"),
+        NadaValue::EcdsaPrivateKey(_) => format!("EcdsaPrivateKey({REDACTED})"),
+        NadaValue::EddsaPrivateKey(_) => format!("EddsaPrivateKey({REDACTED})"),
+        NadaValue::Array { values, .. } => format!("Array({})", join(values.iter())),
+        NadaValue::Tuple { left, right } => format!("Tuple({}, {})", redacted_repr(left), redacted_repr(right)),
+        NadaValue::NTuple { values } => format!("NTuple({})", join(values.iter())),
+        NadaValue::Object { values } => {
+            let fields = values.iter().map(|(name, value)| format!("{name:?}: {}", redacted_repr(value)));
+            format!("Object({{{}}})", fields.collect::<Vec<_>>().join(", "))
+        }
+        value => value.to_string(),
+    }
+}
+
+fn join<'a>(values: impl Iterator<Item = &'a NadaValue<Clear>>) -> String {
+    values.map(redacted_repr).collect::<Vec<_>>().join(", ")
+}
+
+/// Overwrites the memory holding the secrets in a value.
+///
+/// Private keys are not handled here as their scalars are already wiped when dropped.
+pub(crate) fn wipe(value: &mut NadaValue<Clear>) {
+    match value {
+        NadaValue::SecretInteger(value) => wipe_signed(value),
+        NadaValue::SecretUnsignedInteger(value) => wipe_unsigned(value),
+        NadaValue::SecretBoolean(value) => value.zeroize(),
+        NadaValue::SecretBlob(value) => value.zeroize(),
+        NadaValue::Array { values, .. } | NadaValue::NTuple { values } => values.iter_mut().for_each(wipe),
+        NadaValue::Tuple { left, right } => {
+            wipe(left);
+            wipe(right);
+        }
+        NadaValue::Object { values } => values.values_mut().for_each(wipe),
+        _ => (),
+    }
+}
+
+/// Clears an integer's bits in place. Bits are cleared from the lowest one up so that its digits are all zero by the
+/// time the integer is normalized and its storage is released.
+pub(crate) fn wipe_unsigned(value: &mut BigUint) {
+    for bit in 0..value.bits() {
+        value.set_bit(bit, false);
+    }
+}
+
+fn wipe_signed(value: &mut BigInt) {
+    let (_, mut magnitude) = std::mem::take(value).into_parts();
+    wipe_unsigned(&mut magnitude);
+}
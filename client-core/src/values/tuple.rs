@@ -0,0 +1,242 @@
+//! Python bindings for tuples and utilities.
+
+use crate::values::{
+    nada_value_clear_to_pyobject, pyany_to_nada_value_clear, reduce_nada_value,
+    secret::{redacted_repr, wipe},
+};
+use nillion_client_core::values::{Clear, NadaValue};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyBytes};
+
+/// This is a :py:class:`Tuple` class used to
+/// encode a pair of elements.
+///
+/// Arguments
+/// ---------
+/// left : NadaValue
+///     The left element of the tuple.
+/// right : NadaValue
+///     The right element of the tuple.
+///
+/// Returns
+/// -------
+/// Tuple
+///     Instance of the :py:class:`Tuple` class.
+///
+/// Example
+/// -------
+///
+/// .. code-block:: py3
+///
+///     from nillion_client import Tuple, SecretInteger, Integer
+///
+///     tuple = Tuple(SecretInteger(1), Integer(2))
+///
+///     print("The left element is: ", tuple.left)
+///
+/// .. code-block:: text
+///
+///     >>> The left element is: SecretInteger(1)
+#[pyclass(eq)]
+#[derive(PartialEq, Clone)]
+pub struct Tuple {
+    pub(crate) inner: NadaValue<Clear>,
+}
+
+impl TryFrom<NadaValue<Clear>> for Tuple {
+    type Error = PyErr;
+
+    fn try_from(value: NadaValue<Clear>) -> Result<Self, Self::Error> {
+        matches!(value, NadaValue::Tuple { .. })
+            .then(|| Tuple { inner: value })
+            .ok_or_else(|| PyValueError::new_err("expected tuple"))
+    }
+}
+
+impl Drop for Tuple {
+    fn drop(&mut self) {
+        wipe(&mut self.inner);
+    }
+}
+
+impl Tuple {
+    fn elements(&self) -> PyResult<(&NadaValue<Clear>, &NadaValue<Clear>)> {
+        match &self.inner {
+            NadaValue::Tuple { left, right } => Ok((left, right)),
+            _ => Err(PyValueError::new_err("expected tuple")),
+        }
+    }
+}
+
+#[pymethods]
+impl Tuple {
+    /// Returns a new [`Tuple`].
+    #[new]
+    fn new(left: PyObject, right: PyObject, py: Python) -> PyResult<Self> {
+        let left = pyany_to_nada_value_clear(left.into_bound(py))?;
+        let right = pyany_to_nada_value_clear(right.into_bound(py))?;
+        Ok(Tuple { inner: NadaValue::new_tuple(left, right).map_err(|e| PyValueError::new_err(e.to_string()))? })
+    }
+
+    fn __len__(&self) -> usize {
+        2
+    }
+
+    /// Getter method for the left element inside a
+    /// :py:class:`Tuple` instance.
+    #[getter]
+    fn get_left(&self, py: Python<'_>) -> PyResult<PyObject> {
+        nada_value_clear_to_pyobject(py, self.elements()?.0.clone())
+    }
+
+    /// Getter method for the right element inside a
+    /// :py:class:`Tuple` instance.
+    #[getter]
+    fn get_right(&self, py: Python<'_>) -> PyResult<PyObject> {
+        nada_value_clear_to_pyobject(py, self.elements()?.1.clone())
+    }
+
+    /// Getter method for the `value` inside a
+    /// :py:class:`Tuple` instance.
+    ///
+    /// Returns
+    /// -------
+    /// tuple
+    ///     The pair of elements.
+    ///
+    /// Example
+    /// -------
+    ///
+    /// .. code-block:: py3
+    ///
+    ///     print("My tuple: \n", tuple.value)
+    ///
+    /// .. code-block:: text
+    ///
+    ///     >>> My tuple:
+    ///     >>>  (SecretInteger(1), Integer(2))
+    #[getter]
+    fn get_value(&self, py: Python<'_>) -> PyResult<(PyObject, PyObject)> {
+        let (left, right) = self.elements()?;
+        Ok((nada_value_clear_to_pyobject(py, left.clone())?, nada_value_clear_to_pyobject(py, right.clone())?))
+    }
+
+    fn __repr__(&self) -> String {
+        redacted_repr(&self.inner)
+    }
+
+    /// Returns the representation of this tuple including its secrets.
+    ///
+    /// The default representation redacts secrets so that they don't end up in logs or tracebacks.
+    fn reveal(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+        reduce_nada_value(py, &self.inner)
+    }
+}
+
+/// This is a :py:class:`NTuple` class used to
+/// encode a fixed-size sequence of elements of possibly different types.
+///
+/// Note: `__len__` method is implemented to allow
+/// getting the length of the tuple.
+///
+/// Arguments
+/// ---------
+/// value : list
+///     List of encoded elements.
+///
+/// Returns
+/// -------
+/// NTuple
+///     Instance of the :py:class:`NTuple` class.
+///
+/// Example
+/// -------
+///
+/// .. code-block:: py3
+///
+///     from nillion_client import NTuple, SecretInteger, Boolean
+///
+///     ntuple = NTuple([SecretInteger(1), Boolean(True), SecretInteger(3)])
+///
+///     print("The length of the tuple is: ", len(ntuple))
+///
+/// .. code-block:: text
+///
+///     >>> The length of the tuple is: 3
+#[pyclass(eq)]
+#[derive(PartialEq, Clone)]
+pub struct NTuple {
+    pub(crate) inner: NadaValue<Clear>,
+}
+
+impl TryFrom<NadaValue<Clear>> for NTuple {
+    type Error = PyErr;
+
+    fn try_from(value: NadaValue<Clear>) -> Result<Self, Self::Error> {
+        matches!(value, NadaValue::NTuple { .. })
+            .then(|| NTuple { inner: value })
+            .ok_or_else(|| PyValueError::new_err("expected ntuple"))
+    }
+}
+
+impl Drop for NTuple {
+    fn drop(&mut self) {
+        wipe(&mut self.inner);
+    }
+}
+
+impl NTuple {
+    fn elements(&self) -> PyResult<&Vec<NadaValue<Clear>>> {
+        match &self.inner {
+            NadaValue::NTuple { values } => Ok(values),
+            _ => Err(PyValueError::new_err("expected ntuple")),
+        }
+    }
+}
+
+#[pymethods]
+impl NTuple {
+    /// Returns a new [`NTuple`].
+    #[new]
+    fn new(value: Vec<PyObject>, py: Python) -> PyResult<Self> {
+        let values = value
+            .into_iter()
+            .map(|e| pyany_to_nada_value_clear(e.into_bound(py)))
+            .collect::<Result<Vec<_>, PyErr>>()?;
+        Ok(NTuple { inner: NadaValue::new_n_tuple(values).map_err(|e| PyValueError::new_err(e.to_string()))? })
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        Ok(self.elements()?.len())
+    }
+
+    /// Getter method for the `value` inside a
+    /// :py:class:`NTuple` instance.
+    ///
+    /// Returns
+    /// -------
+    /// list
+    ///     List of encoded elements.
+    #[getter]
+    fn get_value(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        self.elements()?.iter().cloned().map(|v| nada_value_clear_to_pyobject(py, v)).collect::<Result<Vec<_>, _>>()
+    }
+
+    fn __repr__(&self) -> String {
+        redacted_repr(&self.inner)
+    }
+
+    /// Returns the representation of this tuple including its secrets.
+    ///
+    /// The default representation redacts secrets so that they don't end up in logs or tracebacks.
+    fn reveal(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+        reduce_nada_value(py, &self.inner)
+    }
+}
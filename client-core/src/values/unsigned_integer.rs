@@ -1,5 +1,14 @@
-use nillion_client_core::values::{BigUint, Clear, NadaValue};
-use pyo3::{exceptions::PyValueError, prelude::*};
+use crate::{
+    ranges::prime_bits,
+    values::{
+        arithmetic::{
+            apply, checked, chosen_modulo, compare, extract_int, hash_value, narrowest, Operation, Signedness,
+        },
+        secret::{redacted_repr, wipe},
+    },
+};
+use nillion_client_core::values::{BigInt, BigUint, Clear, EncodedModulo, NadaValue};
+use pyo3::{exceptions::PyValueError, prelude::*, pyclass::CompareOp};
 
 /// This is a :py:class:`SecretUnsignedInteger` class used to
 /// encode a secret as an unsigned integer.
@@ -8,6 +17,10 @@ use pyo3::{exceptions::PyValueError, prelude::*};
 /// ---------
 /// value : int
 ///     Value of the secret encoded element.
+/// prime_bits : int, optional
+///     The size in bits of the safe prime (64, 128 or 256) that the value and the results of
+///     arithmetic on it are range-checked against. Defaults to checking arithmetic results
+///     against the 256 bit safe prime.
 ///
 /// Returns
 /// -------
@@ -34,10 +47,18 @@ use pyo3::{exceptions::PyValueError, prelude::*};
 /// .. code-block:: text
 ///
 ///     >>> Are the secret unsigned integers the same?  False
-#[pyclass(eq)]
+///
+/// Secret unsigned integers are ordered and support ``+``, ``-``, ``*``, ``//`` and
+/// ``%`` with other secret unsigned integers, public unsigned integers and plain ``int`` values.
+/// Results are secret unsigned integers, checked against the smallest prime chosen by the operands.
+/// Masking checks values against the masker's own prime (see :py:meth:`SecretMasker.validate_ranges`).
+///
+/// Values are immutable and hashable, so they can be used in sets and as dict keys.
+#[pyclass(frozen)]
 #[derive(PartialEq, Clone)]
 pub struct SecretUnsignedInteger {
     pub(crate) inner: NadaValue<Clear>,
+    pub(crate) modulo: Option<EncodedModulo>,
 }
 
 impl TryFrom<NadaValue<Clear>> for SecretUnsignedInteger {
@@ -46,17 +67,54 @@ impl TryFrom<NadaValue<Clear>> for SecretUnsignedInteger {
     fn try_from(value: NadaValue<Clear>) -> Result<Self, Self::Error> {
         value
             .is_secret_unsigned_integer()
-            .then(|| SecretUnsignedInteger { inner: value })
+            .then(|| SecretUnsignedInteger { inner: value, modulo: None })
             .ok_or_else(|| PyValueError::new_err("expected secret unsigned integer"))
     }
 }
 
+impl Drop for SecretUnsignedInteger {
+    fn drop(&mut self) {
+        wipe(&mut self.inner);
+    }
+}
+
+impl SecretUnsignedInteger {
+    /// Extracts the value of an operand that can be combined with a secret unsigned integer.
+    fn operand(other: &Bound<'_, PyAny>) -> Option<(BigInt, Option<EncodedModulo>)> {
+        if let Ok(other) = other.extract::<SecretUnsignedInteger>() {
+            return other.get_value().ok().map(|value| (value.into(), other.modulo));
+        }
+        if let Ok(other) = other.extract::<UnsignedInteger>() {
+            return other.get_value().ok().map(|value| (value.into(), other.modulo));
+        }
+        extract_int(other).map(|value| (value, None))
+    }
+
+    fn binary_op(&self, other: &Bound<'_, PyAny>, operation: Operation, reflected: bool) -> PyResult<PyObject> {
+        let py = other.py();
+        let Some((other, other_modulo)) = Self::operand(other) else {
+            return Ok(py.NotImplemented());
+        };
+        let value = BigInt::from(self.get_value()?);
+        let modulo = narrowest(self.modulo, other_modulo);
+        let (left, right) = if reflected { (other, value) } else { (value, other) };
+        let result = checked(apply(operation, &left, &right)?, Signedness::Unsigned, modulo)?;
+        let result = result.to_biguint().ok_or_else(|| PyValueError::new_err("expected unsigned result"))?;
+        Ok(SecretUnsignedInteger { inner: NadaValue::new_secret_unsigned_integer(result), modulo }.into_py(py))
+    }
+}
+
 #[pymethods]
 impl SecretUnsignedInteger {
     /// Returns a new SecretUnsignedInteger.
     #[new]
-    fn new(value: BigUint) -> SecretUnsignedInteger {
-        SecretUnsignedInteger { inner: NadaValue::new_secret_unsigned_integer(value) }
+    #[pyo3(signature = (value, prime_bits=None))]
+    fn new(value: BigUint, prime_bits: Option<u32>) -> PyResult<SecretUnsignedInteger> {
+        let modulo = chosen_modulo(prime_bits)?;
+        if modulo.is_some() {
+            checked(value.clone().into(), Signedness::Unsigned, modulo)?;
+        }
+        Ok(SecretUnsignedInteger { inner: NadaValue::new_secret_unsigned_integer(value), modulo })
     }
 
     #[getter]
@@ -69,14 +127,89 @@ impl SecretUnsignedInteger {
             .into())
     }
 
-    #[setter]
-    fn set_value(&mut self, value: BigUint) {
-        self.inner = NadaValue::new_secret_unsigned_integer(value);
+    /// The size in bits of the safe prime chosen for this value, if any.
+    #[getter]
+    fn get_prime_bits(&self) -> Option<u32> {
+        self.modulo.map(prime_bits)
     }
 
     fn __repr__(&self) -> String {
+        redacted_repr(&self.inner)
+    }
+
+    /// Returns the representation of this secret unsigned integer including its secrets.
+    ///
+    /// The default representation redacts secrets so that they don't end up in logs or tracebacks.
+    fn reveal(&self) -> String {
         self.inner.to_string()
     }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (BigUint, Option<u32>))> {
+        Ok((
+            py.get_type_bound::<SecretUnsignedInteger>().into_any().unbind(),
+            (self.get_value()?, self.get_prime_bits()),
+        ))
+    }
+
+    fn __add__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Add, false)
+    }
+
+    fn __radd__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Add, true)
+    }
+
+    fn __sub__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Sub, false)
+    }
+
+    fn __rsub__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Sub, true)
+    }
+
+    fn __mul__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mul, false)
+    }
+
+    fn __rmul__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mul, true)
+    }
+
+    fn __floordiv__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::FloorDiv, false)
+    }
+
+    fn __rfloordiv__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::FloorDiv, true)
+    }
+
+    fn __mod__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mod, false)
+    }
+
+    fn __rmod__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mod, true)
+    }
+
+    fn __int__(&self) -> PyResult<BigUint> {
+        self.get_value()
+    }
+
+    fn __index__(&self) -> PyResult<BigUint> {
+        self.get_value()
+    }
+
+    fn __hash__(&self) -> PyResult<u64> {
+        Ok(hash_value("SecretUnsignedInteger", &self.get_value()?))
+    }
+
+    fn __richcmp__(&self, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<PyObject> {
+        let py = other.py();
+        let Ok(other) = other.extract::<SecretUnsignedInteger>() else {
+            return Ok(py.NotImplemented());
+        };
+        Ok(compare(&self.get_value()?, &other.get_value()?, op).into_py(py))
+    }
 }
 
 /// This is a :py:class:`UnsignedInteger` class used to
@@ -86,6 +219,10 @@ impl SecretUnsignedInteger {
 /// ---------
 /// value : int
 ///     Value of the public encoded element.
+/// prime_bits : int, optional
+///     The size in bits of the safe prime (64, 128 or 256) that the value and the results of
+///     arithmetic on it are range-checked against. Defaults to checking arithmetic results
+///     against the 256 bit safe prime.
 ///
 /// Returns
 /// -------
@@ -112,10 +249,17 @@ impl SecretUnsignedInteger {
 /// .. code-block:: text
 ///
 ///     >>> Are the public unsigned integers the same?  False
-#[pyclass(eq)]
+///
+/// Public unsigned integers are ordered and support ``+``, ``-``, ``*``, ``//`` and
+/// ``%`` with other public unsigned integers and plain ``int`` values. Combining a public
+/// unsigned integer with a secret unsigned integer results in a secret unsigned integer.
+///
+/// Values are immutable and hashable, so they can be used in sets and as dict keys.
+#[pyclass(frozen)]
 #[derive(PartialEq, Clone)]
 pub struct UnsignedInteger {
     pub(crate) inner: NadaValue<Clear>,
+    pub(crate) modulo: Option<EncodedModulo>,
 }
 
 impl TryFrom<NadaValue<Clear>> for UnsignedInteger {
@@ -124,17 +268,45 @@ impl TryFrom<NadaValue<Clear>> for UnsignedInteger {
     fn try_from(value: NadaValue<Clear>) -> Result<Self, Self::Error> {
         value
             .is_unsigned_integer()
-            .then(|| UnsignedInteger { inner: value })
+            .then(|| UnsignedInteger { inner: value, modulo: None })
             .ok_or_else(|| PyValueError::new_err("expected unsigned integer"))
     }
 }
 
+impl UnsignedInteger {
+    /// Extracts the value of an operand that can be combined with a public unsigned integer.
+    fn operand(other: &Bound<'_, PyAny>) -> Option<(BigInt, Option<EncodedModulo>)> {
+        if let Ok(other) = other.extract::<UnsignedInteger>() {
+            return other.get_value().ok().map(|value| (value.into(), other.modulo));
+        }
+        extract_int(other).map(|value| (value, None))
+    }
+
+    fn binary_op(&self, other: &Bound<'_, PyAny>, operation: Operation, reflected: bool) -> PyResult<PyObject> {
+        let py = other.py();
+        let Some((other, other_modulo)) = Self::operand(other) else {
+            return Ok(py.NotImplemented());
+        };
+        let value = BigInt::from(self.get_value()?);
+        let modulo = narrowest(self.modulo, other_modulo);
+        let (left, right) = if reflected { (other, value) } else { (value, other) };
+        let result = checked(apply(operation, &left, &right)?, Signedness::Unsigned, modulo)?;
+        let result = result.to_biguint().ok_or_else(|| PyValueError::new_err("expected unsigned result"))?;
+        Ok(UnsignedInteger { inner: NadaValue::new_unsigned_integer(result), modulo }.into_py(py))
+    }
+}
+
 #[pymethods]
 impl UnsignedInteger {
     /// Returns a new UnsignedInteger.
     #[new]
-    fn new(value: BigUint) -> UnsignedInteger {
-        UnsignedInteger { inner: NadaValue::new_unsigned_integer(value) }
+    #[pyo3(signature = (value, prime_bits=None))]
+    fn new(value: BigUint, prime_bits: Option<u32>) -> PyResult<UnsignedInteger> {
+        let modulo = chosen_modulo(prime_bits)?;
+        if modulo.is_some() {
+            checked(value.clone().into(), Signedness::Unsigned, modulo)?;
+        }
+        Ok(UnsignedInteger { inner: NadaValue::new_unsigned_integer(value), modulo })
     }
 
     #[getter]
@@ -147,12 +319,77 @@ impl UnsignedInteger {
             .into())
     }
 
-    #[setter]
-    fn set_value(&mut self, value: BigUint) {
-        self.inner = NadaValue::new_unsigned_integer(value);
+    /// The size in bits of the safe prime chosen for this value, if any.
+    #[getter]
+    fn get_prime_bits(&self) -> Option<u32> {
+        self.modulo.map(prime_bits)
     }
 
     fn __repr__(&self) -> String {
         self.inner.to_string()
     }
+
+    fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (BigUint, Option<u32>))> {
+        Ok((py.get_type_bound::<UnsignedInteger>().into_any().unbind(), (self.get_value()?, self.get_prime_bits())))
+    }
+
+    fn __add__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Add, false)
+    }
+
+    fn __radd__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Add, true)
+    }
+
+    fn __sub__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Sub, false)
+    }
+
+    fn __rsub__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Sub, true)
+    }
+
+    fn __mul__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mul, false)
+    }
+
+    fn __rmul__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mul, true)
+    }
+
+    fn __floordiv__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::FloorDiv, false)
+    }
+
+    fn __rfloordiv__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::FloorDiv, true)
+    }
+
+    fn __mod__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mod, false)
+    }
+
+    fn __rmod__(&self, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.binary_op(other, Operation::Mod, true)
+    }
+
+    fn __int__(&self) -> PyResult<BigUint> {
+        self.get_value()
+    }
+
+    fn __index__(&self) -> PyResult<BigUint> {
+        self.get_value()
+    }
+
+    fn __hash__(&self) -> PyResult<u64> {
+        Ok(hash_value("UnsignedInteger", &self.get_value()?))
+    }
+
+    fn __richcmp__(&self, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<PyObject> {
+        let py = other.py();
+        let Ok(other) = other.extract::<UnsignedInteger>() else {
+            return Ok(py.NotImplemented());
+        };
+        Ok(compare(&self.get_value()?, &other.get_value()?, op).into_py(py))
+    }
 }
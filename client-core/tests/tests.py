@@ -64,11 +64,16 @@ def test_asdf():
     assert unmasked["array"].value == values["array"].value
 
     assert repr(unmasked["integer"]) == "Integer(-1)"
-    assert repr(unmasked["secretInteger"]) == "SecretInteger(-1)"
+    assert repr(unmasked["secretInteger"]) == "SecretInteger(<redacted>)"
+    assert unmasked["secretInteger"].reveal() == "SecretInteger(-1)"
     assert repr(unmasked["unsignedInteger"]) == "UnsignedInteger(1)"
-    assert repr(unmasked["secretUnsignedInteger"]) == "SecretUnsignedInteger(1)"
+    assert repr(unmasked["secretUnsignedInteger"]) == "SecretUnsignedInteger(<redacted>)"
+    assert unmasked["secretUnsignedInteger"].reveal() == "SecretUnsignedInteger(1)"
     assert repr(unmasked["boolean"]) == "Boolean(true)"
-    assert repr(unmasked["secretBoolean"]) == "SecretBoolean(false)"
-    assert repr(unmasked["secretBlob"]) == "Blob(1, 2, 3)"
-    assert repr(unmasked["EcdsaPrivateKey"]) == "EcdsaPrivateKey(NonZero(SecretScalar))"
+    assert repr(unmasked["secretBoolean"]) == "SecretBoolean(<redacted>)"
+    assert unmasked["secretBoolean"].reveal() == "SecretBoolean(false)"
+    assert repr(unmasked["secretBlob"]) == "Blob(<redacted>)"
+    assert unmasked["secretBlob"].reveal() == "Blob(1, 2, 3)"
+    assert repr(unmasked["EcdsaPrivateKey"]) == "EcdsaPrivateKey(<redacted>)"
+    assert unmasked["EcdsaPrivateKey"].reveal() == "EcdsaPrivateKey(NonZero(SecretScalar))"
     assert repr(unmasked["array"]) == "Array(Integer(-1), Integer(-1), Integer(-1))"
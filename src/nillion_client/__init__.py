@@ -18,6 +18,7 @@ from nillion_client_core import (
     Boolean,
     SecretBoolean,
     SecretBlob,
+    BlobIntegrityError,
     EcdsaPrivateKey,
     EcdsaDigestMessage,
     EcdsaSignature,
@@ -27,6 +28,13 @@ from nillion_client_core import (
     EddsaPublicKey,
     EddsaSignature,
     EddsaMessage,
+    Tuple,
+    NTuple,
+    Object,
+    SecretRational,
+    Rational,
+    NadaType,
+    NadaValuesSchema,
 )
 from nillion_client_proto.nillion.preprocessing.v1.element import PreprocessingElement
 from cosmpy.crypto.keypairs import PrivateKey as NilChainPrivateKey
@@ -56,6 +64,7 @@ __all__ = [
     "Boolean",
     "SecretBoolean",
     "SecretBlob",
+    "BlobIntegrityError",
     "EcdsaPrivateKey",
     "EcdsaDigestMessage",
     "EcdsaSignature",
@@ -65,6 +74,13 @@ __all__ = [
     "EddsaPublicKey",
     "EddsaSignature",
     "EddsaMessage",
+    "Tuple",
+    "NTuple",
+    "Object",
+    "SecretRational",
+    "Rational",
+    "NadaType",
+    "NadaValuesSchema",
     "PreprocessingElement",
     "PrivateKey",
     "NilChainPrivateKey",
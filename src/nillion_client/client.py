@@ -27,7 +27,6 @@ from nillion_client_proto.nillion.leader_queries.v1 import LeaderQueriesStub
 from nillion_client_proto.nillion.membership.v1.cluster import (
     Cluster,
     ClusterMember,
-    Prime,
 )
 from nillion_client_proto.nillion.membership.v1 import MembershipStub
 from nillion_client_proto.nillion.payments.v1.quote import (
@@ -77,7 +76,7 @@ from nillion_client_core import (
 
 from nillion_client.values import (
     encrypted_nada_value_from_protobuf,
-    encrypted_nada_values_to_protobuf,
+    request_with_encrypted_values,
 )
 from .errors import PartyError
 
@@ -335,19 +334,9 @@ class VmClient:
         bootnode_channel.close()
 
         # Create a secret masker with a prime that corresponds to the cluster's.
-        match self.cluster.prime:
-            case Prime.SAFE_64_BITS:
-                self.secret_masker = SecretMasker.new_64_bit_safe_prime(
-                    self.cluster.polynomial_degree, parties
-                )
-            case Prime.SAFE_128_BITS:
-                self.secret_masker = SecretMasker.new_128_bit_safe_prime(
-                    self.cluster.polynomial_degree, parties
-                )
-            case Prime.SAFE_256_BITS:
-                self.secret_masker = SecretMasker.new_256_bit_safe_prime(
-                    self.cluster.polynomial_degree, parties
-                )
+        self.secret_masker = SecretMasker.from_prime(
+            self.cluster.prime.name, self.cluster.polynomial_degree, parties
+        )
 
     @classmethod
     async def create(
@@ -920,11 +909,10 @@ class VmClient:
 
         response_futs = []
         for member in self._members:
-            values = encrypted_values[member.party_id]
-            encoded_values = encrypted_nada_values_to_protobuf(values)
-            request = StoreValuesRequest(
+            request = request_with_encrypted_values(
+                StoreValuesRequest,
+                encrypted_values[member.party_id],
                 signed_receipt=receipt,
-                values=encoded_values,
                 permissions=permissions.to_proto(),
                 update_identifier=update_identifier.bytes if update_identifier else b"",
             )
@@ -1280,10 +1268,11 @@ class VmClient:
         response_futs = []
         for member in self._members:
             encrypted_values = encrypted_party_values[member.party_id]
-            request = InvokeComputeRequest(
+            request = request_with_encrypted_values(
+                InvokeComputeRequest,
+                encrypted_values,
                 signed_receipt=receipt,
                 value_ids=[values_id.bytes for values_id in value_ids],
-                values=encrypted_nada_values_to_protobuf(encrypted_values),
                 input_bindings=[binding.to_proto() for binding in input_bindings],
                 output_bindings=[binding.to_proto() for binding in output_bindings],
             )
@@ -1440,8 +1429,4 @@ class VmClient:
     def _compute_values_size(
         self, encrypted_values: Mapping[PartyId, Mapping[str, EncryptedNadaValue]]
     ) -> int:
-        first = next(iter(encrypted_values.values()))
-        proto_values = encrypted_nada_values_to_protobuf(first)
-        return sum(
-            [len(p.name) + len(p.value.SerializeToString()) for p in proto_values]
-        )
+        return self.secret_masker.encoded_payload_size(encrypted_values)
@@ -0,0 +1,43 @@
+from nillion_client_core import PartyId, SecretInteger, SecretMasker, SecretUnsignedInteger
+from nillion_client_proto.nillion.compute.v1.invoke import InvokeComputeRequest
+from nillion_client_proto.nillion.values.v1.store import StoreValuesRequest
+
+from nillion_client.values import (
+    encrypted_nada_value_to_protobuf,
+    request_with_encrypted_values,
+)
+
+
+def encrypted_values():
+    parties = [PartyId.from_bytes(bytes([i])) for i in range(3)]
+    masker = SecretMasker.new_64_bit_safe_prime(1, parties)
+    masked = masker.mask({"a": SecretInteger(-5), "b": SecretUnsignedInteger(7)})
+    return masked[parties[0]]
+
+
+def test_store_request_round_trips_encoded_values():
+    values = encrypted_values()
+    request = request_with_encrypted_values(
+        StoreValuesRequest, values, update_identifier=b"update"
+    )
+    parsed = StoreValuesRequest().parse(bytes(request))
+    assert parsed.update_identifier == b"update"
+    assert {named.name: named.value for named in parsed.values} == {
+        name: encrypted_nada_value_to_protobuf(value) for name, value in values.items()
+    }
+
+
+def test_compute_request_round_trips_encoded_values():
+    values = encrypted_values()
+    request = request_with_encrypted_values(
+        InvokeComputeRequest, values, value_ids=[b"id"]
+    )
+    parsed = InvokeComputeRequest().parse(bytes(request))
+    assert parsed.value_ids == [b"id"]
+    assert sorted(named.name for named in parsed.values) == ["a", "b"]
+
+
+def test_encoded_values_are_not_fields():
+    request = request_with_encrypted_values(StoreValuesRequest, encrypted_values())
+    assert request.values == []
+    assert len(StoreValuesRequest().parse(bytes(request)).values) == 2